@@ -10,12 +10,32 @@ mod systems;
 mod technical;
 use bevy::prelude::*;
 use bevy::winit::WinitSettings;
-use menu::main_menu;
-use systems::{grid_systems::flex_grid, layout::plugin::CharacterCreationPlugin};
+use menu::{encounter_builder::EncounterBuilderPlugin, main_menu};
+use systems::{
+    battle::plugin::BattlePlugin,
+    game::{
+        character::{GroundSpeed, SizeCategory},
+        equipment::{WieldedWeapon, WornArmor},
+        experience::Experience,
+        proficiency::Proficiencies,
+        race::CharacterBuilder,
+    },
+    grid_systems::{
+        flex_grid,
+        grid_position::GridPos,
+        token::{Faction, TokenBundle},
+    },
+    layout::plugin::CharacterCreationPlugin,
+};
 use technical::alternate_traits::MyAltTraitAssetPlugin;
 use technical::archetype::MyArchetypeAssetPlugin;
+use technical::character_traits::MyTraitCatalogAssetPlugin;
 use technical::class::MyClassAssetPlugin;
+use technical::creature::MyCreatureAssetPlugin;
 use technical::default_race_traits::MyDefaultTraitAssetPlugin;
+use technical::deity::MyDeityAssetPlugin;
+use technical::encounter::MyEncounterAssetPlugin;
+use technical::equipment::MyShopCatalogAssetPlugin;
 use technical::favored_class::MyFavoredClassAssetPlugin;
 use technical::race_load::MyRaceAssetPlugin;
 // #[cfg(feature = "debug")]
@@ -52,9 +72,16 @@ fn main() {
         .add_plugins(MyFavoredClassAssetPlugin)
         .add_plugins(MyClassAssetPlugin)
         .add_plugins(MyArchetypeAssetPlugin)
+        .add_plugins(MyCreatureAssetPlugin)
+        .add_plugins(MyEncounterAssetPlugin)
+        .add_plugins(MyDeityAssetPlugin)
+        .add_plugins(MyTraitCatalogAssetPlugin)
+        .add_plugins(MyShopCatalogAssetPlugin)
         .add_systems(PreStartup, load_ascii)
         .add_state::<AppState>()
         .add_plugins(CharacterCreationPlugin)
+        .add_plugins(BattlePlugin)
+        .add_plugins(EncounterBuilderPlugin)
         .add_systems(Startup, my_camera::my_camera_systems::setup)
         // .add_system(my_camera::my_camera_systems::setup.in_schedule(OnEnter(AppState::Battle)))
         .add_systems(OnEnter(AppState::MainMenu), main_menu::setup_main_menu)
@@ -63,7 +90,10 @@ fn main() {
             main_menu::button_system.run_if(in_state(AppState::MainMenu)),
         )
         .add_systems(OnExit(AppState::MainMenu), main_menu::main_menu_cleanup)
-        .add_systems(OnEnter(AppState::Battle), flex_grid::setup_flex_grid);
+        .add_systems(
+            OnEnter(AppState::Battle),
+            (flex_grid::setup_flex_grid, spawn_player),
+        );
     // .add_system(systems::interface::mouse::mouse_scroll);
     // .add_startup_system(new_setup_asset_example)
     // .add_system(new_print_on_load);
@@ -123,26 +153,45 @@ fn main() {
 fn check_state(state: Res<State<AppState>>) {
     println!("State: {:?}", *state.get());
 }
-fn spawn_player(mut commands: Commands, ascii: Res<AsciiSheet>) {
-    let mut sprite = TextureAtlasSprite::new(1);
-    sprite.color = Color::rgb(0.3, 0.3, 0.9);
-    sprite.custom_size = Some(Vec2::splat(50.));
+type PlayerBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Proficiencies>,
+        Option<&'static WieldedWeapon>,
+        Option<&'static WornArmor>,
+    ),
+    With<CharacterBuilder>,
+>;
 
-    commands
-        .spawn(SpriteSheetBundle {
-            sprite,
-            texture_atlas: ascii.0.clone(),
-            transform: Transform {
-                translation: Vec3::new(0., 0., 900.),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(Name::new("Player"));
+// Spawns the player's token, carrying over the proficiencies and gear picked
+// on the character builder.
+fn spawn_player(query_builder: PlayerBuilderQuery, mut commands: Commands, ascii: Res<AsciiSheet>) {
+    let mut player = commands.spawn(TokenBundle::new(
+        &ascii,
+        1,
+        Color::rgb(0.3, 0.3, 0.9),
+        GridPos::new(0, 0),
+        SizeCategory::Medium,
+        Faction::Party,
+        GroundSpeed(30.),
+    ));
+    player.insert((Name::new("Player"), Experience::default()));
+    if let Ok((proficiencies, weapon, armor)) = query_builder.get_single() {
+        if let Some(proficiencies) = proficiencies {
+            player.insert(proficiencies.clone());
+        }
+        if let Some(weapon) = weapon {
+            player.insert(*weapon);
+        }
+        if let Some(armor) = armor {
+            player.insert(armor.clone());
+        }
+    }
 }
 
 #[derive(Resource)]
-struct AsciiSheet(Handle<TextureAtlas>);
+pub struct AsciiSheet(pub Handle<TextureAtlas>);
 
 fn load_ascii(
     mut commands: Commands,
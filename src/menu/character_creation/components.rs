@@ -1,10 +1,18 @@
 use crate::{
-    menu::components::SelectedWrapper,
+    menu::components::{SelectedWrapper, StagesOfCreationButton},
     systems::game::{
         archetype::ArchetypeName,
-        character::{AbilityScore, PlayableRace},
+        character::{AbilityScore, Alignment, CombatManeuverName, Language, PlayableRace},
+        character_traits::CharacterTrait,
         class::PlayableClass,
+        deity::Domain,
+        equipment::{Item, ItemCategory},
+        favored_class::{FavoredClassChoice, FavoredClasses},
+        proficiency::ProficiencyFeat,
         race::RacialTraitName,
+        random_character::{RandomCharacter, ScoreMethod},
+        validation::ValidationIssue,
+        wealth::Wealth,
     },
 };
 
@@ -18,6 +26,12 @@ pub enum Tab {
     Race,
     Class,
     Archetype,
+    Languages,
+    Alignment,
+    Traits,
+    FavoredClass,
+    Equipment,
+    Review,
 }
 
 impl std::fmt::Display for Tab {
@@ -26,6 +40,12 @@ impl std::fmt::Display for Tab {
             Self::Race => write!(f, "Race"),
             Self::Class => write!(f, "Class"),
             Self::Archetype => write!(f, "Archetype"),
+            Self::Languages => write!(f, "Languages"),
+            Self::Alignment => write!(f, "Alignment"),
+            Self::Traits => write!(f, "Traits"),
+            Self::FavoredClass => write!(f, "Favored Class"),
+            Self::Equipment => write!(f, "Equipment"),
+            Self::Review => write!(f, "Review"),
         }
     }
 }
@@ -38,7 +58,17 @@ impl Into<SelectedTab> for Tab {
 
 impl AsVec for Tab {
     fn vec() -> Vec<Self> {
-        vec![Self::Race, Self::Class, Self::Archetype]
+        vec![
+            Self::Race,
+            Self::Class,
+            Self::Archetype,
+            Self::Languages,
+            Self::Alignment,
+            Self::Traits,
+            Self::FavoredClass,
+            Self::Equipment,
+            Self::Review,
+        ]
     }
 }
 
@@ -54,6 +84,12 @@ impl Into<TabListParent> for Tab {
             Self::Race => TabListParent::Race,
             Self::Class => TabListParent::Class,
             Self::Archetype => TabListParent::Archetype,
+            Self::Languages => TabListParent::Languages,
+            Self::Alignment => TabListParent::Alignment,
+            Self::Traits => TabListParent::Traits,
+            Self::FavoredClass => TabListParent::FavoredClass,
+            Self::Equipment => TabListParent::Equipment,
+            Self::Review => TabListParent::Review,
         }
     }
 }
@@ -220,6 +256,12 @@ impl Default for SelectedSubTabsMap {
         hash_map.insert(Tab::Race, SubTab::Description);
         hash_map.insert(Tab::Class, SubTab::Description);
         hash_map.insert(Tab::Archetype, SubTab::Description);
+        hash_map.insert(Tab::Languages, SubTab::Description);
+        hash_map.insert(Tab::Alignment, SubTab::Description);
+        hash_map.insert(Tab::Traits, SubTab::Description);
+        hash_map.insert(Tab::FavoredClass, SubTab::Description);
+        hash_map.insert(Tab::Equipment, SubTab::Description);
+        hash_map.insert(Tab::Review, SubTab::Description);
         SelectedSubTabsMap(hash_map)
     }
 }
@@ -415,6 +457,12 @@ pub enum TabListParent {
     Race,
     Class,
     Archetype,
+    Languages,
+    Alignment,
+    Traits,
+    FavoredClass,
+    Equipment,
+    Review,
 }
 
 impl std::fmt::Display for TabListParent {
@@ -423,6 +471,12 @@ impl std::fmt::Display for TabListParent {
             Self::Race => write!(f, "Race"),
             Self::Class => write!(f, "Class"),
             Self::Archetype => write!(f, "Archetype"),
+            Self::Languages => write!(f, "Languages"),
+            Self::Alignment => write!(f, "Alignment"),
+            Self::Traits => write!(f, "Traits"),
+            Self::FavoredClass => write!(f, "Favored Class"),
+            Self::Equipment => write!(f, "Equipment"),
+            Self::Review => write!(f, "Review"),
         }
     }
 }
@@ -433,6 +487,294 @@ impl Into<Tab> for TabListParent {
             Self::Race => Tab::Race,
             Self::Class => Tab::Class,
             Self::Archetype => Tab::Archetype,
+            Self::Languages => Tab::Languages,
+            Self::Alignment => Tab::Alignment,
+            Self::Traits => Tab::Traits,
+            Self::FavoredClass => Tab::FavoredClass,
+            Self::Equipment => Tab::Equipment,
+            Self::Review => Tab::Review,
+        }
+    }
+}
+
+// Languages picked in the languages tab, in the order they were picked.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChosenLanguages(pub Vec<Language>);
+
+// Button in the languages tab that picks or drops a language.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LanguageButton(pub Language);
+
+// Text at the top of the languages tab with the known languages and the
+// picks left.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct LanguageSummaryText;
+
+// Alignment, deity and domains picked in the alignment tab.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChosenAlignment {
+    pub alignment: Option<Alignment>,
+    // Name of the chosen deity's asset.
+    pub deity: Option<String>,
+    pub domains: Vec<Domain>,
+}
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AlignmentButton(pub Alignment);
+
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct DeityButton(pub String);
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DomainButton(pub Domain);
+
+// Row in the alignment tab that deity buttons are added to once the deities
+// have loaded.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct DeityList;
+
+// Text at the bottom of the alignment tab listing what makes the choice
+// illegal.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct AlignmentIssuesText;
+
+// Names of the traits picked in the traits tab, in the order they were
+// picked.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChosenTraits(pub Vec<String>);
+
+// Button in the traits tab that picks or drops a trait, shown under the
+// trait's category.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct TraitButton(pub CharacterTrait);
+
+// Node in the traits tab that trait buttons are added to once the catalog
+// has loaded.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct TraitList;
+
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct TraitSummaryText;
+
+// Favored classes and per-level bonuses picked in the favored class tab.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChosenFavoredClasses(pub FavoredClasses);
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FavoredClassButton(pub PlayableClass);
+
+// Button picking the bonus for one favored class level, `slot` being the
+// level's index in `FavoredClasses::choices`.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FavoredBonusButton {
+    pub slot: usize,
+    pub choice: FavoredClassChoice,
+}
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FavoredManeuverButton(pub CombatManeuverName);
+
+// Node in the favored class tab holding a row of bonus buttons for each
+// favored class level, rebuilt when the levels change.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct FavoredBonusList;
+
+// Row of maneuver buttons, only shown when a CMD bonus is taken.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct FavoredManeuverRow;
+
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct FavoredClassSummaryText;
+
+// Starting wealth and purchases made in the equipment tab.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct ChosenEquipment {
+    // Starting wealth rolled for a class. The class's average is used when
+    // nothing was rolled for the selected class.
+    pub rolled: Option<(PlayableClass, Wealth)>,
+    // Items bought, in the order they were bought.
+    pub purchases: Vec<Item>,
+}
+
+// Which items the shop in the equipment tab lists.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShopFilter {
+    // `None` lists every category.
+    pub category: Option<ItemCategory>,
+    pub proficient_only: bool,
+}
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StartingWealthButton {
+    Average,
+    Roll,
+}
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ShopCategoryButton(pub Option<ItemCategory>);
+
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct ProficientOnlyButton;
+
+// Button in the shop that buys the item.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct ShopItemButton(pub Item);
+
+// Button in the inventory that sells back the purchase at this index.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InventoryItemButton(pub usize);
+
+// Node in the equipment tab that shop buttons are added to once the catalog
+// has loaded.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct ShopList;
+
+// Node in the equipment tab holding the purchases, rebuilt when they change.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct InventoryList;
+
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct EquipmentSummaryText;
+
+// Proficiency feats picked in the equipment tab, in the order they were
+// picked.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChosenProficiencyFeats(pub Vec<ProficiencyFeat>);
+
+// Button in the equipment tab that picks or drops a proficiency feat.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ProficiencyFeatButton(pub ProficiencyFeat);
+
+// Node in the equipment tab that proficiency feat buttons are added to once
+// the catalog has loaded, with a weapon feat for each weapon in it.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct ProficiencyFeatList;
+
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct ProficiencyFeatSummaryText;
+
+// How the tabs are moved through. The guided mode walks the stages of
+// creation in order with the Previous and Next buttons and won't go past a
+// stage with issues that can be fixed in a tab, the expert mode lets any tab
+// be picked at any time.
+#[derive(Resource, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CreationMode {
+    #[default]
+    Guided,
+    Expert,
+}
+
+// The step the guided mode is on, counting the stages of creation in order
+// and then the review.
+#[derive(Resource, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WizardStep(pub usize);
+
+impl WizardStep {
+    // The stage of creation, or `None` on the review.
+    pub fn stage(&self) -> Option<StagesOfCreationButton> {
+        StagesOfCreationButton::vec().get(self.0).copied()
+    }
+    pub fn count() -> usize {
+        StagesOfCreationButton::vec().len() + 1
+    }
+}
+
+// A problem the validator found, with the tab it's fixed in if it has one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StageIssue {
+    pub tab: Option<Tab>,
+    pub text: String,
+}
+
+// What is still missing or illegal in each stage of creation, one entry per
+// stage in order. A stage with no issues is complete.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StageIssues(pub Vec<(StagesOfCreationButton, Vec<StageIssue>)>);
+
+impl StageIssues {
+    pub fn of(&self, stage: &StagesOfCreationButton) -> &[StageIssue] {
+        self.0
+            .iter()
+            .find(|(other, _)| other == stage)
+            .map_or(&[], |(_, issues)| issues.as_slice())
+    }
+    pub fn is_complete(&self, stage: &StagesOfCreationButton) -> bool {
+        self.of(stage).is_empty()
+    }
+    // Whether no issue is fixed in `tab`. The review is complete once every
+    // stage is.
+    pub fn tab_is_complete(&self, tab: &Tab) -> bool {
+        self.0
+            .iter()
+            .flat_map(|(_, issues)| issues.iter())
+            .all(|issue| *tab != Tab::Review && issue.tab != Some(*tab))
+    }
+}
+
+// What the validator found wrong with the builder, kept up to date during
+// character creation.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CharacterIssues(pub Vec<ValidationIssue>);
+
+// Text in the review tab listing every stage and its issues.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct ReviewSummaryText;
+
+// Which of the current race, class and archetype the Randomize button keeps.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RandomLock {
+    #[default]
+    Nothing,
+    Race,
+    Class,
+    RaceAndClass,
+    RaceClassAndArchetype,
+}
+
+impl RandomLock {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Nothing => Self::Race,
+            Self::Race => Self::Class,
+            Self::Class => Self::RaceAndClass,
+            Self::RaceAndClass => Self::RaceClassAndArchetype,
+            Self::RaceClassAndArchetype => Self::Nothing,
+        }
+    }
+}
+
+impl std::fmt::Display for RandomLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nothing => write!(f, "Keep Nothing"),
+            Self::Race => write!(f, "Keep Race"),
+            Self::Class => write!(f, "Keep Class"),
+            Self::RaceAndClass => write!(f, "Keep Race and Class"),
+            Self::RaceClassAndArchetype => write!(f, "Keep Race, Class and Archetype"),
+        }
+    }
+}
+
+// Options of the Randomize button, and the random character it made.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Randomizer {
+    pub lock: RandomLock,
+    pub method: ScoreMethod,
+    // Seed of the last random character, shown in the review tab.
+    pub seed: Option<u64>,
+    // A random character waiting to be put on the builder. Its race, class
+    // and archetype are selected first, the rest goes on once the race is
+    // built.
+    pub pending: Option<RandomCharacter>,
+}
+
+impl Default for Randomizer {
+    fn default() -> Self {
+        Self {
+            lock: RandomLock::default(),
+            method: ScoreMethod::PointBuy(15),
+            seed: None,
+            pending: None,
         }
     }
 }
@@ -698,6 +1040,17 @@ pub struct CharacterSheetButton;
 pub struct PreviousButton;
 #[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
 pub struct NextButton;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+pub struct CreationModeButton;
+// Text in the bottom container with the current stage and what it is missing.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+pub struct StageStatusText;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+pub struct RandomizeButton;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+pub struct RandomLockButton;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+pub struct ScoreMethodButton;
 
 // Tooltip labels
 #[derive(Component, Clone, Debug, Copy)]
@@ -5,6 +5,9 @@ pub const RACIAL_DEFAULT_TRAITS_DESCRIPTION_FOLDER: &str = "text/descriptions/ra
 pub const RACIAL_ALT_TRAITS_FOLDER: &str = "text/descriptions/races/alternate_traits";
 pub const CLASS_DESCRIPTIONS_FOLDER: &str = "text/descriptions/class";
 pub const ARCHETYPE_DESCRIPTIONS_FOLDER: &str = "text/descriptions/class/archetypes";
+pub const DEITY_DESCRIPTIONS_FOLDER: &str = "text/descriptions/deities";
+pub const TRAIT_DESCRIPTIONS_FOLDER: &str = "text/descriptions/traits";
+pub const EQUIPMENT_FOLDER: &str = "text/descriptions/equipment";
 pub const CLASS_DESCRIPTION_TITLE: &'static str = "Class Description";
 
 pub const PROGRESSION_TABLE_HEADERS: [&'static str; 6] = [
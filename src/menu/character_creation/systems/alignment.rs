@@ -0,0 +1,331 @@
+use crate::menu::character_creation::components::*;
+use crate::menu::styles::*;
+use crate::systems::game::{
+    character::{Alignment, ClassLevels},
+    class::PlayableClass,
+    deity::{alignment_issues, domain_count, AlignmentIssues, Deity, Domain, Domains},
+    race::CharacterBuilder,
+};
+use crate::technical::deity::DeityAsset;
+use bevy::prelude::*;
+
+fn spawn_title(parent: &mut ChildBuilder, font: &Handle<Font>, title: &str) {
+    parent.spawn(TextBundle::from_section(
+        title,
+        TextStyle {
+            font: font.clone(),
+            font_size: LIST_TITLE_TEXT_SIZE,
+            color: PANEL_TITLE_COLOR,
+        },
+    ));
+}
+
+fn spawn_row(parent: &mut ChildBuilder) -> Entity {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_wrap: FlexWrap::Wrap,
+                column_gap: Val::Px(8.),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            ..default()
+        })
+        .id()
+}
+
+fn spawn_choice_button(
+    commands: &mut Commands,
+    row: Entity,
+    font: &Handle<Font>,
+    label: String,
+    display: Display,
+    choice: impl Bundle,
+) {
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    display,
+                    padding: UiRect::all(Val::Px(5.)),
+                    ..default()
+                },
+                background_color: RACE_BUTTON_COLOR.into(),
+                ..default()
+            },
+            choice,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: LIST_BUTTON_TEXT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            ));
+        })
+        .set_parent(row);
+}
+
+// Fills the alignment tab with rows of alignment and domain buttons, an empty
+// row for the deities, and the list of issues at the bottom.
+pub fn setup_alignment_tab(
+    query_parent: Query<(Entity, &TabListParent)>,
+    asset_server: Res<AssetServer>,
+    mut chosen: ResMut<ChosenAlignment>,
+    mut commands: Commands,
+) {
+    *chosen = ChosenAlignment::default();
+    let Some((parent, _)) = query_parent
+        .iter()
+        .find(|(_, list_parent)| **list_parent == TabListParent::Alignment)
+    else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    let (mut alignment_row, mut domain_row) = (parent, parent);
+    commands.entity(parent).with_children(|list| {
+        spawn_title(list, &shared_font, "Alignment");
+        alignment_row = spawn_row(list);
+        spawn_title(list, &shared_font, "Deity");
+        list.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(8.),
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            DeityList,
+        ));
+        spawn_title(list, &shared_font, "Domains");
+        domain_row = spawn_row(list);
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            ),
+            AlignmentIssuesText,
+        ));
+    });
+    for alignment in Alignment::as_array() {
+        spawn_choice_button(
+            &mut commands,
+            alignment_row,
+            &shared_font,
+            alignment.to_string(),
+            Display::Flex,
+            AlignmentButton(alignment),
+        );
+    }
+    for domain in Domain::array() {
+        spawn_choice_button(
+            &mut commands,
+            domain_row,
+            &shared_font,
+            domain.to_string(),
+            Display::None,
+            DomainButton(domain),
+        );
+    }
+}
+
+// Adds a button for every deity, sorted by name, once they have loaded.
+pub fn build_deity_buttons(
+    query_list: Query<Entity, (With<DeityList>, Without<Children>)>,
+    deities: Res<Assets<DeityAsset>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok(row) = query_list.get_single() else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    let mut sorted: Vec<&DeityAsset> = deities.iter().map(|(_, deity)| deity).collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    for deity in sorted {
+        spawn_choice_button(
+            &mut commands,
+            row,
+            &shared_font,
+            format!("{} ({})", deity.name, deity.alignment),
+            Display::Flex,
+            DeityButton(deity.name.clone()),
+        );
+    }
+}
+
+// Clicking an alignment picks it, and clicking a deity or domain picks it or
+// drops it if it was already picked. Changing deity drops the domains, and
+// domains past what the class takes are ignored.
+pub fn choose_alignment(
+    query_alignment: Query<(&Interaction, &AlignmentButton), Changed<Interaction>>,
+    query_deity: Query<(&Interaction, &DeityButton), Changed<Interaction>>,
+    query_domain: Query<(&Interaction, &DomainButton), Changed<Interaction>>,
+    selected_class: Res<SelectedClass>,
+    mut chosen: ResMut<ChosenAlignment>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+    for (_, button) in query_alignment.iter().filter(|(i, _)| pressed(i)) {
+        chosen.alignment = Some(button.0);
+    }
+    for (_, button) in query_deity.iter().filter(|(i, _)| pressed(i)) {
+        if chosen.deity.as_ref() == Some(&button.0) {
+            chosen.deity = None;
+        } else {
+            chosen.deity = Some(button.0.clone());
+        }
+        chosen.domains.clear();
+    }
+    let needed = domain_count(&selected_class.inner());
+    for (_, button) in query_domain.iter().filter(|(i, _)| pressed(i)) {
+        if let Some(index) = chosen.domains.iter().position(|domain| *domain == button.0) {
+            chosen.domains.remove(index);
+        } else if chosen.domains.len() < needed {
+            chosen.domains.push(button.0);
+        }
+    }
+}
+
+type AlignmentBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static ClassLevels>,
+        Option<&'static AlignmentIssues>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Puts the chosen alignment, deity and domains on the builder, along with
+// whatever makes them illegal for its classes.
+pub fn update_alignment(
+    query_builder: AlignmentBuilderQuery,
+    chosen: Res<ChosenAlignment>,
+    selected_class: Res<SelectedClass>,
+    deities: Res<Assets<DeityAsset>>,
+    mut commands: Commands,
+) {
+    let Ok((entity, class_levels, old_issues)) = query_builder.get_single() else {
+        return;
+    };
+    let mut classes: Vec<PlayableClass> = class_levels
+        .map(|levels| levels.inner_ref().keys().copied().collect())
+        .unwrap_or_default();
+    if !classes.contains(&selected_class.inner()) {
+        classes.push(selected_class.inner());
+    }
+    let deity = chosen.deity.as_ref().and_then(|name| {
+        deities
+            .iter()
+            .map(|(_, deity)| deity)
+            .find(|deity| deity.name == *name)
+    });
+    let issues = AlignmentIssues(alignment_issues(
+        chosen.alignment.as_ref(),
+        deity,
+        &chosen.domains,
+        &classes,
+    ));
+    if old_issues != Some(&issues) {
+        commands.entity(entity).insert(issues);
+    }
+    if !chosen.is_changed() {
+        return;
+    }
+    let mut builder = commands.entity(entity);
+    match chosen.alignment {
+        Some(alignment) => builder.insert(alignment),
+        None => builder.remove::<Alignment>(),
+    };
+    match &chosen.deity {
+        Some(name) => builder.insert((Deity(name.clone()), Domains(chosen.domains.clone()))),
+        None => builder.remove::<(Deity, Domains)>(),
+    };
+}
+
+type AlignmentButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut BackgroundColor,
+        Option<&'static AlignmentButton>,
+        Option<&'static DeityButton>,
+    ),
+    Or<(With<AlignmentButton>, With<DeityButton>)>,
+>;
+
+// Highlights the picked buttons, shows the domains of the chosen deity when
+// the class takes domains, and lists the issues in red.
+pub fn display_alignment(
+    chosen: Res<ChosenAlignment>,
+    selected_class: Res<SelectedClass>,
+    deities: Res<Assets<DeityAsset>>,
+    query_issues: Query<&AlignmentIssues, With<CharacterBuilder>>,
+    mut query_buttons: AlignmentButtonQuery,
+    mut query_domain: Query<
+        (&mut Style, &mut BackgroundColor, &DomainButton),
+        Without<AlignmentButton>,
+    >,
+    mut query_text: Query<&mut Text, With<AlignmentIssuesText>>,
+) {
+    let highlight = |background_color: &mut Mut<BackgroundColor>, picked: bool| {
+        let color = if picked {
+            RACE_BUTTON_COLOR_SELECTED
+        } else {
+            RACE_BUTTON_COLOR
+        };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    };
+    for (mut background_color, alignment, deity) in query_buttons.iter_mut() {
+        let picked = alignment.is_some_and(|button| chosen.alignment == Some(button.0))
+            || deity.is_some_and(|button| chosen.deity.as_ref() == Some(&button.0));
+        highlight(&mut background_color, picked);
+    }
+    let granted: Vec<Domain> = chosen
+        .deity
+        .as_ref()
+        .and_then(|name| deities.iter().find(|(_, deity)| deity.name == *name))
+        .filter(|_| domain_count(&selected_class.inner()) > 0)
+        .map(|(_, deity)| deity.domains.clone())
+        .unwrap_or_default();
+    for (mut style, mut background_color, button) in query_domain.iter_mut() {
+        let display = if granted.contains(&button.0) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+        highlight(&mut background_color, chosen.domains.contains(&button.0));
+    }
+    let (Ok(issues), Ok(mut text)) = (query_issues.get_single(), query_text.get_single_mut())
+    else {
+        return;
+    };
+    let (value, color) = if issues.0.is_empty() {
+        ("Alignment is legal".to_string(), TEXT_COLOR)
+    } else {
+        let lines: Vec<String> = issues.0.iter().map(|issue| issue.to_string()).collect();
+        (lines.join("\n"), Color::RED)
+    };
+    if text
+        .sections
+        .first()
+        .is_some_and(|section| section.value != value)
+    {
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+}
@@ -0,0 +1,258 @@
+use crate::menu::character_creation::components::*;
+use crate::menu::styles::*;
+use crate::systems::game::{
+    character_traits::{
+        trait_issues, traits_allowed, CharacterTrait, CharacterTraits, TraitCategory,
+    },
+    effects::{Effect, EffectSource},
+    race::CharacterBuilder,
+};
+use crate::technical::character_traits::TraitCatalogAsset;
+use bevy::prelude::*;
+
+// The traits with the given names, from every loaded catalog, in the order
+// the names are given.
+pub fn find_traits<'a>(
+    catalogs: &'a Assets<TraitCatalogAsset>,
+    names: &[String],
+) -> Vec<&'a CharacterTrait> {
+    names
+        .iter()
+        .filter_map(|name| {
+            catalogs
+                .iter()
+                .flat_map(|(_, catalog)| catalog.traits.iter())
+                .find(|character_trait| character_trait.name == *name)
+        })
+        .collect()
+}
+
+// Adds the summary text and an empty node for the trait buttons, which are
+// added by `build_trait_buttons` once the catalog has loaded.
+pub fn setup_trait_tab(
+    query_parent: Query<(Entity, &TabListParent)>,
+    asset_server: Res<AssetServer>,
+    mut chosen: ResMut<ChosenTraits>,
+    mut commands: Commands,
+) {
+    chosen.0.clear();
+    let Some((parent, _)) = query_parent
+        .iter()
+        .find(|(_, list_parent)| **list_parent == TabListParent::Traits)
+    else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(parent).with_children(|list| {
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(DESCRIPTION_MAX_WIDTH),
+                ..default()
+            }),
+            TraitSummaryText,
+        ));
+        list.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            TraitList,
+        ));
+    });
+}
+
+// Adds a title for each trait category with a row of its traits under it.
+pub fn build_trait_buttons(
+    query_list: Query<Entity, (With<TraitList>, Without<Children>)>,
+    catalogs: Res<Assets<TraitCatalogAsset>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok(trait_list) = query_list.get_single() else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    let all_traits: Vec<&CharacterTrait> = catalogs
+        .iter()
+        .flat_map(|(_, catalog)| catalog.traits.iter())
+        .collect();
+    commands.entity(trait_list).with_children(|list| {
+        for category in TraitCategory::array() {
+            list.spawn(TextBundle::from_section(
+                category.to_string(),
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: LIST_TITLE_TEXT_SIZE,
+                    color: PANEL_TITLE_COLOR,
+                },
+            ));
+            list.spawn(NodeBundle {
+                style: Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(8.),
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                for character_trait in all_traits
+                    .iter()
+                    .filter(|character_trait| character_trait.category == category)
+                {
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(5.)),
+                                ..default()
+                            },
+                            background_color: RACE_BUTTON_COLOR.into(),
+                            ..default()
+                        },
+                        TraitButton((*character_trait).clone()),
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            character_trait.name.clone(),
+                            TextStyle {
+                                font: shared_font.clone(),
+                                font_size: LIST_BUTTON_TEXT_SIZE,
+                                color: TEXT_COLOR,
+                            },
+                        ));
+                    });
+                }
+            });
+        }
+    });
+}
+
+// Clicking a trait picks it, or drops it if it was already picked. A pick
+// that would make the selection illegal is ignored.
+pub fn choose_trait(
+    query_button: Query<(&Interaction, &TraitButton), Changed<Interaction>>,
+    catalogs: Res<Assets<TraitCatalogAsset>>,
+    selected_race: Res<SelectedRace>,
+    mut chosen: ResMut<ChosenTraits>,
+) {
+    for (_, button) in query_button
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        if let Some(index) = chosen.0.iter().position(|name| *name == button.0.name) {
+            chosen.0.remove(index);
+            continue;
+        }
+        let mut picks = chosen.0.clone();
+        picks.push(button.0.name.clone());
+        if trait_issues(&find_traits(&catalogs, &picks), &selected_race.inner()).is_empty() {
+            chosen.0 = picks;
+        }
+    }
+}
+
+// Keeps `CharacterTraits` and the trait effects on the builder in line with
+// the picks. Picks a newly chosen race no longer allows are dropped.
+pub fn update_character_traits(
+    query_builder: Query<(Entity, Option<&CharacterTraits>), With<CharacterBuilder>>,
+    query_effects: Query<(Entity, &Effect)>,
+    catalogs: Res<Assets<TraitCatalogAsset>>,
+    selected_race: Res<SelectedRace>,
+    mut chosen: ResMut<ChosenTraits>,
+    mut commands: Commands,
+) {
+    let Ok((builder, character_traits)) = query_builder.get_single() else {
+        return;
+    };
+    let mut picks = chosen.0.clone();
+    while !trait_issues(&find_traits(&catalogs, &picks), &selected_race.inner()).is_empty() {
+        picks.pop();
+    }
+    if picks != chosen.0 {
+        chosen.0 = picks;
+    }
+    if character_traits.is_some_and(|character_traits| character_traits.0 == chosen.0) {
+        return;
+    }
+    for (entity, _) in query_effects.iter().filter(|(_, effect)| {
+        effect.target == builder && matches!(effect.source, EffectSource::CharacterTrait(_))
+    }) {
+        commands.entity(entity).despawn();
+    }
+    for character_trait in find_traits(&catalogs, &chosen.0) {
+        commands.spawn(Effect::from_character_trait(builder, character_trait));
+    }
+    commands
+        .entity(builder)
+        .insert(CharacterTraits(chosen.0.clone()));
+}
+
+// Hides race traits for other races, highlights the picked traits, and
+// describes the picks above the list.
+pub fn display_traits(
+    chosen: Res<ChosenTraits>,
+    selected_race: Res<SelectedRace>,
+    catalogs: Res<Assets<TraitCatalogAsset>>,
+    mut query_button: Query<(&mut Style, &mut BackgroundColor, &TraitButton)>,
+    mut query_text: Query<&mut Text, With<TraitSummaryText>>,
+) {
+    for (mut style, mut background_color, button) in query_button.iter_mut() {
+        let display = if button
+            .0
+            .race
+            .is_none_or(|race| race == selected_race.inner())
+        {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let color = if chosen.0.contains(&button.0.name) {
+            RACE_BUTTON_COLOR_SELECTED
+        } else {
+            RACE_BUTTON_COLOR
+        };
+        if style.display != display {
+            style.display = display;
+        }
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+    let Ok(mut text) = query_text.get_single_mut() else {
+        return;
+    };
+    let picked = find_traits(&catalogs, &chosen.0);
+    let traits = picked
+        .iter()
+        .filter(|picked| picked.category != TraitCategory::Drawback)
+        .count();
+    let mut value = format!(
+        "Traits: {traits} of {}, each from a different category. Taking a drawback allows one more.",
+        traits_allowed(&picked)
+    );
+    for picked in picked.iter() {
+        value.push_str(&format!(
+            "\n\n{} ({}): {}",
+            picked.name, picked.category, picked.description
+        ));
+    }
+    if text
+        .sections
+        .first()
+        .is_some_and(|section| section.value != value)
+    {
+        text.sections[0].value = value;
+    }
+}
@@ -0,0 +1,672 @@
+use crate::menu::character_creation::components::*;
+use crate::menu::styles::*;
+use crate::systems::game::{
+    archetype::ArchetypeMap,
+    character::{CharacterWeaponProficiency, ClassLevels, FloatingBonusFeats},
+    class::PlayableClass,
+    dice::GameRng,
+    equipment::{
+        Inventory, Item, ItemCategory, ItemKind, WeaponName, WeaponProficiency, WieldedWeapon,
+        WornArmor,
+    },
+    proficiency::{feat_slots, Proficiencies, ProficiencyFeat, ProficiencyFeats},
+    race::CharacterBuilder,
+    wealth::{starting_wealth, Wealth},
+};
+use crate::technical::equipment::ShopCatalogAsset;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// Gold rolled for the class, or the class's average if nothing was rolled
+// for it.
+fn starting_amount(chosen: &ChosenEquipment, class: &PlayableClass) -> Wealth {
+    chosen
+        .rolled
+        .filter(|(rolled_class, _)| rolled_class == class)
+        .map_or(starting_wealth(class).average(), |(_, wealth)| wealth)
+}
+
+fn spent(purchases: &[Item]) -> u32 {
+    purchases.iter().map(|item| item.cost).sum()
+}
+
+// Gold left after the purchases, or `None` when they cost more than the
+// starting wealth, as they can for a moment after the class changes.
+fn left_over(chosen: &ChosenEquipment, class: &PlayableClass) -> Option<u32> {
+    starting_amount(chosen, class)
+        .0
+        .checked_sub(spent(&chosen.purchases))
+}
+
+// The first weapon bought is wielded, and the first armor and first shield
+// are worn.
+fn equipped(purchases: &[Item]) -> (Option<WieldedWeapon>, WornArmor) {
+    let weapon = purchases.iter().find_map(|item| match item.kind {
+        ItemKind::Weapon(weapon) => Some(WieldedWeapon(weapon)),
+        _ => None,
+    });
+    let pieces: Vec<_> = purchases
+        .iter()
+        .filter_map(|item| match item.kind {
+            ItemKind::Armor(piece) => Some(piece),
+            _ => None,
+        })
+        .collect();
+    let armor = pieces.iter().find(|piece| !piece.is_shield());
+    let shield = pieces.iter().find(|piece| piece.is_shield());
+    (
+        weapon,
+        WornArmor(armor.into_iter().chain(shield).copied().collect()),
+    )
+}
+
+fn spawn_title(parent: &mut ChildBuilder, font: &Handle<Font>, title: &str) {
+    parent.spawn(TextBundle::from_section(
+        title,
+        TextStyle {
+            font: font.clone(),
+            font_size: LIST_TITLE_TEXT_SIZE,
+            color: PANEL_TITLE_COLOR,
+        },
+    ));
+}
+
+fn spawn_row(parent: &mut ChildBuilder, bundle: impl Bundle, row: impl FnOnce(&mut ChildBuilder)) {
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(8.),
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            bundle,
+        ))
+        .with_children(row);
+}
+
+fn spawn_choice_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: String,
+    choice: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(5.)),
+                    ..default()
+                },
+                background_color: RACE_BUTTON_COLOR.into(),
+                ..default()
+            },
+            choice,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: LIST_BUTTON_TEXT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            ));
+        });
+}
+
+// Feats the builder picks, which proficiency feats fill. Until the builder
+// has class levels it counts as first level in the selected class.
+fn feats_allowed(
+    floating: Option<&FloatingBonusFeats>,
+    class_levels: Option<&ClassLevels>,
+    selected_class: &SelectedClass,
+) -> usize {
+    let racial = floating.map_or(0, |floating| {
+        floating.inner().iter().map(|feat| feat.number).sum()
+    });
+    match class_levels {
+        Some(class_levels) => feat_slots(class_levels, racial),
+        None => feat_slots(
+            &ClassLevels::from(HashMap::from([(selected_class.inner(), 1)])),
+            racial,
+        ),
+    }
+}
+
+// Fills the equipment tab with the wealth summary and buttons, the shop
+// filters, and empty nodes for the shop, the inventory and the proficiency
+// feats.
+pub fn setup_equipment_tab(
+    query_parent: Query<(Entity, &TabListParent)>,
+    asset_server: Res<AssetServer>,
+    mut chosen: ResMut<ChosenEquipment>,
+    mut chosen_feats: ResMut<ChosenProficiencyFeats>,
+    mut filter: ResMut<ShopFilter>,
+    mut commands: Commands,
+) {
+    *chosen = ChosenEquipment::default();
+    chosen_feats.0.clear();
+    *filter = ShopFilter::default();
+    let Some((parent, _)) = query_parent
+        .iter()
+        .find(|(_, list_parent)| **list_parent == TabListParent::Equipment)
+    else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(parent).with_children(|list| {
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(DESCRIPTION_MAX_WIDTH),
+                ..default()
+            }),
+            EquipmentSummaryText,
+        ));
+        spawn_title(list, &shared_font, "Starting Wealth");
+        spawn_row(list, (), |row| {
+            spawn_choice_button(
+                row,
+                &shared_font,
+                "Average".to_string(),
+                StartingWealthButton::Average,
+            );
+            spawn_choice_button(
+                row,
+                &shared_font,
+                "Roll".to_string(),
+                StartingWealthButton::Roll,
+            );
+        });
+        spawn_title(list, &shared_font, "Shop");
+        spawn_row(list, (), |row| {
+            spawn_choice_button(
+                row,
+                &shared_font,
+                "All".to_string(),
+                ShopCategoryButton(None),
+            );
+            for category in ItemCategory::array() {
+                spawn_choice_button(
+                    row,
+                    &shared_font,
+                    category.to_string(),
+                    ShopCategoryButton(Some(category)),
+                );
+            }
+            spawn_choice_button(
+                row,
+                &shared_font,
+                "Proficient Only".to_string(),
+                ProficientOnlyButton,
+            );
+        });
+        spawn_row(list, ShopList, |_| {});
+        spawn_title(list, &shared_font, "Inventory");
+        spawn_row(list, InventoryList, |_| {});
+        spawn_title(list, &shared_font, "Proficiency Feats");
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(DESCRIPTION_MAX_WIDTH),
+                ..default()
+            }),
+            ProficiencyFeatSummaryText,
+        ));
+        spawn_row(list, ProficiencyFeatList, |_| {});
+    });
+}
+
+// Adds a button for the simple weapon and armor proficiency feats, and a
+// martial or exotic weapon feat for every such weapon in the loaded catalogs.
+pub fn build_proficiency_feat_buttons(
+    query_list: Query<Entity, (With<ProficiencyFeatList>, Without<Children>)>,
+    catalogs: Res<Assets<ShopCatalogAsset>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    use ProficiencyFeat::*;
+    let Ok(feat_list) = query_list.get_single() else {
+        return;
+    };
+    let mut weapons: Vec<WeaponName> = Vec::new();
+    for (_, catalog) in catalogs.iter() {
+        for item in catalog.items.iter() {
+            if let ItemKind::Weapon(weapon) = item.kind {
+                if !weapons.contains(&weapon) {
+                    weapons.push(weapon);
+                }
+            }
+        }
+    }
+    if weapons.is_empty() {
+        return;
+    }
+    let weapon_feats = weapons
+        .into_iter()
+        .filter_map(|weapon| match weapon.proficiency() {
+            WeaponProficiency::Martial => Some(MartialWeapon(weapon)),
+            WeaponProficiency::Exotic => Some(ExoticWeapon(weapon)),
+            _ => None,
+        });
+    let feats = [
+        SimpleWeapon,
+        LightArmor,
+        MediumArmor,
+        HeavyArmor,
+        Shield,
+        TowerShield,
+    ]
+    .into_iter()
+    .chain(weapon_feats);
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(feat_list).with_children(|list| {
+        for feat in feats {
+            spawn_choice_button(
+                list,
+                &shared_font,
+                feat.to_string(),
+                ProficiencyFeatButton(feat),
+            );
+        }
+    });
+}
+
+// Clicking a proficiency feat picks it, or drops it if it was already picked.
+// A pick past the character's feats is ignored.
+pub fn choose_proficiency_feat(
+    query_button: Query<(&Interaction, &ProficiencyFeatButton), Changed<Interaction>>,
+    query_builder: Query<
+        (Option<&FloatingBonusFeats>, Option<&ClassLevels>),
+        With<CharacterBuilder>,
+    >,
+    selected_class: Res<SelectedClass>,
+    mut chosen: ResMut<ChosenProficiencyFeats>,
+) {
+    let (floating, class_levels) = query_builder.get_single().unwrap_or((None, None));
+    let allowed = feats_allowed(floating, class_levels, &selected_class);
+    for (_, button) in query_button
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        if let Some(index) = chosen.0.iter().position(|feat| *feat == button.0) {
+            chosen.0.remove(index);
+        } else if chosen.0.len() < allowed {
+            chosen.0.push(button.0);
+        }
+    }
+}
+
+// Adds a button for every item in the loaded catalogs.
+pub fn build_shop_buttons(
+    query_list: Query<Entity, (With<ShopList>, Without<Children>)>,
+    catalogs: Res<Assets<ShopCatalogAsset>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok(shop_list) = query_list.get_single() else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(shop_list).with_children(|list| {
+        for item in catalogs
+            .iter()
+            .flat_map(|(_, catalog)| catalog.items.iter())
+        {
+            spawn_choice_button(
+                list,
+                &shared_font,
+                format!("{} ({})", item.name, Wealth(item.cost)),
+                ShopItemButton(item.clone()),
+            );
+        }
+    });
+}
+
+// Wealth buttons take the class's average or roll for it, filter buttons
+// change what the shop lists, shop items are bought if there is gold left
+// for them, and inventory items are sold back for what they cost.
+#[allow(clippy::too_many_arguments)]
+pub fn choose_equipment(
+    query_wealth: Query<(&Interaction, &StartingWealthButton), Changed<Interaction>>,
+    query_category: Query<(&Interaction, &ShopCategoryButton), Changed<Interaction>>,
+    query_proficient: Query<&Interaction, (Changed<Interaction>, With<ProficientOnlyButton>)>,
+    query_shop: Query<(&Interaction, &ShopItemButton), Changed<Interaction>>,
+    query_inventory: Query<(&Interaction, &InventoryItemButton), Changed<Interaction>>,
+    selected_class: Res<SelectedClass>,
+    mut rng: ResMut<GameRng>,
+    mut filter: ResMut<ShopFilter>,
+    mut chosen: ResMut<ChosenEquipment>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+    let class = selected_class.inner();
+    for (_, button) in query_wealth.iter().filter(|(i, _)| pressed(i)) {
+        chosen.rolled = match button {
+            StartingWealthButton::Average => None,
+            StartingWealthButton::Roll => Some((class, starting_wealth(&class).roll(&mut rng))),
+        };
+    }
+    for (_, button) in query_category.iter().filter(|(i, _)| pressed(i)) {
+        filter.category = button.0;
+    }
+    for _ in query_proficient.iter().filter(|i| pressed(i)) {
+        filter.proficient_only = !filter.proficient_only;
+    }
+    for (_, button) in query_shop.iter().filter(|(i, _)| pressed(i)) {
+        if left_over(&chosen, &class).is_some_and(|left| button.0.cost <= left) {
+            chosen.purchases.push(button.0.clone());
+        }
+    }
+    for (_, button) in query_inventory.iter().filter(|(i, _)| pressed(i)) {
+        if button.0 < chosen.purchases.len() {
+            chosen.purchases.remove(button.0);
+        }
+    }
+}
+
+type ProficiencyBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static CharacterWeaponProficiency>,
+        Option<&'static FloatingBonusFeats>,
+        Option<&'static ClassLevels>,
+        Option<&'static ProficiencyFeats>,
+        Option<&'static Proficiencies>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Puts the picked proficiency feats on the builder and merges them with the
+// class, archetype and racial weapon familiarity into its `Proficiencies`.
+// Picks past what a newly built race or class allows are dropped, latest
+// first.
+pub fn update_proficiencies(
+    query_builder: ProficiencyBuilderQuery,
+    selected_class: Res<SelectedClass>,
+    selected_archetype: Res<SelectedArchetype>,
+    archetype_map: Res<ArchetypeMap>,
+    mut chosen_feats: ResMut<ChosenProficiencyFeats>,
+    mut commands: Commands,
+) {
+    let Ok((entity, racial, floating, class_levels, old_feats, old)) = query_builder.get_single()
+    else {
+        return;
+    };
+    let allowed = feats_allowed(floating, class_levels, &selected_class);
+    if chosen_feats.0.len() > allowed {
+        chosen_feats.0.truncate(allowed);
+    }
+    let feats = ProficiencyFeats(chosen_feats.0.clone());
+    if old_feats != Some(&feats) {
+        commands.entity(entity).insert(feats.clone());
+    }
+    let feats = Some(&feats);
+    let class = selected_class.inner();
+    let archetypes: Vec<_> = archetype_map
+        .inner_ref()
+        .get(&selected_archetype.inner())
+        .filter(|info| info.class == class)
+        .into_iter()
+        .collect();
+    let proficiencies = Proficiencies::new(&[class], &archetypes, racial, feats);
+    if old != Some(&proficiencies) {
+        commands.entity(entity).insert(proficiencies);
+    }
+}
+
+type EquipmentBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static Wealth>,
+        Option<&'static Inventory>,
+        Option<&'static WieldedWeapon>,
+        Option<&'static WornArmor>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Keeps `Wealth`, `Inventory` and what is wielded and worn on the builder in
+// line with the starting wealth and purchases. If a newly chosen class starts
+// with less gold, the latest purchases are given back until the rest can be
+// paid for.
+pub fn update_equipment(
+    query_builder: EquipmentBuilderQuery,
+    selected_class: Res<SelectedClass>,
+    mut chosen: ResMut<ChosenEquipment>,
+    mut commands: Commands,
+) {
+    let Ok((builder, wealth, inventory, old_weapon, old_armor)) = query_builder.get_single() else {
+        return;
+    };
+    let starting = starting_amount(&chosen, &selected_class.inner());
+    let mut purchases = chosen.purchases.clone();
+    while spent(&purchases) > starting.0 {
+        purchases.pop();
+    }
+    if purchases != chosen.purchases {
+        chosen.purchases = purchases;
+    }
+    let left = Wealth(starting.0.saturating_sub(spent(&chosen.purchases)));
+    if wealth != Some(&left) {
+        commands.entity(builder).insert(left);
+    }
+    if inventory.is_none_or(|inventory| inventory.0 != chosen.purchases) {
+        commands
+            .entity(builder)
+            .insert(Inventory(chosen.purchases.clone()));
+    }
+    let (weapon, armor) = equipped(&chosen.purchases);
+    if old_weapon != weapon.as_ref() {
+        match weapon {
+            Some(weapon) => commands.entity(builder).insert(weapon),
+            None => commands.entity(builder).remove::<WieldedWeapon>(),
+        };
+    }
+    if old_armor.is_none_or(|old| *old != armor) {
+        commands.entity(builder).insert(armor);
+    }
+}
+
+// Rebuilds the inventory buttons whenever the purchases change.
+pub fn build_inventory_buttons(
+    query_list: Query<Entity, With<InventoryList>>,
+    chosen: Res<ChosenEquipment>,
+    asset_server: Res<AssetServer>,
+    mut built: Local<Option<Vec<Item>>>,
+    mut commands: Commands,
+) {
+    let Ok(inventory_list) = query_list.get_single() else {
+        return;
+    };
+    if built.as_ref() == Some(&chosen.purchases) {
+        return;
+    }
+    *built = Some(chosen.purchases.clone());
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(inventory_list).despawn_descendants();
+    commands.entity(inventory_list).with_children(|list| {
+        for (index, item) in chosen.purchases.iter().enumerate() {
+            spawn_choice_button(
+                list,
+                &shared_font,
+                item.name.clone(),
+                InventoryItemButton(index),
+            );
+        }
+    });
+}
+
+type ShopButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Style,
+        &'static mut BackgroundColor,
+        Option<&'static ShopItemButton>,
+        Option<&'static ShopCategoryButton>,
+        Option<&'static ProficientOnlyButton>,
+        Option<&'static StartingWealthButton>,
+    ),
+    Or<(
+        With<ShopItemButton>,
+        With<ShopCategoryButton>,
+        With<ProficientOnlyButton>,
+        With<StartingWealthButton>,
+    )>,
+>;
+
+type EquippedBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Proficiencies>,
+        Option<&'static WieldedWeapon>,
+        Option<&'static WornArmor>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Hides the shop items the filter leaves out, highlights the active filters
+// and wealth choice, and sums up the gold, load and any attack penalty from
+// non-proficient gear above the list.
+pub fn display_equipment(
+    chosen: Res<ChosenEquipment>,
+    filter: Res<ShopFilter>,
+    selected_class: Res<SelectedClass>,
+    query_builder: EquippedBuilderQuery,
+    mut query_buttons: ShopButtonQuery,
+    mut query_text: Query<&mut Text, With<EquipmentSummaryText>>,
+) {
+    let class = selected_class.inner();
+    let (proficiencies, weapon, armor) = query_builder.get_single().unwrap_or((None, None, None));
+    let rolled = chosen
+        .rolled
+        .is_some_and(|(rolled_class, _)| rolled_class == class);
+    for (mut style, mut background_color, item, category, proficient, wealth) in
+        query_buttons.iter_mut()
+    {
+        let shown = item.is_none_or(|button| {
+            filter
+                .category
+                .is_none_or(|category| button.0.kind.category() == category)
+                && (!filter.proficient_only
+                    || proficiencies.is_none_or(|known| known.with_item(&button.0)))
+        });
+        let display = if shown { Display::Flex } else { Display::None };
+        let picked = category.is_some_and(|button| button.0 == filter.category)
+            || (proficient.is_some() && filter.proficient_only)
+            || wealth.is_some_and(|button| (*button == StartingWealthButton::Roll) == rolled);
+        let color = if picked {
+            RACE_BUTTON_COLOR_SELECTED
+        } else {
+            RACE_BUTTON_COLOR
+        };
+        if style.display != display {
+            style.display = display;
+        }
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+    let Ok(mut text) = query_text.get_single_mut() else {
+        return;
+    };
+    let starting = starting_amount(&chosen, &class);
+    let spent = spent(&chosen.purchases);
+    let weight: f32 = chosen.purchases.iter().map(|item| item.weight).sum();
+    let mut value = format!(
+        "Starting wealth: {starting} ({}, {})\nSpent: {}, left: {}\nCarrying {weight} lb.",
+        starting_wealth(&class),
+        if rolled { "rolled" } else { "average" },
+        Wealth(spent),
+        Wealth(starting.0.saturating_sub(spent)),
+    );
+    let penalty = proficiencies.map_or(0, |known| known.attack_penalty(weapon, armor));
+    if penalty != 0 {
+        value.push_str(&format!("\nNot proficient: {penalty} on attack rolls"));
+    }
+    if text
+        .sections
+        .first()
+        .is_some_and(|section| section.value != value)
+    {
+        text.sections[0].value = value;
+    }
+}
+
+type ProficiencyFeatBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static FloatingBonusFeats>,
+        Option<&'static ClassLevels>,
+        Option<&'static Proficiencies>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Highlights the picked proficiency feats and hides the ones granting what
+// the character already has, with the picks left above them.
+pub fn display_proficiency_feats(
+    chosen: Res<ChosenProficiencyFeats>,
+    query_builder: ProficiencyFeatBuilderQuery,
+    selected_class: Res<SelectedClass>,
+    mut query_button: Query<(&mut Style, &mut BackgroundColor, &ProficiencyFeatButton)>,
+    mut query_text: Query<&mut Text, With<ProficiencyFeatSummaryText>>,
+) {
+    let (floating, class_levels, proficiencies) =
+        query_builder.get_single().unwrap_or((None, None, None));
+    for (mut style, mut background_color, button) in query_button.iter_mut() {
+        let picked = chosen.0.contains(&button.0);
+        let shown = picked || proficiencies.is_none_or(|known| !known.has_feat(&button.0));
+        let display = if shown { Display::Flex } else { Display::None };
+        let color = if picked {
+            RACE_BUTTON_COLOR_SELECTED
+        } else {
+            RACE_BUTTON_COLOR
+        };
+        if style.display != display {
+            style.display = display;
+        }
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+    let Ok(mut text) = query_text.get_single_mut() else {
+        return;
+    };
+    let value = format!(
+        "Feats: {} of {} picked",
+        chosen.0.len(),
+        feats_allowed(floating, class_levels, &selected_class)
+    );
+    if text
+        .sections
+        .first()
+        .is_some_and(|section| section.value != value)
+    {
+        text.sections[0].value = value;
+    }
+}
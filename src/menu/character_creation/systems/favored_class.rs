@@ -0,0 +1,432 @@
+use crate::menu::character_creation::components::*;
+use crate::menu::styles::*;
+use crate::systems::game::{
+    character::{ClassLevels, CombatManeuverName, PlayableRace},
+    class::{FavoredClass, PlayableClass},
+    effects::{Effect, EffectSource},
+    favored_class::{
+        alternate_options, alternates_taken, favored_class_count, favored_class_issues,
+        favored_levels, maneuvers_needed, FavoredClassChoice, FavoredClassIssues, FavoredClasses,
+    },
+    race::{CharacterBuilder, RaceBuilder},
+};
+use crate::technical::favored_class::FavoredClassAsset;
+use bevy::prelude::*;
+
+pub fn all_options(assets: &Assets<FavoredClassAsset>) -> Vec<&FavoredClass> {
+    assets
+        .iter()
+        .flat_map(|(_, asset)| asset.favored_classes.iter())
+        .collect()
+}
+
+fn spawn_title(parent: &mut ChildBuilder, font: &Handle<Font>, title: &str) {
+    parent.spawn(TextBundle::from_section(
+        title,
+        TextStyle {
+            font: font.clone(),
+            font_size: LIST_TITLE_TEXT_SIZE,
+            color: PANEL_TITLE_COLOR,
+        },
+    ));
+}
+
+fn spawn_row(parent: &mut ChildBuilder, row: impl FnOnce(&mut ChildBuilder)) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_wrap: FlexWrap::Wrap,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(row);
+}
+
+fn spawn_choice_button(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    label: String,
+    choice: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(5.)),
+                    max_width: Val::Px(DESCRIPTION_MAX_WIDTH),
+                    ..default()
+                },
+                background_color: RACE_BUTTON_COLOR.into(),
+                ..default()
+            },
+            choice,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: LIST_BUTTON_TEXT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            ));
+        });
+}
+
+// Fills the favored class tab with the summary, a button for every class,
+// an empty node for the per-level bonuses and the maneuver buttons used by
+// CMD bonuses.
+pub fn setup_favored_class_tab(
+    query_parent: Query<(Entity, &TabListParent)>,
+    asset_server: Res<AssetServer>,
+    mut chosen: ResMut<ChosenFavoredClasses>,
+    mut commands: Commands,
+) {
+    *chosen = ChosenFavoredClasses::default();
+    let Some((parent, _)) = query_parent
+        .iter()
+        .find(|(_, list_parent)| **list_parent == TabListParent::FavoredClass)
+    else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(parent).with_children(|list| {
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(DESCRIPTION_MAX_WIDTH),
+                ..default()
+            }),
+            FavoredClassSummaryText,
+        ));
+        spawn_title(list, &shared_font, "Favored Class");
+        spawn_row(list, |row| {
+            for class in PlayableClass::array() {
+                spawn_choice_button(
+                    row,
+                    &shared_font,
+                    class.to_string(),
+                    FavoredClassButton(class),
+                );
+            }
+        });
+        spawn_title(list, &shared_font, "Bonus per Level");
+        list.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            FavoredBonusList,
+        ));
+        list.spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            FavoredManeuverRow,
+        ))
+        .with_children(|maneuvers| {
+            spawn_title(maneuvers, &shared_font, "Combat Maneuvers");
+            spawn_row(maneuvers, |row| {
+                for maneuver in CombatManeuverName::array() {
+                    spawn_choice_button(
+                        row,
+                        &shared_font,
+                        maneuver.to_string(),
+                        FavoredManeuverButton(maneuver),
+                    );
+                }
+            });
+        });
+    });
+}
+
+// Clicking a class makes it favored, or drops it if it already was. Bonus
+// buttons pick the bonus for their level, and maneuver buttons pick or drop
+// a maneuver. Picks past what the race or bonuses allow are ignored.
+pub fn choose_favored_class(
+    query_class: Query<(&Interaction, &FavoredClassButton), Changed<Interaction>>,
+    query_bonus: Query<(&Interaction, &FavoredBonusButton), Changed<Interaction>>,
+    query_maneuver: Query<(&Interaction, &FavoredManeuverButton), Changed<Interaction>>,
+    race_builder: Res<RaceBuilder>,
+    selected_race: Res<SelectedRace>,
+    assets: Res<Assets<FavoredClassAsset>>,
+    mut chosen: ResMut<ChosenFavoredClasses>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+    let allowed = favored_class_count(race_builder.inner());
+    for (_, button) in query_class.iter().filter(|(i, _)| pressed(i)) {
+        let classes = &mut chosen.0.classes;
+        if let Some(index) = classes.iter().position(|class| *class == button.0) {
+            classes.remove(index);
+        } else if classes.len() < allowed {
+            classes.push(button.0);
+        }
+    }
+    for (_, button) in query_bonus.iter().filter(|(i, _)| pressed(i)) {
+        if let Some((_, choice)) = chosen.0.choices.get_mut(button.slot) {
+            *choice = button.choice;
+        }
+    }
+    let needed = maneuvers_needed(&alternates_taken(
+        &chosen.0,
+        &all_options(&assets),
+        &selected_race.inner(),
+    ));
+    for (_, button) in query_maneuver.iter().filter(|(i, _)| pressed(i)) {
+        let maneuvers = &mut chosen.0.maneuvers;
+        if let Some(index) = maneuvers.iter().position(|maneuver| *maneuver == button.0) {
+            maneuvers.remove(index);
+        } else if maneuvers.len() < needed {
+            maneuvers.push(button.0);
+        }
+    }
+}
+
+type FavoredClassBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static ClassLevels>,
+        Option<&'static FavoredClasses>,
+        Option<&'static FavoredClassIssues>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Keeps `FavoredClasses`, its issues and the alternate bonus effects on the
+// builder in line with the picks. Favored classes past what the race allows,
+// picks for levels no longer taken and maneuvers no longer needed are
+// dropped. Until the builder has class levels it counts as first level in
+// the selected class.
+#[allow(clippy::too_many_arguments)]
+pub fn update_favored_classes(
+    query_builder: FavoredClassBuilderQuery,
+    query_effects: Query<(Entity, &Effect)>,
+    race_builder: Res<RaceBuilder>,
+    selected_race: Res<SelectedRace>,
+    selected_class: Res<SelectedClass>,
+    assets: Res<Assets<FavoredClassAsset>>,
+    mut chosen: ResMut<ChosenFavoredClasses>,
+    mut commands: Commands,
+) {
+    let Ok((builder, class_levels, favored, old_issues)) = query_builder.get_single() else {
+        return;
+    };
+    let race = selected_race.inner();
+    let allowed = favored_class_count(race_builder.inner());
+    let mut picks = chosen.0.clone();
+    picks.classes.truncate(allowed);
+    let levels = favored_levels(&picks.classes, |class| {
+        let level = class_levels.map_or(0, |levels| levels.level(class));
+        if *class == selected_class.inner() {
+            level.max(1)
+        } else {
+            level
+        }
+    });
+    picks.fit_to_levels(&levels);
+    let needed = maneuvers_needed(&alternates_taken(&picks, &all_options(&assets), &race));
+    picks.maneuvers.truncate(needed);
+    if picks != chosen.0 {
+        chosen.0 = picks;
+    }
+    let issues = FavoredClassIssues(favored_class_issues(
+        &chosen.0,
+        allowed,
+        &all_options(&assets),
+        &race,
+    ));
+    if old_issues != Some(&issues) {
+        commands.entity(builder).insert(issues);
+    }
+    if favored == Some(&chosen.0) {
+        return;
+    }
+    for (entity, _) in query_effects.iter().filter(|(_, effect)| {
+        effect.target == builder && matches!(effect.source, EffectSource::FavoredClass(_))
+    }) {
+        commands.entity(entity).despawn();
+    }
+    for (class, bonus, taken) in alternates_taken(&chosen.0, &all_options(&assets), &race) {
+        if let Some(effect) = bonus.and_then(|bonus| {
+            Effect::from_favored_class(builder, class, &bonus, taken, &chosen.0.maneuvers)
+        }) {
+            commands.spawn(effect);
+        }
+    }
+    commands.entity(builder).insert(chosen.0.clone());
+}
+
+// Rebuilds the rows of bonus buttons whenever the favored class levels or
+// the race change, with one row per level offering a hit point, a skill
+// rank or one of the race's alternate bonuses for that class.
+pub fn build_favored_bonus_buttons(
+    query_list: Query<Entity, With<FavoredBonusList>>,
+    chosen: Res<ChosenFavoredClasses>,
+    selected_race: Res<SelectedRace>,
+    assets: Res<Assets<FavoredClassAsset>>,
+    asset_server: Res<AssetServer>,
+    mut built: Local<Option<(PlayableRace, Vec<PlayableClass>)>>,
+    mut commands: Commands,
+) {
+    let Ok(list) = query_list.get_single() else {
+        return;
+    };
+    let levels: Vec<PlayableClass> = chosen.0.choices.iter().map(|(class, _)| *class).collect();
+    let race = selected_race.inner();
+    if built
+        .as_ref()
+        .is_some_and(|(built_race, built_levels)| *built_race == race && *built_levels == levels)
+    {
+        return;
+    }
+    *built = Some((race, levels.clone()));
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(list).despawn_descendants();
+    commands.entity(list).with_children(|list| {
+        for (slot, class) in levels.iter().enumerate() {
+            let level = levels[..=slot].iter().filter(|c| *c == class).count();
+            spawn_row(list, |row| {
+                row.spawn(TextBundle::from_section(
+                    format!("{class} level {level}:"),
+                    TextStyle {
+                        font: shared_font.clone(),
+                        font_size: LIST_BUTTON_TEXT_SIZE,
+                        color: TEXT_COLOR,
+                    },
+                ));
+                let mut choices = vec![
+                    ("+1 Hit Point".to_string(), FavoredClassChoice::HitPoint),
+                    ("+1 Skill Rank".to_string(), FavoredClassChoice::SkillRank),
+                ];
+                for (index, option) in alternate_options(&all_options(&assets), &race, class)
+                    .iter()
+                    .enumerate()
+                {
+                    choices.push((
+                        option.description.clone(),
+                        FavoredClassChoice::Alternate(index),
+                    ));
+                }
+                for (label, choice) in choices {
+                    spawn_choice_button(
+                        row,
+                        &shared_font,
+                        label,
+                        FavoredBonusButton { slot, choice },
+                    );
+                }
+            });
+        }
+    });
+}
+
+type FavoredButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut BackgroundColor,
+        Option<&'static FavoredClassButton>,
+        Option<&'static FavoredBonusButton>,
+        Option<&'static FavoredManeuverButton>,
+    ),
+    Or<(
+        With<FavoredClassButton>,
+        With<FavoredBonusButton>,
+        With<FavoredManeuverButton>,
+    )>,
+>;
+
+// Highlights the picks, shows the maneuvers when a CMD bonus is taken, and
+// sums up the bonuses above the list with any issues in red.
+pub fn display_favored_classes(
+    chosen: Res<ChosenFavoredClasses>,
+    selected_race: Res<SelectedRace>,
+    assets: Res<Assets<FavoredClassAsset>>,
+    query_issues: Query<&FavoredClassIssues, With<CharacterBuilder>>,
+    mut query_buttons: FavoredButtonQuery,
+    mut query_maneuver_row: Query<&mut Style, With<FavoredManeuverRow>>,
+    mut query_text: Query<&mut Text, With<FavoredClassSummaryText>>,
+) {
+    for (mut background_color, class, bonus, maneuver) in query_buttons.iter_mut() {
+        let picked = class.is_some_and(|button| chosen.0.classes.contains(&button.0))
+            || bonus.is_some_and(|button| {
+                chosen
+                    .0
+                    .choices
+                    .get(button.slot)
+                    .is_some_and(|(_, choice)| *choice == button.choice)
+            })
+            || maneuver.is_some_and(|button| chosen.0.maneuvers.contains(&button.0));
+        let color = if picked {
+            RACE_BUTTON_COLOR_SELECTED
+        } else {
+            RACE_BUTTON_COLOR
+        };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+    let needed = maneuvers_needed(&alternates_taken(
+        &chosen.0,
+        &all_options(&assets),
+        &selected_race.inner(),
+    ));
+    if let Ok(mut style) = query_maneuver_row.get_single_mut() {
+        let display = if needed > 0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+    let (Ok(issues), Ok(mut text)) = (query_issues.get_single(), query_text.get_single_mut())
+    else {
+        return;
+    };
+    let mut value = format!(
+        "Favored class bonuses: +{} hit points, +{} skill ranks",
+        chosen.0.hit_points(),
+        chosen.0.skill_ranks()
+    );
+    for issue in issues.0.iter() {
+        value.push_str(&format!("\n{issue}"));
+    }
+    let color = if issues.0.is_empty() {
+        TEXT_COLOR
+    } else {
+        Color::RED
+    };
+    if text
+        .sections
+        .first()
+        .is_some_and(|section| section.value != value)
+    {
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+}
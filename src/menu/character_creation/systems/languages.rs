@@ -0,0 +1,206 @@
+use crate::menu::character_creation::components::*;
+use crate::menu::styles::*;
+use crate::systems::game::{
+    character::{AbilityScores, BaseLanguages, Language},
+    languages::{BonusLanguages, KnownLanguages},
+    race::CharacterBuilder,
+    skills::SkillRanks,
+};
+use bevy::prelude::*;
+
+type LanguageBuilderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static BaseLanguages>,
+        Option<&'static AbilityScores>,
+        Option<&'static SkillRanks>,
+        Option<&'static KnownLanguages>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+// Fills the languages tab with a summary and a button for every language.
+// Buttons are shown by `display_languages` once the race offers them.
+pub fn setup_language_tab(
+    query_parent: Query<(Entity, &TabListParent)>,
+    asset_server: Res<AssetServer>,
+    mut chosen: ResMut<ChosenLanguages>,
+    mut commands: Commands,
+) {
+    chosen.0.clear();
+    let Some((parent, _)) = query_parent
+        .iter()
+        .find(|(_, list_parent)| **list_parent == TabListParent::Languages)
+    else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(parent).with_children(|list| {
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font.clone(),
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_style(Style {
+                width: Val::Percent(100.),
+                ..default()
+            }),
+            LanguageSummaryText,
+            Name::from("Language summary"),
+        ));
+        for language in Language::array() {
+            list.spawn((
+                ButtonBundle {
+                    style: Style {
+                        display: Display::None,
+                        padding: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    },
+                    background_color: RACE_BUTTON_COLOR.into(),
+                    ..default()
+                },
+                LanguageButton(language),
+                Name::from("Language button"),
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    language.to_string(),
+                    TextStyle {
+                        font: shared_font.clone(),
+                        font_size: LIST_BUTTON_TEXT_SIZE,
+                        color: TEXT_COLOR,
+                    },
+                ));
+            });
+        }
+    });
+}
+
+// Clicking a language picks it, or drops it if it was already picked. A pick
+// past what Intelligence and Linguistics allow is ignored, and nothing can be
+// picked before the builder has ability scores.
+pub fn choose_language(
+    query_button: Query<(&Interaction, &LanguageButton), Changed<Interaction>>,
+    query_builder: LanguageBuilderQuery,
+    selected_class: Res<SelectedClass>,
+    mut chosen: ResMut<ChosenLanguages>,
+) {
+    let Ok((_, Some(base), Some(scores), ranks, _)) = query_builder.get_single() else {
+        return;
+    };
+    let bonus = BonusLanguages::new(Some(scores), ranks);
+    for (_, button) in query_button
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+    {
+        if let Some(index) = chosen.0.iter().position(|language| *language == button.0) {
+            chosen.0.remove(index);
+            continue;
+        }
+        let mut picks = chosen.0.clone();
+        picks.push(button.0);
+        if bonus.allows(base, &selected_class.inner(), &picks) {
+            chosen.0 = picks;
+        }
+    }
+}
+
+// Keeps `KnownLanguages` on the builder in line with the race, class and
+// picks. Picks a newly chosen race, class or score no longer allows are
+// dropped, latest first.
+pub fn update_known_languages(
+    query_builder: LanguageBuilderQuery,
+    selected_class: Res<SelectedClass>,
+    mut chosen: ResMut<ChosenLanguages>,
+    mut commands: Commands,
+) {
+    let Ok((entity, Some(base), scores, ranks, known)) = query_builder.get_single() else {
+        return;
+    };
+    let class = selected_class.inner();
+    let bonus = BonusLanguages::new(scores, ranks);
+    let mut picks = chosen.0.clone();
+    while !bonus.allows(base, &class, &picks) {
+        picks.pop();
+    }
+    if picks != chosen.0 {
+        chosen.0 = picks;
+    }
+    let new_known = KnownLanguages::new(base, &class, &chosen.0);
+    if known != Some(&new_known) {
+        commands.entity(entity).insert(new_known);
+    }
+}
+
+// Shows the languages that can be picked, highlighting the picked ones, and
+// the known languages with the picks left above them.
+pub fn display_languages(
+    query_builder: LanguageBuilderQuery,
+    selected_class: Res<SelectedClass>,
+    chosen: Res<ChosenLanguages>,
+    mut query_button: Query<(&mut Style, &mut BackgroundColor, &LanguageButton)>,
+    mut query_text: Query<&mut Text, With<LanguageSummaryText>>,
+) {
+    let Ok((_, base, scores, ranks, known)) = query_builder.get_single() else {
+        return;
+    };
+    let class = selected_class.inner();
+    let bonus = BonusLanguages::new(scores, ranks);
+    for (mut style, mut background_color, button) in query_button.iter_mut() {
+        let offered = base.is_some_and(|base| bonus.offers(base, &class, &button.0));
+        let display = if offered {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let color = if chosen.0.contains(&button.0) {
+            RACE_BUTTON_COLOR_SELECTED
+        } else {
+            RACE_BUTTON_COLOR
+        };
+        // Only written when different, so the UI isn't re-laid out every frame.
+        if style.display != display {
+            style.display = display;
+        }
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+    if let Ok(mut text) = query_text.get_single_mut() {
+        let known = known.map_or(String::new(), |known| {
+            known
+                .0
+                .iter()
+                .map(|language| language.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        });
+        let value = match scores {
+            Some(_) => format!(
+                "Known languages: {known}\n\
+                Bonus languages: {} of {} picked ({} from Intelligence, {} from Linguistics)",
+                chosen.0.len(),
+                bonus.total(),
+                bonus.intelligence,
+                bonus.linguistics,
+            ),
+            None => format!(
+                "Known languages: {known}\n\
+                Bonus languages can be picked once ability scores are set"
+            ),
+        };
+        if text
+            .sections
+            .first()
+            .is_some_and(|section| section.value != value)
+        {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -1,8 +1,15 @@
+pub mod alignment;
 pub mod archetype;
+pub mod character_traits;
 pub mod display_central;
+pub mod equipment;
+pub mod favored_class;
+pub mod languages;
 pub mod left_panel;
 pub mod race_tab;
+pub mod randomize;
 pub mod right_panel;
 pub mod select_tab;
 pub mod setup;
 pub mod tooltip;
+pub mod wizard;
@@ -1,7 +1,15 @@
 use crate::menu::character_creation::components::*;
 use crate::menu::styles::*;
-use crate::systems::game::character::{CharacterSize, CreatureSubtypes, CreatureType, GroundSpeed};
-use crate::systems::game::race::{CharacterBuilder, RaceBuilder};
+use crate::systems::game::character::{
+    CharacterSize, CharacterWeaponProficiency, CreatureSubtypes, CreatureType,
+    FloatingAbilityBonus, FloatingAbilityBonuses, FloatingBonusFeat, FloatingBonusFeats,
+    FloatingSkillBonus, FloatingSkillBonuses, GroundSpeed,
+};
+use crate::systems::game::effects::Effect;
+use crate::systems::game::race::{
+    BuiltRacialTraits, CharacterBuilder, IntoComponentBuilder, IntoVecBuilder, RaceBuilder,
+    RacialComponents,
+};
 use crate::systems::layout::character_creation::COMMON_TRAIT_FONT_SIZE;
 use crate::technical::alternate_traits::AltTraitAsset;
 use crate::{
@@ -555,10 +563,63 @@ pub fn update_common_traits_display(
         }
     }
 }
-pub fn reset_race(builder: Query<Entity, With<CharacterBuilder>>, mut commands: Commands) {
-    let race = builder.get_single().unwrap();
-    commands.get_entity(race).unwrap().despawn();
-    commands.spawn(CharacterBuilder);
+type ResetRaceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static BuiltRacialTraits>,
+        Option<&'static mut FloatingBonusFeats>,
+        Option<&'static mut FloatingAbilityBonuses>,
+        Option<&'static mut FloatingSkillBonuses>,
+        Option<&'static CharacterWeaponProficiency>,
+    ),
+    With<CharacterBuilder>,
+>;
+
+fn remove_one<T: PartialEq>(vec: &mut Vec<T>, item: &T) {
+    if let Some(index) = vec.iter().position(|existing| existing == item) {
+        vec.remove(index);
+    }
+}
+
+// Removes what the previous race gave the builder, leaving everything else on
+// it in place. Its bonuses are effects, and the floating bonuses and weapon
+// proficiency it added are taken back out one by one.
+pub fn reset_race(
+    mut builder: ResetRaceQuery,
+    query_effects: Query<(Entity, &Effect)>,
+    mut commands: Commands,
+) {
+    let (builder, built, mut feats, mut abilities, mut skills, proficiency) =
+        builder.get_single_mut().unwrap();
+    for (entity, _) in query_effects
+        .iter()
+        .filter(|(_, effect)| effect.target == builder && effect.source.is_racial_trait())
+    {
+        commands.entity(entity).despawn();
+    }
+    commands.entity(builder).remove::<RacialComponents>();
+    for name in built.iter().flat_map(|built| built.0.iter()) {
+        if let (Some(feats), Ok(feat)) = (feats.as_mut(), FloatingBonusFeat::from_name(name)) {
+            remove_one(&mut feats.0, &feat);
+        }
+        if let (Some(abilities), Ok(ability)) =
+            (abilities.as_mut(), FloatingAbilityBonus::from_name(name))
+        {
+            remove_one(&mut abilities.0, &ability);
+        }
+        if let (Some(skills), Ok(skill)) = (skills.as_mut(), FloatingSkillBonus::from_name(name)) {
+            remove_one(&mut skills.0, &skill);
+        }
+        if CharacterWeaponProficiency::from_name(name)
+            .is_ok_and(|racial| proficiency == Some(&racial))
+        {
+            commands
+                .entity(builder)
+                .remove::<CharacterWeaponProficiency>();
+        }
+    }
 }
 fn build_text(string: String, font: Handle<Font>) -> Text {
     // use crate::systems::layout::character_creation::COMMON_TRAIT_FONT_SIZE;
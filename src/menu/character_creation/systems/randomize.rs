@@ -0,0 +1,230 @@
+use crate::menu::character_creation::components::*;
+use crate::menu::components::SelectedWrapper;
+use crate::systems::game::{
+    archetype::ArchetypeMap,
+    character::PointBuy,
+    class::ClassMap,
+    dice::GameRng,
+    race::{CharacterBuilder, RaceBuilder},
+    random_character::{random_character, RandomCatalogs, RandomLocks, ScoreMethod},
+};
+use crate::technical::{
+    alternate_traits::AltTraitAsset, deity::DeityAsset, equipment::ShopCatalogAsset,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+// The current choices and the catalogs a random character is drawn from.
+#[derive(SystemParam)]
+pub struct RandomSources<'w> {
+    selected_race: Res<'w, SelectedRace>,
+    selected_class: Res<'w, SelectedClass>,
+    selected_archetype: Res<'w, SelectedArchetype>,
+    class_map: Res<'w, ClassMap>,
+    archetype_map: Res<'w, ArchetypeMap>,
+    alternate_traits: Res<'w, Assets<AltTraitAsset>>,
+    deities: Res<'w, Assets<DeityAsset>>,
+    shop_catalogs: Res<'w, Assets<ShopCatalogAsset>>,
+}
+
+// Point buys the score method button steps through after rolled scores.
+const POINT_BUYS: [i32; 4] = [10, 15, 20, 25];
+
+fn next_method(method: &ScoreMethod) -> ScoreMethod {
+    match method {
+        ScoreMethod::Roll => ScoreMethod::PointBuy(POINT_BUYS[0]),
+        ScoreMethod::PointBuy(points) => POINT_BUYS
+            .iter()
+            .find(|next| *next > points)
+            .map_or(ScoreMethod::Roll, |next| ScoreMethod::PointBuy(*next)),
+    }
+}
+
+// The lock and score method buttons step through their options. Their
+// markers are on the buttons' text, so the press is read from the parent.
+pub fn randomize_options(
+    query_lock: Query<&Parent, With<RandomLockButton>>,
+    query_method: Query<&Parent, With<ScoreMethodButton>>,
+    query_interaction: Query<&Interaction, Changed<Interaction>>,
+    mut randomizer: ResMut<Randomizer>,
+) {
+    let pressed = |parent: &Parent| {
+        query_interaction
+            .get(parent.get())
+            .is_ok_and(|interaction| *interaction == Interaction::Pressed)
+    };
+    if query_lock.iter().any(pressed) {
+        randomizer.lock = randomizer.lock.next();
+    }
+    if query_method.iter().any(pressed) {
+        randomizer.method = next_method(&randomizer.method);
+    }
+}
+
+// Makes a random character from a new seed, keeping what the lock names.
+// Its race, class and archetype are selected the way the left panel does,
+// with the race last, since only the last race event is read.
+pub fn randomize(
+    query_button: Query<&Parent, With<RandomizeButton>>,
+    query_interaction: Query<&Interaction, Changed<Interaction>>,
+    sources: RandomSources,
+    mut rng: ResMut<GameRng>,
+    mut randomizer: ResMut<Randomizer>,
+    mut left_panel_event: EventWriter<LeftPanelEvent>,
+) {
+    let pressed = query_button.iter().any(|parent| {
+        query_interaction
+            .get(parent.get())
+            .is_ok_and(|interaction| *interaction == Interaction::Pressed)
+    });
+    if !pressed {
+        return;
+    }
+    let (race, class, archetype) = match randomizer.lock {
+        RandomLock::Nothing => (false, false, false),
+        RandomLock::Race => (true, false, false),
+        RandomLock::Class => (false, true, false),
+        RandomLock::RaceAndClass => (true, true, false),
+        RandomLock::RaceClassAndArchetype => (true, true, true),
+    };
+    let locks = RandomLocks {
+        race: race.then(|| sources.selected_race.inner()),
+        class: class.then(|| sources.selected_class.inner()),
+        archetype: archetype.then(|| sources.selected_archetype.inner()),
+    };
+    let archetypes: Vec<_> = sources.archetype_map.inner_ref().values().collect();
+    let alternate_traits: Vec<_> = sources
+        .alternate_traits
+        .iter()
+        .map(|(_, asset)| asset)
+        .collect();
+    let deities: Vec<_> = sources.deities.iter().map(|(_, asset)| asset).collect();
+    let items: Vec<_> = sources
+        .shop_catalogs
+        .iter()
+        .flat_map(|(_, catalog)| catalog.items.iter())
+        .collect();
+    let catalogs = RandomCatalogs {
+        class_map: &sources.class_map,
+        archetypes: &archetypes,
+        alternate_traits: &alternate_traits,
+        deities: &deities,
+        items: &items,
+    };
+    let seed = rng.next_seed();
+    let character = random_character(seed, &locks, randomizer.method, &catalogs);
+
+    let exit_class: LeftPanelEvent = sources.selected_class.selected().into();
+    left_panel_event.send(exit_class.set_status(Status::Exiting));
+    let class: LeftPanelEvent = character.class.into();
+    left_panel_event.send(class.set_status(Status::Entering));
+    if let Some(archetype) = character.archetype {
+        let exit_archetype: LeftPanelEvent = sources.selected_archetype.selected().into();
+        left_panel_event.send(exit_archetype.set_status(Status::Exiting));
+        let archetype: LeftPanelEvent = archetype.into();
+        left_panel_event.send(archetype.set_status(Status::Entering));
+    }
+    let exit_race: LeftPanelEvent = sources.selected_race.selected().into();
+    left_panel_event.send(exit_race.set_status(Status::Exiting));
+    let race: LeftPanelEvent = character.race.into();
+    left_panel_event.send(race.set_status(Status::Entering));
+
+    randomizer.seed = Some(seed);
+    randomizer.pending = Some(character);
+}
+
+// Swaps the random character's alternate traits in after the race builder
+// was reset to the race's default traits.
+pub fn apply_random_race(
+    randomizer: Res<Randomizer>,
+    selected_race: Res<SelectedRace>,
+    mut race_builder: ResMut<RaceBuilder>,
+) {
+    let Some(character) = randomizer.pending.as_ref() else {
+        return;
+    };
+    if selected_race.inner() == character.race && *race_builder.inner() != character.racial_traits {
+        *race_builder.inner_mut() = character.racial_traits.clone();
+    }
+}
+
+// Once the random character's race is built and its class and archetype are
+// selected, puts the rest of it on the builder and into the choices of the
+// other tabs. Traits aren't randomized, so the old ones are dropped.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_random_character(
+    query_builder: Query<Entity, With<CharacterBuilder>>,
+    selected_race: Res<SelectedRace>,
+    selected_class: Res<SelectedClass>,
+    selected_archetype: Res<SelectedArchetype>,
+    race_builder: Res<RaceBuilder>,
+    mut randomizer: ResMut<Randomizer>,
+    mut chosen_languages: ResMut<ChosenLanguages>,
+    mut chosen_alignment: ResMut<ChosenAlignment>,
+    mut chosen_traits: ResMut<ChosenTraits>,
+    mut chosen_favored: ResMut<ChosenFavoredClasses>,
+    mut chosen_equipment: ResMut<ChosenEquipment>,
+    mut chosen_feats: ResMut<ChosenProficiencyFeats>,
+    mut commands: Commands,
+) {
+    let Some(character) = randomizer.pending.as_ref() else {
+        return;
+    };
+    let ready = selected_race.inner() == character.race
+        && selected_class.inner() == character.class
+        && character
+            .archetype
+            .is_none_or(|archetype| selected_archetype.inner() == archetype)
+        && *race_builder.inner() == character.racial_traits;
+    if !ready {
+        return;
+    }
+    let Ok(builder) = query_builder.get_single() else {
+        return;
+    };
+    let mut builder = commands.entity(builder);
+    builder.insert((
+        character.scores.clone(),
+        character.floating_picks.clone(),
+        character.skill_ranks.clone(),
+    ));
+    match character.point_buy {
+        Some(point_buy) => builder.insert(point_buy),
+        None => builder.remove::<PointBuy>(),
+    };
+    chosen_languages.0 = character.languages.clone();
+    *chosen_alignment = ChosenAlignment {
+        alignment: Some(character.alignment),
+        deity: character.deity.clone(),
+        domains: character.domains.clone(),
+    };
+    chosen_traits.0.clear();
+    chosen_favored.0 = character.favored.clone();
+    *chosen_equipment = ChosenEquipment {
+        rolled: Some((character.class, character.starting_wealth)),
+        purchases: character.purchases.clone(),
+    };
+    chosen_feats.0 = character.feats.0.clone();
+    randomizer.pending = None;
+}
+
+pub fn display_randomizer(
+    randomizer: Res<Randomizer>,
+    mut query_lock: Query<&mut Text, (With<RandomLockButton>, Without<ScoreMethodButton>)>,
+    mut query_method: Query<&mut Text, (With<ScoreMethodButton>, Without<RandomLockButton>)>,
+) {
+    let texts = query_lock
+        .iter_mut()
+        .map(|text| (text, randomizer.lock.to_string()))
+        .chain(
+            query_method
+                .iter_mut()
+                .map(|text| (text, randomizer.method.to_string())),
+        );
+    for (mut text, value) in texts {
+        if let Some(section) = text.sections.first_mut() {
+            if section.value != value {
+                section.value = value;
+            }
+        }
+    }
+}
@@ -274,3 +274,27 @@ pub fn display_subtab_buttons(
         }
     }
 }
+
+// Leaves the selected tab for `tab`, sending the same events as
+// `tab_button_select`.
+pub fn change_tab(
+    entity: Entity,
+    tab: Tab,
+    selected: &mut SelectedTab,
+    tab_event_writer: &mut EventWriter<SelectTabEvent>,
+) {
+    if selected.selected() == tab {
+        return;
+    }
+    tab_event_writer.send(SelectTabEvent {
+        entity,
+        tab: selected.selected(),
+        tab_state: InTab::Exiting,
+    });
+    selected.0 = tab;
+    tab_event_writer.send(SelectTabEvent {
+        entity,
+        tab,
+        tab_state: InTab::Entering,
+    });
+}
@@ -2,17 +2,22 @@ use crate::{
     menu::character_creation::{components::*, constants::*},
     systems::game::character::PlayableRace,
     technical::{
-        alternate_traits::AltTraitAsset, class::ClassAsset, default_race_traits::DefaultTraitAsset,
+        alternate_traits::AltTraitAsset, character_traits::TraitCatalogAsset, class::ClassAsset,
+        default_race_traits::DefaultTraitAsset, deity::DeityAsset, equipment::ShopCatalogAsset,
         is_custom_asset_loaded::CustomAssetLoadState, race_load::RaceAsset,
     },
 };
 use bevy::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn setup_assets(
     mut races_asset_struct: ResMut<CustomAssetLoadState<RaceAsset>>,
     mut default_trait_struct: ResMut<CustomAssetLoadState<DefaultTraitAsset>>,
     mut alt_trait_struct: ResMut<CustomAssetLoadState<AltTraitAsset>>,
     mut class_asset_struct: ResMut<CustomAssetLoadState<ClassAsset>>,
+    mut deity_asset_struct: ResMut<CustomAssetLoadState<DeityAsset>>,
+    mut trait_catalog_struct: ResMut<CustomAssetLoadState<TraitCatalogAsset>>,
+    mut shop_catalog_struct: ResMut<CustomAssetLoadState<ShopCatalogAsset>>,
     asset_server: Res<AssetServer>,
 ) {
     let finding_assets = asset_server.load_folder(RACE_DESCRIPTION_FOLDER);
@@ -39,6 +44,24 @@ pub fn setup_assets(
             class_asset_struct.add_untyped(&handle);
         }
     }
+    let finding_assets = asset_server.load_folder(DEITY_DESCRIPTIONS_FOLDER);
+    if let Ok(found_assets) = finding_assets {
+        for handle in found_assets {
+            deity_asset_struct.add_untyped(&handle);
+        }
+    }
+    let finding_assets = asset_server.load_folder(TRAIT_DESCRIPTIONS_FOLDER);
+    if let Ok(found_assets) = finding_assets {
+        for handle in found_assets {
+            trait_catalog_struct.add_untyped(&handle);
+        }
+    }
+    let finding_assets = asset_server.load_folder(EQUIPMENT_FOLDER);
+    if let Ok(found_assets) = finding_assets {
+        for handle in found_assets {
+            shop_catalog_struct.add_untyped(&handle);
+        }
+    }
 }
 
 #[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd)]
@@ -0,0 +1,585 @@
+use crate::menu::character_creation::{
+    components::*,
+    layout::generics::list_traits::AsVec,
+    systems::{character_traits::find_traits, favored_class::all_options, select_tab::change_tab},
+};
+use crate::menu::components::{SelectedWrapper, StagesOfCreationButton};
+use crate::menu::styles::*;
+use crate::systems::game::{
+    archetype::ArchetypeMap,
+    character::*,
+    character_traits::CharacterTraits,
+    class::ClassMap,
+    deity::{Deity, Domains},
+    favored_class::FavoredClasses,
+    languages::KnownLanguages,
+    proficiency::ProficiencyFeats,
+    race::{CharacterBuilder, RaceBuilder},
+    skills::SkillRanks,
+    validation::{validate, CharacterRecord, CreationStage, Rules, Severity},
+};
+use crate::technical::{
+    alternate_traits::AltTraitAsset, character_traits::TraitCatalogAsset, deity::DeityAsset,
+    favored_class::FavoredClassAsset,
+};
+use bevy::{
+    ecs::{query::WorldQuery, system::SystemParam},
+    prelude::*,
+};
+use std::collections::HashMap;
+
+// Everything on the builder the validator reads.
+#[derive(WorldQuery)]
+pub struct ValidationQuery {
+    class_levels: Option<&'static ClassLevels>,
+    scores: Option<&'static AbilityScores>,
+    ability_bonuses: Option<&'static AbilityScoreBonuses>,
+    point_buy: Option<&'static PointBuy>,
+    skill_ranks: Option<&'static SkillRanks>,
+    bonus_skills: Option<&'static BonusSkillPerLevel>,
+    floating_abilities: Option<&'static FloatingAbilityBonuses>,
+    floating_skills: Option<&'static FloatingSkillBonuses>,
+    floating_feats: Option<&'static FloatingBonusFeats>,
+    floating_picks: Option<&'static FloatingBonusPicks>,
+    feats: Option<&'static ProficiencyFeats>,
+    base_languages: Option<&'static BaseLanguages>,
+    known_languages: Option<&'static KnownLanguages>,
+    alignment: Option<&'static Alignment>,
+    deity: Option<&'static Deity>,
+    domains: Option<&'static Domains>,
+    favored: Option<&'static FavoredClasses>,
+    traits: Option<&'static CharacterTraits>,
+}
+
+// The choices made so far and the catalogs the builder is checked against.
+#[derive(SystemParam)]
+pub struct ValidationSources<'w> {
+    selected_race: Res<'w, SelectedRace>,
+    selected_class: Res<'w, SelectedClass>,
+    selected_archetype: Res<'w, SelectedArchetype>,
+    race_builder: Res<'w, RaceBuilder>,
+    class_map: Res<'w, ClassMap>,
+    archetype_map: Res<'w, ArchetypeMap>,
+    alternate_traits: Res<'w, Assets<AltTraitAsset>>,
+    deities: Res<'w, Assets<DeityAsset>>,
+    trait_catalogs: Res<'w, Assets<TraitCatalogAsset>>,
+    favored_options: Res<'w, Assets<FavoredClassAsset>>,
+}
+
+// Fills the review tab with the summary of every stage.
+pub fn setup_review_tab(
+    query_parent: Query<(Entity, &TabListParent)>,
+    asset_server: Res<AssetServer>,
+    mut stages: ResMut<StageIssues>,
+    mut issues: ResMut<CharacterIssues>,
+    mut step: ResMut<WizardStep>,
+    mut commands: Commands,
+) {
+    *stages = StageIssues::default();
+    *step = WizardStep::default();
+    *issues = CharacterIssues::default();
+    let Some((parent, _)) = query_parent
+        .iter()
+        .find(|(_, list_parent)| **list_parent == TabListParent::Review)
+    else {
+        return;
+    };
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.entity(parent).with_children(|list| {
+        list.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: shared_font,
+                    font_size: DESCRIPTION_FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(DESCRIPTION_MAX_WIDTH),
+                ..default()
+            }),
+            ReviewSummaryText,
+        ));
+    });
+}
+
+// Runs the validator over the builder. Until the builder has class levels it
+// counts as first level in the selected class.
+pub fn validate_builder(
+    query_builder: Query<ValidationQuery, With<CharacterBuilder>>,
+    sources: ValidationSources,
+    mut issues: ResMut<CharacterIssues>,
+) {
+    let Ok(item) = query_builder.get_single() else {
+        return;
+    };
+    let class_levels = item
+        .class_levels
+        .cloned()
+        .unwrap_or_else(|| ClassLevels::from(HashMap::from([(sources.selected_class.inner(), 1)])));
+    let archetypes: Vec<_> = sources
+        .archetype_map
+        .inner_ref()
+        .get(&sources.selected_archetype.inner())
+        .filter(|info| class_levels.level(&info.class) > 0)
+        .into_iter()
+        .collect();
+    let deity = item.deity.and_then(|deity| {
+        sources
+            .deities
+            .iter()
+            .map(|(_, asset)| asset)
+            .find(|asset| asset.name == deity.0)
+    });
+    let traits = item.traits.map_or(Vec::new(), |traits| {
+        find_traits(&sources.trait_catalogs, &traits.0)
+    });
+    let alternate_traits: Vec<_> = sources
+        .alternate_traits
+        .iter()
+        .flat_map(|(_, asset)| asset.alternate_traits.iter())
+        .collect();
+    let record = CharacterRecord {
+        race: sources.selected_race.inner(),
+        racial_traits: sources.race_builder.inner(),
+        class_levels: &class_levels,
+        archetypes: &archetypes,
+        scores: item.scores,
+        ability_bonuses: item.ability_bonuses,
+        point_buy: item.point_buy,
+        skill_ranks: item.skill_ranks,
+        bonus_skills: item.bonus_skills,
+        floating_abilities: item.floating_abilities,
+        floating_skills: item.floating_skills,
+        floating_feats: item.floating_feats,
+        floating_picks: item.floating_picks,
+        feats: item.feats,
+        base_languages: item.base_languages,
+        known_languages: item.known_languages,
+        alignment: item.alignment,
+        deity,
+        domains: item.domains.map_or(&[], |domains| domains.0.as_slice()),
+        favored: item.favored,
+        traits: &traits,
+    };
+    let rules = Rules {
+        class_map: &sources.class_map,
+        alternate_traits: &alternate_traits,
+        favored_options: &all_options(&sources.favored_options),
+    };
+    let new_issues = CharacterIssues(validate(&record, &rules));
+    if *issues != new_issues {
+        *issues = new_issues;
+    }
+}
+
+// The tab a stage is fixed in. Feats are picked with the proficiencies in
+// the equipment tab. Ability scores and skill ranks have no tab yet and are
+// only set by the Randomize button.
+fn stage_tab(stage: &CreationStage) -> Option<Tab> {
+    match stage {
+        CreationStage::Race => Some(Tab::Race),
+        CreationStage::Class => Some(Tab::Class),
+        CreationStage::Archetype => Some(Tab::Archetype),
+        CreationStage::Feats | CreationStage::BonusFeats => Some(Tab::Equipment),
+        CreationStage::Languages => Some(Tab::Languages),
+        CreationStage::Alignment => Some(Tab::Alignment),
+        CreationStage::Traits => Some(Tab::Traits),
+        CreationStage::FavoredClass => Some(Tab::FavoredClass),
+        CreationStage::AbilityScores | CreationStage::Skills => None,
+    }
+}
+
+// The step of the guided mode a validator stage belongs to.
+fn wizard_stage(stage: &CreationStage) -> StagesOfCreationButton {
+    match stage {
+        CreationStage::Race => StagesOfCreationButton::Race,
+        CreationStage::AbilityScores => StagesOfCreationButton::AbilityScores,
+        CreationStage::Class | CreationStage::Archetype => StagesOfCreationButton::Class,
+        CreationStage::Skills => StagesOfCreationButton::Skills,
+        CreationStage::Feats => StagesOfCreationButton::Feats,
+        CreationStage::BonusFeats => StagesOfCreationButton::BonusFeats,
+        CreationStage::Languages
+        | CreationStage::Alignment
+        | CreationStage::Traits
+        | CreationStage::FavoredClass => StagesOfCreationButton::Optional,
+    }
+}
+
+// The tabs a stage of creation is made in, the first one shown when the
+// guided mode reaches it. Stages without a tab show the review.
+fn stage_tabs(stage: &StagesOfCreationButton) -> &'static [Tab] {
+    match stage {
+        StagesOfCreationButton::Race => &[Tab::Race],
+        StagesOfCreationButton::Class => &[Tab::Class, Tab::Archetype],
+        StagesOfCreationButton::AbilityScores | StagesOfCreationButton::Skills => &[],
+        StagesOfCreationButton::Feats | StagesOfCreationButton::BonusFeats => &[Tab::Equipment],
+        StagesOfCreationButton::Optional => &[
+            Tab::Languages,
+            Tab::Alignment,
+            Tab::Traits,
+            Tab::FavoredClass,
+            Tab::Equipment,
+        ],
+    }
+}
+
+fn step_tab(step: &WizardStep) -> Tab {
+    step.stage()
+        .and_then(|stage| stage_tabs(&stage).first().copied())
+        .unwrap_or(Tab::Review)
+}
+
+fn step_has_tab(step: &WizardStep, tab: &Tab) -> bool {
+    match step.stage() {
+        Some(stage) => stage_tabs(&stage).contains(tab),
+        None => *tab == Tab::Review,
+    }
+}
+
+// The first step made in a tab.
+fn first_step(tab: &Tab) -> WizardStep {
+    (0..WizardStep::count())
+        .map(WizardStep)
+        .find(|step| step_has_tab(step, tab))
+        .unwrap_or(WizardStep(WizardStep::count() - 1))
+}
+
+// The step a tab is picked in: the current one if the tab is part of it,
+// otherwise the first stage made in that tab.
+fn tab_step(tab: &Tab, current: &WizardStep) -> WizardStep {
+    if step_has_tab(current, tab) {
+        *current
+    } else {
+        first_step(tab)
+    }
+}
+
+// Sorts the validator's issues into the stages of creation that fix them.
+pub fn update_stage_issues(issues: Res<CharacterIssues>, mut stages: ResMut<StageIssues>) {
+    let new_stages = StageIssues(
+        StagesOfCreationButton::vec()
+            .into_iter()
+            .map(|stage| {
+                let in_stage = issues
+                    .0
+                    .iter()
+                    .filter(|issue| wizard_stage(&issue.stage()) == stage)
+                    .map(|issue| StageIssue {
+                        tab: stage_tab(&issue.stage()),
+                        text: issue.to_string(),
+                    })
+                    .collect();
+                (stage, in_stage)
+            })
+            .collect(),
+    );
+    if *stages != new_stages {
+        *stages = new_stages;
+    }
+}
+
+fn issue_texts(issues: &[StageIssue], separator: &str) -> String {
+    issues
+        .iter()
+        .map(|issue| issue.text.as_str())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+// Whether the guided mode waits for a stage to be complete. Stages without a
+// tab have no controls to fix them with yet, so they only report their issues.
+fn blocks(stages: &StageIssues, stage: &StagesOfCreationButton) -> bool {
+    !stages.is_complete(stage) && !stage_tabs(stage).is_empty()
+}
+
+// Whether no stage before `step` holds up the guided mode.
+fn reaches(stages: &StageIssues, step: &WizardStep) -> bool {
+    StagesOfCreationButton::vec()
+        .iter()
+        .take(step.0)
+        .all(|stage| !blocks(stages, stage))
+}
+
+// Next is blocked in the guided mode until the current stage is complete.
+fn next_allowed(mode: &CreationMode, stages: &StageIssues, step: &WizardStep) -> bool {
+    step.0 + 1 < WizardStep::count()
+        && (*mode == CreationMode::Expert
+            || step.stage().is_none_or(|stage| !blocks(stages, &stage)))
+}
+
+// In the guided mode the tab buttons only go back to a stage, or forward up
+// to the first one holding it up. The expert mode uses `tab_button_select`.
+#[allow(clippy::type_complexity)]
+pub fn guided_tab_select(
+    query_button: Query<(&Interaction, Entity, &Tab), (Changed<Interaction>, With<Button>)>,
+    stages: Res<StageIssues>,
+    mut step: ResMut<WizardStep>,
+    mut selected: ResMut<SelectedTab>,
+    mut tab_event_writer: EventWriter<SelectTabEvent>,
+) {
+    for (_, entity, tab) in query_button
+        .iter()
+        .filter(|(interaction, _, _)| **interaction == Interaction::Pressed)
+    {
+        let target = tab_step(tab, &step);
+        if reaches(&stages, &target) {
+            *step = target;
+            change_tab(entity, *tab, &mut selected, &mut tab_event_writer);
+        }
+    }
+}
+
+// The Previous and Next buttons step through the stages in order, showing
+// each one's first tab. The markers are on the buttons' text, so the press is
+// read from their parent.
+#[allow(clippy::too_many_arguments)]
+pub fn previous_next_buttons(
+    query_previous: Query<&Parent, With<PreviousButton>>,
+    query_next: Query<&Parent, With<NextButton>>,
+    query_interaction: Query<&Interaction, Changed<Interaction>>,
+    mode: Res<CreationMode>,
+    stages: Res<StageIssues>,
+    mut step: ResMut<WizardStep>,
+    mut selected: ResMut<SelectedTab>,
+    mut tab_event_writer: EventWriter<SelectTabEvent>,
+) {
+    let pressed = |parent: &Parent| {
+        query_interaction
+            .get(parent.get())
+            .is_ok_and(|interaction| *interaction == Interaction::Pressed)
+    };
+    let target = if let Some(parent) = query_previous.iter().find(|parent| pressed(parent)) {
+        step.0
+            .checked_sub(1)
+            .map(|index| (parent.get(), WizardStep(index)))
+    } else if let Some(parent) = query_next.iter().find(|parent| pressed(parent)) {
+        next_allowed(&mode, &stages, &step).then(|| (parent.get(), WizardStep(step.0 + 1)))
+    } else {
+        None
+    };
+    if let Some((entity, target)) = target {
+        *step = target;
+        change_tab(
+            entity,
+            step_tab(&target),
+            &mut selected,
+            &mut tab_event_writer,
+        );
+    }
+}
+
+// Switching back to the guided mode picks up at the stage of the tab the
+// expert mode left open.
+pub fn toggle_creation_mode(
+    query_button: Query<&Parent, With<CreationModeButton>>,
+    query_interaction: Query<&Interaction, Changed<Interaction>>,
+    selected: Res<SelectedTab>,
+    mut mode: ResMut<CreationMode>,
+    mut step: ResMut<WizardStep>,
+) {
+    let pressed = query_button.iter().any(|parent| {
+        query_interaction
+            .get(parent.get())
+            .is_ok_and(|interaction| *interaction == Interaction::Pressed)
+    });
+    if pressed {
+        *mode = match *mode {
+            CreationMode::Guided => CreationMode::Expert,
+            CreationMode::Expert => {
+                *step = tab_step(&selected.selected(), &step);
+                CreationMode::Guided
+            }
+        };
+    }
+}
+
+// Greys out Previous and Next when they can't be used, and shows the
+// current stage with its issues and the mode in the bottom container.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn display_wizard(
+    mode: Res<CreationMode>,
+    stages: Res<StageIssues>,
+    step: Res<WizardStep>,
+    query_previous: Query<&Parent, With<PreviousButton>>,
+    query_next: Query<&Parent, With<NextButton>>,
+    mut query_background: Query<&mut BackgroundColor>,
+    mut query_status: Query<&mut Text, (With<StageStatusText>, Without<CreationModeButton>)>,
+    mut query_mode: Query<&mut Text, (With<CreationModeButton>, Without<StageStatusText>)>,
+) {
+    let buttons = query_previous
+        .iter()
+        .map(|parent| (parent.get(), step.0 > 0))
+        .chain(
+            query_next
+                .iter()
+                .map(|parent| (parent.get(), next_allowed(&mode, &stages, &step))),
+        );
+    for (entity, usable) in buttons {
+        let color = if usable {
+            RACE_BUTTON_COLOR
+        } else {
+            DISABLED_BUTTON_COLOR
+        };
+        if let Ok(mut background_color) = query_background.get_mut(entity) {
+            // Only written when different, so the UI isn't re-laid out every frame.
+            if background_color.0 != color {
+                background_color.0 = color;
+            }
+        }
+    }
+    let (name, status, color) = match step.stage() {
+        Some(stage) if stages.is_complete(&stage) => {
+            (stage.to_string(), "Complete".to_string(), TEXT_COLOR)
+        }
+        Some(stage) if stage_tabs(&stage).is_empty() => (
+            stage.to_string(),
+            format!(
+                "{}\nThere are no controls for this stage yet, so it doesn't hold up Next. The Randomize button fills it in",
+                issue_texts(stages.of(&stage), "\n")
+            ),
+            Color::RED,
+        ),
+        Some(stage) => (
+            stage.to_string(),
+            issue_texts(stages.of(&stage), "\n"),
+            Color::RED,
+        ),
+        None if stages.tab_is_complete(&Tab::Review) => {
+            ("Review".to_string(), "Complete".to_string(), TEXT_COLOR)
+        }
+        None => (
+            "Review".to_string(),
+            "Some stages still have issues".to_string(),
+            Color::RED,
+        ),
+    };
+    let value = format!(
+        "Step {} of {}: {name}\n{status}",
+        step.0 + 1,
+        WizardStep::count()
+    );
+    if let Ok(mut text) = query_status.get_single_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            if section.value != value || section.style.color != color {
+                section.value = value;
+                section.style.color = color;
+            }
+        }
+    }
+    let value = match *mode {
+        CreationMode::Guided => "Guided Mode",
+        CreationMode::Expert => "Expert Mode",
+    };
+    for mut text in query_mode.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            if section.value != value {
+                section.value = value.to_string();
+            }
+        }
+    }
+}
+
+// In the guided mode the tab buttons are numbered by the first stage made in
+// them, in red while an issue is fixed there. The expert mode shows the plain
+// names.
+pub fn display_tab_stages(
+    mode: Res<CreationMode>,
+    stages: Res<StageIssues>,
+    query_tab: Query<(&Tab, &Children), With<Button>>,
+    mut query_text: Query<&mut Text>,
+) {
+    for (tab, children) in query_tab.iter() {
+        let step = first_step(tab).0 + 1;
+        let (value, color) = match *mode {
+            CreationMode::Guided if !stages.tab_is_complete(tab) => {
+                (format!("{step}. {tab}"), Color::RED)
+            }
+            CreationMode::Guided => (format!("{step}. {tab}"), TEXT_COLOR),
+            CreationMode::Expert => (tab.to_string(), TEXT_COLOR),
+        };
+        let mut texts = query_text.iter_many_mut(children.iter());
+        while let Some(mut text) = texts.fetch_next() {
+            if let Some(section) = text.sections.first_mut() {
+                if section.value != value || section.style.color != color {
+                    section.value = value.clone();
+                    section.style.color = color;
+                }
+            }
+        }
+    }
+}
+
+// Lists every stage of creation with its issues, then whether the character
+// is finished and legal. The seed of the last random character comes last.
+pub fn display_review(
+    stages: Res<StageIssues>,
+    issues: Res<CharacterIssues>,
+    randomizer: Res<Randomizer>,
+    mut query_text: Query<&mut Text, With<ReviewSummaryText>>,
+) {
+    let Ok(mut text) = query_text.get_single_mut() else {
+        return;
+    };
+    let mut lines: Vec<(String, Color)> = stages
+        .0
+        .iter()
+        .map(|(stage, issues)| match issues.as_slice() {
+            [] => (format!("{stage}: Complete\n"), TEXT_COLOR),
+            issues => (
+                format!("{stage}: {}\n", issue_texts(issues, "; ")),
+                Color::RED,
+            ),
+        })
+        .collect();
+    let count = |severity: Severity| {
+        issues
+            .0
+            .iter()
+            .filter(|issue| issue.severity() == severity)
+            .count()
+    };
+    lines.push(
+        match (count(Severity::Illegal), count(Severity::Unfinished)) {
+            (0, 0) => (
+                "\nThe character is finished and legal. The character sheet shows all of it."
+                    .to_string(),
+                TEXT_COLOR,
+            ),
+            (illegal, unfinished) => (
+                format!("\n{illegal} illegal and {unfinished} unfinished issues left."),
+                Color::RED,
+            ),
+        },
+    );
+    if let Some(seed) = randomizer.seed {
+        lines.push((format!("\nLast random character seed: {seed}"), TEXT_COLOR));
+    }
+    let unchanged = text.sections.len() == lines.len()
+        && text
+            .sections
+            .iter()
+            .zip(lines.iter())
+            .all(|(section, (value, color))| {
+                section.value == *value && section.style.color == *color
+            });
+    if unchanged {
+        return;
+    }
+    let Some(style) = text.sections.first().map(|section| section.style.clone()) else {
+        return;
+    };
+    text.sections = lines
+        .into_iter()
+        .map(|(value, color)| {
+            TextSection::new(
+                value,
+                TextStyle {
+                    color,
+                    ..style.clone()
+                },
+            )
+        })
+        .collect();
+}
@@ -1 +1,923 @@
+use crate::{
+    menu::{character_creation::components::*, styles::*},
+    systems::game::{
+        archetype::ArchetypeMap,
+        character::*,
+        character_traits::CharacterTraits,
+        class::{ClassFeature, ClassFeatures, ClassMap, PlayableClass},
+        deity::{Deity, Domains},
+        equipment::{Inventory, WieldedWeapon, WornArmor},
+        favored_class::FavoredClasses,
+        languages::KnownLanguages,
+        magic::CastFrequency,
+        proficiency::{Proficiencies, ProficiencyFeats},
+        race::{CharacterBuilder, RaceBuilder, RacialTraitName},
+        skills::{SkillName, SkillRanks},
+        wealth::Wealth,
+    },
+    technical::{
+        alternate_traits::AltTraitAsset, class::ClassAsset, default_race_traits::DefaultTraitAsset,
+    },
+};
+use bevy::{
+    ecs::{query::WorldQuery, system::SystemParam},
+    prelude::*,
+    ui::FocusPolicy,
+};
+use std::collections::{BTreeMap, HashMap};
 
+// Components
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CharacterSheetPanel;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CharacterSheetContent;
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CloseCharacterSheetButton;
+
+// One line on the sheet. Hovering a line with a breakdown shows it, one part
+// per line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SheetEntry {
+    pub label: String,
+    pub value: String,
+    pub breakdown: Vec<String>,
+}
+
+impl SheetEntry {
+    fn text(label: impl ToString, value: impl ToString) -> Self {
+        Self {
+            label: label.to_string(),
+            value: value.to_string(),
+            breakdown: Vec::new(),
+        }
+    }
+    // A number made of `parts`, with conditional bonuses that aren't in the
+    // total listed after it. Bonuses like saves are shown with their sign,
+    // scores like AC without.
+    fn total(
+        label: impl ToString,
+        parts: Vec<(String, i32)>,
+        notes: Vec<String>,
+        signed: bool,
+    ) -> Self {
+        let total: i32 = parts.iter().map(|(_, value)| value).sum();
+        let mut breakdown: Vec<String> = parts
+            .iter()
+            .filter(|(_, value)| *value != 0)
+            .map(|(source, value)| format!("{value:+} {source}"))
+            .collect();
+        breakdown.extend(notes.iter().map(|note| format!("Conditional: {note}")));
+        let mut value = if signed {
+            format!("{total:+}")
+        } else {
+            total.to_string()
+        };
+        if !notes.is_empty() {
+            value.push_str(" *");
+        }
+        Self {
+            label: label.to_string(),
+            value,
+            breakdown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SheetSection {
+    pub title: &'static str,
+    pub entries: Vec<SheetEntry>,
+}
+
+// Everything on the builder the sheet is read from.
+#[derive(WorldQuery)]
+pub struct SheetQuery {
+    alignment: Option<&'static Alignment>,
+    deity: Option<&'static Deity>,
+    domains: Option<&'static Domains>,
+    size: Option<&'static CharacterSize>,
+    creature_type: Option<&'static CreatureType>,
+    subtypes: Option<&'static CreatureSubtypes>,
+    speed: Option<&'static GroundSpeed>,
+    low_light_vision: Option<&'static LowLightVision>,
+    dark_vision: Option<&'static DarkVision>,
+    scores: Option<&'static AbilityScores>,
+    ability_bonuses: Option<&'static AbilityScoreBonuses>,
+    class_levels: Option<&'static ClassLevels>,
+    armor_class_bonuses: Option<&'static ArmorClassBonuses>,
+    save_bonuses: Option<&'static SavingThrowBonuses>,
+    cmd_bonuses: Option<&'static CMDBonuses>,
+    skill_ranks: Option<&'static SkillRanks>,
+    skill_bonuses: Option<&'static SkillBonuses>,
+    favored: Option<&'static FavoredClasses>,
+    languages: Option<&'static KnownLanguages>,
+    traits: Option<&'static CharacterTraits>,
+    proficiency_feats: Option<&'static ProficiencyFeats>,
+    bonus_feats: Option<&'static FloatingBonusFeats>,
+    class_features: Option<&'static ClassFeatures>,
+    spell_like_abilities: Option<&'static SpellLikeAbilities>,
+    inventory: Option<&'static Inventory>,
+    wealth: Option<&'static Wealth>,
+    weapon: Option<&'static WieldedWeapon>,
+    armor: Option<&'static WornArmor>,
+    proficiencies: Option<&'static Proficiencies>,
+}
+
+// The choices made so far and the descriptions used to name things.
+#[derive(SystemParam)]
+pub struct SheetSources<'w> {
+    selected_race: Res<'w, SelectedRace>,
+    selected_class: Res<'w, SelectedClass>,
+    selected_archetype: Res<'w, SelectedArchetype>,
+    race_builder: Res<'w, RaceBuilder>,
+    class_map: Res<'w, ClassMap>,
+    archetype_map: Res<'w, ArchetypeMap>,
+    class_assets: Res<'w, Assets<ClassAsset>>,
+    default_traits: Res<'w, Assets<DefaultTraitAsset>>,
+    alternate_traits: Res<'w, Assets<AltTraitAsset>>,
+    issues: Res<'w, CharacterIssues>,
+}
+
+impl SheetSources<'_> {
+    // Levels from the builder's `ClassLevels`, or a single level in the
+    // selected class while it has none.
+    fn class_levels(&self, class_levels: Option<&ClassLevels>) -> ClassLevels {
+        class_levels
+            .cloned()
+            .unwrap_or_else(|| ClassLevels::from(HashMap::from([(self.selected_class.inner(), 1)])))
+    }
+    fn racial_trait_title(&self, name: &RacialTraitName) -> String {
+        let default = self
+            .default_traits
+            .iter()
+            .flat_map(|(_, asset)| asset.default_traits.iter())
+            .find(|display| display.my_trait_name == *name)
+            .map(|display| display.title.clone());
+        let alternate = || {
+            self.alternate_traits
+                .iter()
+                .flat_map(|(_, asset)| asset.alternate_traits.iter())
+                .find(|display| display.my_trait_name == *name)
+                .map(|display| display.title.clone())
+        };
+        default
+            .or_else(alternate)
+            .unwrap_or_else(|| format!("{name:?}"))
+    }
+    fn class_feature_title(&self, feature: &ClassFeature) -> String {
+        self.class_assets
+            .iter()
+            .flat_map(|(_, asset)| asset.class_features.iter())
+            .find(|description| description.class_feature_name == *feature)
+            .map_or_else(
+                || format!("{feature:?}"),
+                |description| description.title.clone(),
+            )
+    }
+}
+
+// Stacks bonuses by type into one part per type, so the breakdown shows what
+// actually counts toward the total.
+fn stacked(bonuses: impl Iterator<Item = (BonusType, i32)>) -> Vec<(String, i32)> {
+    let mut by_type: BTreeMap<BonusType, Vec<i32>> = BTreeMap::new();
+    for (bonus_type, bonus) in bonuses {
+        by_type.entry(bonus_type).or_default().push(bonus);
+    }
+    by_type
+        .iter()
+        .map(|(bonus_type, bonuses)| {
+            (
+                bonus_type.to_string(),
+                stack_bonuses(bonus_type, bonuses.iter().copied()),
+            )
+        })
+        .collect()
+}
+
+// Levels by class name, so the sheet lists them the same way every frame.
+fn sorted_levels(levels: &ClassLevels) -> Vec<(PlayableClass, usize)> {
+    let mut sorted: Vec<(PlayableClass, usize)> = levels
+        .inner_ref()
+        .iter()
+        .map(|(class, level)| (*class, *level))
+        .collect();
+    sorted.sort_by_key(|(class, _)| class.to_string());
+    sorted
+}
+
+fn always_applies(limitations: &[LimitationEnum]) -> bool {
+    limitations.is_empty() || limitations.contains(&LimitationEnum::None)
+}
+
+fn note(bonus: i32, bonus_type: &BonusType, limitations: &[LimitationEnum]) -> String {
+    let limitations: Vec<String> = limitations.iter().map(|l| l.to_string()).collect();
+    format!("{bonus:+} {bonus_type} {}", limitations.join(" or "))
+}
+
+// Scores from `AbilityScores` with the bonuses that always apply added in.
+fn final_scores(item: &SheetQueryItem) -> AbilityScores {
+    let mut scores = AbilityScores::new();
+    for ability in AbilityScore::as_array() {
+        let parts = ability_parts(item, &ability);
+        scores.set(ability, parts.iter().map(|(_, value)| value).sum());
+    }
+    scores
+}
+
+fn ability_parts(item: &SheetQueryItem, ability: &AbilityScore) -> Vec<(String, i32)> {
+    let base = item.scores.map_or(10, |scores| scores.get(ability));
+    let bonuses = item
+        .ability_bonuses
+        .and_then(|bonuses| bonuses.0.get(ability))
+        .into_iter()
+        .flatten()
+        .filter(|bonus| bonus.limitation == LimitationEnum::None)
+        .map(|bonus| (bonus.bonus_type, bonus.bonus));
+    let mut parts = vec![("base score".to_string(), base)];
+    parts.extend(stacked(bonuses));
+    parts
+}
+
+fn identity_section(item: &SheetQueryItem, sources: &SheetSources) -> SheetSection {
+    let class = sources.selected_class.inner();
+    let mut entries = vec![
+        SheetEntry::text("Race", sources.selected_race.0),
+        SheetEntry::text("Class", class),
+    ];
+    if let Some(archetype) = sources
+        .archetype_map
+        .inner_ref()
+        .get(&sources.selected_archetype.inner())
+        .filter(|info| info.class == class)
+    {
+        entries.push(SheetEntry::text("Archetype", archetype.name));
+    }
+    if let Some(alignment) = item.alignment {
+        entries.push(SheetEntry::text("Alignment", alignment));
+    }
+    if let Some(deity) = item.deity {
+        entries.push(SheetEntry::text("Deity", &deity.0));
+    }
+    if let Some(domains) = item.domains.filter(|domains| !domains.0.is_empty()) {
+        let names: Vec<String> = domains.0.iter().map(|domain| domain.to_string()).collect();
+        entries.push(SheetEntry::text("Domains", names.join(", ")));
+    }
+    if let Some(size) = item.size {
+        entries.push(SheetEntry::text("Size", size.category));
+    }
+    if let Some(creature_type) = item.creature_type {
+        let value = match item.subtypes.filter(|subtypes| !subtypes.0.is_empty()) {
+            Some(subtypes) => format!("{creature_type} ({subtypes})"),
+            None => creature_type.to_string(),
+        };
+        entries.push(SheetEntry::text("Type", value));
+    }
+    if let Some(speed) = item.speed {
+        entries.push(SheetEntry::text("Speed", speed));
+    }
+    if let Some(vision) = item.low_light_vision {
+        entries.push(SheetEntry::text(
+            "Low-light vision",
+            format!("x{}", vision.0),
+        ));
+    }
+    if let Some(vision) = item.dark_vision {
+        entries.push(SheetEntry::text("Darkvision", format!("{} ft.", vision.0)));
+    }
+    SheetSection {
+        title: "Identity",
+        entries,
+    }
+}
+
+fn ability_section(item: &SheetQueryItem) -> SheetSection {
+    let scores = final_scores(item);
+    let entries = AbilityScore::as_array()
+        .iter()
+        .map(|ability| {
+            let mut entry =
+                SheetEntry::total(ability, ability_parts(item, ability), Vec::new(), false);
+            entry.value = format!("{} ({:+})", scores.get(ability), scores.modifier(ability));
+            entry
+        })
+        .collect();
+    SheetSection {
+        title: "Ability Scores",
+        entries,
+    }
+}
+
+fn combat_section(item: &SheetQueryItem, sources: &SheetSources) -> SheetSection {
+    let scores = final_scores(item);
+    let levels = sources.class_levels(item.class_levels);
+    let size = item.size.map_or(SizeCategory::Medium, |size| size.category);
+    let dex = scores.modifier(&AbilityScore::Dexterity);
+    let strength = scores.modifier(&AbilityScore::Strength);
+    let con = scores.modifier(&AbilityScore::Constitution);
+
+    // Max hit points at 1st level in the selected class, average rolls after.
+    let mut hit_points = Vec::new();
+    let mut first = Some(sources.selected_class.inner());
+    for (class, level) in sorted_levels(&levels) {
+        let Some(info) = sources.class_map.inner_ref().get(&class) else {
+            continue;
+        };
+        let sides = info.hit_die.sides();
+        let mut level = level;
+        if first == Some(class) && level > 0 {
+            hit_points.push((format!("{class} hit die at 1st level"), sides));
+            level -= 1;
+            first = None;
+        }
+        if level > 0 {
+            hit_points.push((
+                format!("{class} hit dice, average"),
+                level as i32 * (sides / 2 + 1),
+            ));
+        }
+    }
+    hit_points.push((
+        "Constitution".to_string(),
+        con * levels.total_level() as i32,
+    ));
+    hit_points.push((
+        "favored class".to_string(),
+        item.favored.map_or(0, |favored| favored.hit_points()) as i32,
+    ));
+
+    let ac_bonuses: Vec<&ArmorClassBonus> = item
+        .armor_class_bonuses
+        .iter()
+        .flat_map(|bonuses| bonuses.0.values().flatten())
+        .collect();
+    let mut ac_notes: Vec<String> = ac_bonuses
+        .iter()
+        .filter(|bonus| {
+            !matches!(
+                bonus.limitation,
+                LimitationEnum::None | LimitationEnum::AttacksFrom(_)
+            )
+        })
+        .map(|bonus| note(bonus.bonus, &bonus.bonus_type, &[bonus.limitation]))
+        .collect();
+    ac_notes.sort();
+    let armor_class = |keep: &dyn Fn(&BonusType) -> bool, with_dex: bool| {
+        let mut parts = vec![
+            ("base".to_string(), 10),
+            ("size".to_string(), size.size_modifier()),
+        ];
+        if with_dex {
+            parts.push(("Dexterity".to_string(), dex));
+        }
+        parts.extend(stacked(
+            ac_bonuses
+                .iter()
+                .filter(|bonus| bonus.limitation == LimitationEnum::None)
+                .filter(|bonus| keep(&bonus.bonus_type))
+                .map(|bonus| (bonus.bonus_type, bonus.bonus)),
+        ));
+        parts
+    };
+    let touch = |bonus_type: &BonusType| {
+        !matches!(
+            bonus_type,
+            BonusType::Armor | BonusType::Shield | BonusType::NaturalArmor
+        )
+    };
+
+    let base_attack = levels.base_attack(&sources.class_map);
+    let cmb_ability = if size <= SizeCategory::Tiny {
+        ("Dexterity", dex)
+    } else {
+        ("Strength", strength)
+    };
+    let cmd_bonuses: Vec<&CMDBonus> = item
+        .cmd_bonuses
+        .iter()
+        .flat_map(|bonuses| bonuses.0.values().flatten())
+        .collect();
+    let mut cmd = vec![
+        ("base".to_string(), 10),
+        ("base attack".to_string(), base_attack.base as i32),
+        ("Strength".to_string(), strength),
+        ("Dexterity".to_string(), dex),
+        ("size".to_string(), size.special_size_modifier()),
+    ];
+    cmd.extend(stacked(
+        cmd_bonuses
+            .iter()
+            .filter(|bonus| always_applies(&bonus.limitation))
+            .map(|bonus| (bonus.bonus_type, bonus.bonus)),
+    ));
+    let mut cmd_notes: Vec<String> = cmd_bonuses
+        .iter()
+        .filter(|bonus| !always_applies(&bonus.limitation))
+        .map(|bonus| note(bonus.bonus, &bonus.bonus_type, &bonus.limitation))
+        .collect();
+    cmd_notes.sort();
+
+    let mut entries = vec![
+        SheetEntry::total("Hit points", hit_points, Vec::new(), false),
+        SheetEntry::total("AC", armor_class(&|_| true, true), ac_notes.clone(), false),
+        SheetEntry::total(
+            "Touch AC",
+            armor_class(&touch, true),
+            ac_notes.clone(),
+            false,
+        ),
+        SheetEntry::total(
+            "Flat-footed AC",
+            armor_class(&|bonus_type| *bonus_type != BonusType::Dodge, dex < 0),
+            ac_notes,
+            false,
+        ),
+        SheetEntry::total(
+            "Base attack",
+            vec![("class levels".to_string(), base_attack.base as i32)],
+            Vec::new(),
+            true,
+        ),
+        SheetEntry::total(
+            "CMB",
+            vec![
+                ("base attack".to_string(), base_attack.base as i32),
+                (cmb_ability.0.to_string(), cmb_ability.1),
+                ("size".to_string(), size.special_size_modifier()),
+            ],
+            Vec::new(),
+            true,
+        ),
+        SheetEntry::total("CMD", cmd, cmd_notes, false),
+    ];
+    let penalty = item
+        .proficiencies
+        .map_or(0, |known| known.attack_penalty(item.weapon, item.armor));
+    if penalty != 0 {
+        entries.push(SheetEntry::total(
+            "Non-proficiency",
+            vec![("weapon or armor not proficient with".to_string(), penalty)],
+            Vec::new(),
+            true,
+        ));
+    }
+    SheetSection {
+        title: "Combat",
+        entries,
+    }
+}
+
+fn saves_section(item: &SheetQueryItem, sources: &SheetSources) -> SheetSection {
+    let scores = final_scores(item);
+    let base_saves = sources
+        .class_levels(item.class_levels)
+        .base_saves(&sources.class_map);
+    let entries = [
+        SavingThrowName::Fort,
+        SavingThrowName::Reflex,
+        SavingThrowName::Will,
+    ]
+    .iter()
+    .map(|save| {
+        let bonuses: Vec<&SavingThrowBonus> = item
+            .save_bonuses
+            .and_then(|bonuses| bonuses.0.get(save))
+            .into_iter()
+            .flatten()
+            .collect();
+        let mut parts = vec![("base save".to_string(), base_saves.get(save) as i32)];
+        if let Some(ability) = save.ability() {
+            parts.push((ability.to_string(), scores.modifier(&ability)));
+        }
+        parts.extend(stacked(
+            bonuses
+                .iter()
+                .filter(|bonus| bonus.limitation == LimitationEnum::None)
+                .map(|bonus| (bonus.bonus_type, bonus.bonus)),
+        ));
+        let notes = bonuses
+            .iter()
+            .filter(|bonus| bonus.limitation != LimitationEnum::None)
+            .map(|bonus| note(bonus.bonus, &bonus.bonus_type, &[bonus.limitation]))
+            .collect();
+        SheetEntry::total(save, parts, notes, true)
+    })
+    .collect();
+    SheetSection {
+        title: "Saving Throws",
+        entries,
+    }
+}
+
+// Skills with ranks, class skills and skills with a bonus. Class skills with
+// at least one rank get +3.
+fn skills_section(item: &SheetQueryItem, sources: &SheetSources) -> SheetSection {
+    let scores = final_scores(item);
+    let levels = sources.class_levels(item.class_levels);
+    let class_skills: Vec<SkillName> = levels
+        .inner_ref()
+        .keys()
+        .filter_map(|class| sources.class_map.inner_ref().get(class))
+        .flat_map(|info| info.class_skills.iter().copied())
+        .collect();
+    let entries = SkillName::default()
+        .iterator()
+        .filter_map(|skill| {
+            let ranks = item.skill_ranks.map_or(0, |ranks| ranks.get(&skill));
+            let bonuses: Vec<&SkillBonus> = item
+                .skill_bonuses
+                .and_then(|bonuses| bonuses.0.get(&skill))
+                .into_iter()
+                .flatten()
+                .collect();
+            let class_skill = class_skills.contains(&skill);
+            if ranks == 0 && !class_skill && bonuses.is_empty() {
+                return None;
+            }
+            let ability = skill.ability();
+            let mut parts = vec![
+                ("ranks".to_string(), ranks as i32),
+                (ability.to_string(), scores.modifier(&ability)),
+            ];
+            if class_skill && ranks > 0 {
+                parts.push(("class skill".to_string(), 3));
+            }
+            parts.extend(stacked(
+                bonuses
+                    .iter()
+                    .filter(|bonus| always_applies(&bonus.limitation))
+                    .map(|bonus| (bonus.bonus_type, bonus.bonus)),
+            ));
+            let notes = bonuses
+                .iter()
+                .filter(|bonus| !always_applies(&bonus.limitation))
+                .map(|bonus| note(bonus.bonus, &bonus.bonus_type, &bonus.limitation))
+                .collect();
+            let label = if class_skill {
+                format!("{skill} (class)")
+            } else {
+                skill.to_string()
+            };
+            Some(SheetEntry::total(label, parts, notes, true))
+        })
+        .collect();
+    SheetSection {
+        title: "Skills",
+        entries,
+    }
+}
+
+fn feats_section(item: &SheetQueryItem) -> SheetSection {
+    let mut entries: Vec<SheetEntry> = item
+        .proficiency_feats
+        .iter()
+        .flat_map(|feats| feats.0.iter())
+        .map(|feat| SheetEntry::text("Feat", feat))
+        .collect();
+    for bonus_feat in item.bonus_feats.iter().flat_map(|feats| feats.inner()) {
+        entries.push(SheetEntry::text(
+            "Bonus feats to choose",
+            format!("{} ({:?})", bonus_feat.number, bonus_feat.group),
+        ));
+    }
+    for name in item.traits.iter().flat_map(|traits| traits.0.iter()) {
+        entries.push(SheetEntry::text("Trait", name));
+    }
+    SheetSection {
+        title: "Feats and Traits",
+        entries,
+    }
+}
+
+fn racial_traits_section(sources: &SheetSources) -> SheetSection {
+    SheetSection {
+        title: "Racial Traits",
+        entries: sources
+            .race_builder
+            .inner()
+            .iter()
+            .map(|name| SheetEntry::text(sources.racial_trait_title(name), ""))
+            .collect(),
+    }
+}
+
+// Features gained so far, or those of the levels in `ClassLevels` while the
+// builder has no `ClassFeatures` yet.
+fn class_features_section(item: &SheetQueryItem, sources: &SheetSources) -> SheetSection {
+    let features: Vec<(PlayableClass, ClassFeature)> = match item.class_features {
+        Some(features) => features
+            .inner_ref()
+            .iter()
+            .map(|feature| (sources.selected_class.inner(), *feature))
+            .collect(),
+        None => sorted_levels(&sources.class_levels(item.class_levels))
+            .into_iter()
+            .filter_map(|(class, level)| {
+                let info = sources.class_map.inner_ref().get(&class)?;
+                Some(
+                    info.class_features
+                        .iter()
+                        .take(level)
+                        .flatten()
+                        .map(|feature| (class, *feature))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .flatten()
+            .collect(),
+    };
+    SheetSection {
+        title: "Class Features",
+        entries: features
+            .iter()
+            .filter(|(_, feature)| *feature != ClassFeature::None)
+            .map(|(class, feature)| SheetEntry::text(sources.class_feature_title(feature), class))
+            .collect(),
+    }
+}
+
+fn languages_section(item: &SheetQueryItem) -> SheetSection {
+    SheetSection {
+        title: "Languages",
+        entries: item
+            .languages
+            .iter()
+            .flat_map(|languages| languages.0.iter())
+            .map(|language| SheetEntry::text(language, ""))
+            .collect(),
+    }
+}
+
+fn spell_like_abilities_section(item: &SheetQueryItem) -> SheetSection {
+    let mut entries: Vec<SheetEntry> = item
+        .spell_like_abilities
+        .iter()
+        .flat_map(|abilities| abilities.0.values().flatten())
+        .map(|ability| {
+            let frequency = match (ability.cast_frequency, ability.uses) {
+                (CastFrequency::AtWill, _) => "at will".to_string(),
+                (CastFrequency::PerDay, uses) => format!("{}/day", uses.unwrap_or(1)),
+            };
+            SheetEntry::text(format!("{:?}", ability.spell_name), frequency)
+        })
+        .collect();
+    entries.sort_by(|a, b| a.label.cmp(&b.label));
+    SheetSection {
+        title: "Spell-Like Abilities",
+        entries,
+    }
+}
+
+fn equipment_section(item: &SheetQueryItem) -> SheetSection {
+    let mut entries = Vec::new();
+    if let Some(wealth) = item.wealth {
+        entries.push(SheetEntry::text("Coins", wealth));
+    }
+    if let Some(weapon) = item.weapon {
+        entries.push(SheetEntry::text("Wielding", weapon.0));
+    }
+    if let Some(armor) = item.armor.filter(|armor| !armor.0.is_empty()) {
+        let pieces: Vec<String> = armor
+            .0
+            .iter()
+            .map(|piece| piece.category.to_string())
+            .collect();
+        entries.push(SheetEntry::text("Wearing", pieces.join(", ")));
+    }
+    for item in item
+        .inventory
+        .iter()
+        .flat_map(|inventory| inventory.0.iter())
+    {
+        entries.push(SheetEntry::text(
+            &item.name,
+            format!("{}, {} lb.", Wealth(item.cost), item.weight),
+        ));
+    }
+    SheetSection {
+        title: "Equipment",
+        entries,
+    }
+}
+
+// What the validator found, with the stage that fixes each issue on hover.
+fn issues_section(sources: &SheetSources) -> SheetSection {
+    SheetSection {
+        title: "Issues",
+        entries: sources
+            .issues
+            .0
+            .iter()
+            .map(|issue| SheetEntry {
+                label: issue.to_string(),
+                value: issue.severity().to_string(),
+                breakdown: vec![format!("Fixed in the {} stage", issue.stage())],
+            })
+            .collect(),
+    }
+}
+
+pub fn character_sheet(item: &SheetQueryItem, sources: &SheetSources) -> Vec<SheetSection> {
+    vec![
+        identity_section(item, sources),
+        ability_section(item),
+        combat_section(item, sources),
+        saves_section(item, sources),
+        skills_section(item, sources),
+        feats_section(item),
+        racial_traits_section(sources),
+        class_features_section(item, sources),
+        languages_section(item),
+        spell_like_abilities_section(item),
+        equipment_section(item),
+        issues_section(sources),
+    ]
+}
+
+// Systems
+// The sheet covers the creation screen and stays hidden until the Character
+// Sheet button is pressed.
+pub fn setup_character_sheet(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    padding: UiRect::all(Val::Px(20.)),
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+            CharacterSheetPanel,
+            Name::from("Character Sheet"),
+        ))
+        .with_children(|panel| {
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        ..CHARACTER_CREATION_TITLE_STYLE
+                    },
+                    ..default()
+                })
+                .with_children(|header| {
+                    header.spawn(TextBundle::from_section(
+                        "Character Sheet",
+                        TextStyle {
+                            font: shared_font.clone(),
+                            font_size: LIST_TITLE_TEXT_SIZE,
+                            color: TEXT_COLOR,
+                        },
+                    ));
+                    header
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::all(Val::Px(10.)),
+                                    ..default()
+                                },
+                                background_color: RACE_BUTTON_COLOR.into(),
+                                ..default()
+                            },
+                            CloseCharacterSheetButton,
+                        ))
+                        .with_children(|button| {
+                            button.spawn(TextBundle::from_section(
+                                "Close",
+                                TextStyle {
+                                    font: shared_font.clone(),
+                                    font_size: SUBTAB_BUTTON_FONT,
+                                    color: TEXT_COLOR,
+                                },
+                            ));
+                        });
+                });
+            panel.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        flex_wrap: FlexWrap::Wrap,
+                        align_content: AlignContent::FlexStart,
+                        column_gap: Val::Px(30.),
+                        flex_grow: 1.,
+                        ..default()
+                    },
+                    ..default()
+                },
+                CharacterSheetContent,
+            ));
+        });
+}
+
+// The Character Sheet button opens the sheet and its Close button hides it
+// again. The marker is on the button's text, so the press is read from its
+// parent.
+pub fn toggle_character_sheet(
+    query_open: Query<&Parent, With<CharacterSheetButton>>,
+    query_close: Query<&Interaction, (Changed<Interaction>, With<CloseCharacterSheetButton>)>,
+    query_interaction: Query<&Interaction, Changed<Interaction>>,
+    mut query_panel: Query<&mut Style, With<CharacterSheetPanel>>,
+) {
+    let pressed = |interaction: &Interaction| *interaction == Interaction::Pressed;
+    let open = query_open
+        .iter()
+        .any(|parent| query_interaction.get(parent.get()).is_ok_and(pressed));
+    let close = query_close.iter().any(pressed);
+    let Ok(mut style) = query_panel.get_single_mut() else {
+        return;
+    };
+    let display = match (open, close) {
+        (true, _) => Display::Flex,
+        (_, true) => Display::None,
+        _ => return,
+    };
+    if style.display != display {
+        style.display = display;
+    }
+}
+
+// Rebuilds the open sheet whenever anything on it changes. Lines with a
+// breakdown get a tooltip, shown by `display_on_hover`.
+pub fn display_character_sheet(
+    query_builder: Query<SheetQuery, With<CharacterBuilder>>,
+    query_panel: Query<&Style, With<CharacterSheetPanel>>,
+    query_content: Query<Entity, With<CharacterSheetContent>>,
+    sources: SheetSources,
+    asset_server: Res<AssetServer>,
+    mut built: Local<Vec<SheetSection>>,
+    mut commands: Commands,
+) {
+    if !query_panel
+        .get_single()
+        .is_ok_and(|style| style.display != Display::None)
+    {
+        return;
+    }
+    let (Ok(item), Ok(content)) = (query_builder.get_single(), query_content.get_single()) else {
+        return;
+    };
+    let sections = character_sheet(&item, &sources);
+    if *built == sections {
+        return;
+    }
+    let shared_font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    let text_style = TextStyle {
+        font: shared_font.clone(),
+        font_size: DESCRIPTION_FONT_SIZE,
+        color: TEXT_COLOR,
+    };
+    commands.entity(content).despawn_descendants();
+    commands.entity(content).with_children(|content| {
+        for section in sections
+            .iter()
+            .filter(|section| !section.entries.is_empty())
+        {
+            content
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        margin: UiRect::bottom(Val::Px(15.)),
+                        ..CHARACTER_SHEET_STYLE_H2
+                    },
+                    ..default()
+                })
+                .with_children(|list| {
+                    list.spawn(TextBundle::from_section(
+                        section.title,
+                        TextStyle {
+                            font: shared_font.clone(),
+                            font_size: LIST_TITLE_TEXT_SIZE,
+                            color: STAGES_OF_CREATION_BUTTON_COLOR,
+                        },
+                    ));
+                    for entry in section.entries.iter() {
+                        let label = if entry.value.is_empty() {
+                            entry.label.clone()
+                        } else {
+                            format!("{}: {}", entry.label, entry.value)
+                        };
+                        let mut line =
+                            list.spawn(TextBundle::from_section(label, text_style.clone()));
+                        if !entry.breakdown.is_empty() {
+                            line.insert((
+                                Interaction::default(),
+                                TooltipText(Text::from_section(
+                                    entry.breakdown.join("\n"),
+                                    text_style.clone(),
+                                )),
+                            ));
+                        }
+                    }
+                });
+        }
+    });
+    *built = sections;
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::menu::character_creation::components::RaceTab;
+use crate::menu::character_creation::{components::RaceTab, layout::generics::list_traits::AsVec};
 
 #[derive(Component, Debug)]
 pub enum Container {
@@ -14,12 +14,13 @@ pub enum Container {
 pub enum NavBarButtonType {
     Battle,
     CharacterCreation,
+    EncounterBuilder,
     Exit,
     #[default]
     Empty,
 }
 
-#[derive(Component, Debug, Copy, Clone)]
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum StagesOfCreationButton {
     Race,
     AbilityScores,
@@ -30,6 +31,34 @@ pub enum StagesOfCreationButton {
     Optional,
 }
 
+impl std::fmt::Display for StagesOfCreationButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Race => write!(f, "Race"),
+            Self::AbilityScores => write!(f, "Ability Scores"),
+            Self::Class => write!(f, "Class"),
+            Self::Skills => write!(f, "Skills"),
+            Self::Feats => write!(f, "Feats"),
+            Self::BonusFeats => write!(f, "Bonus Feats"),
+            Self::Optional => write!(f, "Optional"),
+        }
+    }
+}
+
+impl AsVec for StagesOfCreationButton {
+    fn vec() -> Vec<Self> {
+        vec![
+            Self::Race,
+            Self::AbilityScores,
+            Self::Class,
+            Self::Skills,
+            Self::Feats,
+            Self::BonusFeats,
+            Self::Optional,
+        ]
+    }
+}
+
 pub trait SelectedWrapper<V>
 where
     V: Component + Eq + PartialEq + Copy,
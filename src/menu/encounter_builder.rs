@@ -0,0 +1,366 @@
+use crate::{
+    my_camera::my_camera_systems::MainCamera,
+    system_scheduling::states::AppState,
+    systems::{
+        game::{
+            creature::{spawn_creature, ExperienceValue},
+            effects::Effect,
+            encounter::{average_party_level, xp_budget, Difficulty, SelectedEncounter},
+        },
+        grid_systems::{
+            flex_grid,
+            grid_position::{Footprint, GridPos},
+            token::{cursor_grid_pos, Faction},
+        },
+    },
+    technical::{
+        creature::{CreatureAsset, CREATURE_FOLDER},
+        encounter::{EncounterAsset, EncounterCreature},
+        is_custom_asset_loaded::{is_custom_asset_loaded, CustomAssetLoadState},
+    },
+    AsciiSheet,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+pub const MAX_PARTY_LEVEL: usize = 20;
+pub const MAX_PARTY_SIZE: usize = 8;
+
+// Marks everything spawned by the builder, so it can be cleaned up on exit.
+#[derive(Component)]
+pub struct EncounterBuilderEntity;
+
+#[derive(Component)]
+pub struct EncounterBuilderText;
+
+// A creature placed in the encounter, holding the file name of its stat block.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct PlacedCreature(pub String);
+
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct EncounterBuilder {
+    pub party_level: usize,
+    pub party_size: usize,
+    pub difficulty: Difficulty,
+    // Index of the stat block placed by clicking, in CR order.
+    pub selected: usize,
+    // Result of the last save, shown under the controls.
+    pub status: String,
+}
+
+impl Default for EncounterBuilder {
+    fn default() -> Self {
+        Self {
+            party_level: 1,
+            party_size: 4,
+            difficulty: Difficulty::default(),
+            selected: 0,
+            status: String::new(),
+        }
+    }
+}
+
+impl EncounterBuilder {
+    // The party level the budget is built for, adjusted for the party size.
+    pub fn effective_level(&self) -> usize {
+        average_party_level(&vec![self.party_level; self.party_size])
+    }
+    pub fn budget(&self) -> u32 {
+        xp_budget(self.effective_level(), &self.difficulty)
+    }
+}
+
+// Every loaded stat block, with the file name it was loaded from.
+#[derive(SystemParam)]
+pub struct CreatureCatalog<'w> {
+    load_state: Res<'w, CustomAssetLoadState<CreatureAsset>>,
+    stat_blocks: Res<'w, Assets<CreatureAsset>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+impl<'w> CreatureCatalog<'w> {
+    // Stat blocks sorted by CR, then name.
+    pub fn entries(&self) -> Vec<(String, &CreatureAsset)> {
+        let mut entries: Vec<(String, &CreatureAsset)> = self
+            .load_state
+            .handles()
+            .iter()
+            .filter_map(|handle| {
+                let file_name = self
+                    .asset_server
+                    .get_handle_path(handle)?
+                    .path()
+                    .file_name()?
+                    .to_str()?
+                    .to_string();
+                Some((file_name, self.stat_blocks.get(handle)?))
+            })
+            .collect();
+        entries.sort_by(|(_, a), (_, b)| {
+            (a.challenge_rating, &a.name).cmp(&(b.challenge_rating, &b.name))
+        });
+        entries
+    }
+}
+
+// Systems
+pub fn setup_encounter_builder(
+    mut builder: ResMut<EncounterBuilder>,
+    mut load_state: ResMut<CustomAssetLoadState<CreatureAsset>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    *builder = EncounterBuilder::default();
+    if load_state.handles().is_empty() {
+        if let Ok(found_assets) = asset_server.load_folder(CREATURE_FOLDER) {
+            for handle in found_assets {
+                load_state.add_untyped(&handle);
+            }
+        }
+    }
+    let font: Handle<Font> = asset_server.load("fonts/simple_font.TTF");
+    commands.spawn((
+        TextBundle::from_section(
+            "Loading creatures...",
+            TextStyle {
+                font,
+                font_size: 24.,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..default()
+        }),
+        EncounterBuilderText,
+        EncounterBuilderEntity,
+    ));
+}
+
+// Escape goes back to the main menu, even while the stat blocks are loading.
+pub fn exit_encounter_builder(
+    key_input: Res<Input<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if key_input.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+// Up and down change the party level, page up and page down the party size,
+// left and right the difficulty, and tab picks the next stat block.
+pub fn adjust_encounter_settings(
+    key_input: Res<Input<KeyCode>>,
+    catalog: CreatureCatalog,
+    mut builder: ResMut<EncounterBuilder>,
+) {
+    if key_input.just_pressed(KeyCode::Up) {
+        builder.party_level = (builder.party_level + 1).min(MAX_PARTY_LEVEL);
+    }
+    if key_input.just_pressed(KeyCode::Down) {
+        builder.party_level = builder.party_level.saturating_sub(1).max(1);
+    }
+    if key_input.just_pressed(KeyCode::PageUp) {
+        builder.party_size = (builder.party_size + 1).min(MAX_PARTY_SIZE);
+    }
+    if key_input.just_pressed(KeyCode::PageDown) {
+        builder.party_size = builder.party_size.saturating_sub(1).max(1);
+    }
+    if key_input.just_pressed(KeyCode::Right) {
+        builder.difficulty = builder.difficulty.harder();
+    }
+    if key_input.just_pressed(KeyCode::Left) {
+        builder.difficulty = builder.difficulty.easier();
+    }
+    if key_input.just_pressed(KeyCode::Tab) {
+        let count = catalog.entries().len().max(1);
+        builder.selected = (builder.selected + 1) % count;
+    }
+}
+
+// Left click places the selected stat block on an empty square, and right
+// click removes the creature under the cursor.
+#[allow(clippy::too_many_arguments)]
+pub fn place_encounter_creatures(
+    mouse_input: Res<Input<MouseButton>>,
+    builder: Res<EncounterBuilder>,
+    catalog: CreatureCatalog,
+    query_window: Query<&Window>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    query_placed: Query<(Entity, &GridPos, &Footprint), With<PlacedCreature>>,
+    query_effects: Query<(Entity, &Effect)>,
+    ascii: Res<AsciiSheet>,
+    mut commands: Commands,
+) {
+    let placing = mouse_input.just_pressed(MouseButton::Left);
+    let removing = mouse_input.just_pressed(MouseButton::Right);
+    if !placing && !removing {
+        return;
+    }
+    let Some(square) = cursor_grid_pos(&query_window, &query_camera) else {
+        return;
+    };
+    let occupant = query_placed
+        .iter()
+        .find(|(_, pos, footprint)| pos.squares(footprint).contains(&square))
+        .map(|(entity, _, _)| entity);
+    match occupant {
+        Some(entity) if removing => {
+            for (effect_entity, _) in query_effects
+                .iter()
+                .filter(|(_, effect)| effect.target == entity)
+            {
+                commands.entity(effect_entity).despawn();
+            }
+            commands.entity(entity).despawn_recursive();
+        }
+        None if placing => {
+            let entries = catalog.entries();
+            let Some((file_name, stat_block)) = entries.get(builder.selected) else {
+                return;
+            };
+            let entity =
+                spawn_creature(&mut commands, stat_block, &ascii, square, Faction::Hostile);
+            commands
+                .entity(entity)
+                .insert((PlacedCreature(file_name.clone()), EncounterBuilderEntity));
+        }
+        _ => (),
+    }
+}
+
+// Enter saves the encounter to the encounter folder and makes it the one the
+// next battle starts with.
+pub fn save_encounter(
+    key_input: Res<Input<KeyCode>>,
+    mut builder: ResMut<EncounterBuilder>,
+    query_placed: Query<(&GridPos, &PlacedCreature)>,
+    mut encounters: ResMut<Assets<EncounterAsset>>,
+    mut selected: ResMut<SelectedEncounter>,
+) {
+    if !key_input.just_pressed(KeyCode::Return) {
+        return;
+    }
+    let mut creatures: Vec<EncounterCreature> = query_placed
+        .iter()
+        .map(|(grid_pos, placed)| EncounterCreature {
+            stat_block: placed.0.clone(),
+            grid_pos: *grid_pos,
+        })
+        .collect();
+    creatures.sort_by_key(|creature| creature.grid_pos);
+    let encounter = EncounterAsset {
+        name: format!("{} level {}", builder.difficulty, builder.party_level),
+        party_level: builder.party_level,
+        difficulty: builder.difficulty,
+        creatures,
+    };
+    builder.status = match encounter.save() {
+        Ok(path) => {
+            info!("Saved encounter {} to {path}", encounter.name);
+            format!("Saved to {path}")
+        }
+        Err(error) => {
+            warn!("Failed to save encounter {}: {error}", encounter.name);
+            format!("Failed to save encounter: {error}")
+        }
+    };
+    selected.0 = Some(encounters.add(encounter));
+}
+
+pub fn update_encounter_builder_text(
+    builder: Res<EncounterBuilder>,
+    catalog: CreatureCatalog,
+    query_placed: Query<&ExperienceValue, With<PlacedCreature>>,
+    mut query_text: Query<&mut Text, With<EncounterBuilderText>>,
+) {
+    let Ok(mut text) = query_text.get_single_mut() else {
+        return;
+    };
+    let budget = builder.budget();
+    let spent: u32 = query_placed.iter().map(|xp| xp.0).sum();
+    let entries = catalog.entries();
+    let selected = entries.get(builder.selected).map_or(
+        "No creatures found".to_string(),
+        |(_, stat_block)| {
+            format!(
+                "{} (CR {}, {} XP)",
+                stat_block.name,
+                stat_block.challenge_rating,
+                stat_block
+                    .experience
+                    .unwrap_or(stat_block.challenge_rating.experience())
+            )
+        },
+    );
+    let value = format!(
+        "Encounter Builder\n\
+        Party level: {} (Up/Down)\n\
+        Party size: {} (Page Up/Page Down), effective level {}\n\
+        Difficulty: {} (Left/Right)\n\
+        XP: {spent} of {budget}{}\n\
+        Creature: {selected} (Tab)\n\
+        Left click: place, Right click: remove\n\
+        Enter: save and use in battle, Escape: main menu\n\
+        {}",
+        builder.party_level,
+        builder.party_size,
+        builder.effective_level(),
+        builder.difficulty,
+        if spent > budget { " (over budget)" } else { "" },
+        builder.status,
+    );
+    if let Some(section) = text.sections.first_mut() {
+        section.value = value;
+    }
+}
+
+pub fn cleanup_encounter_builder(
+    query_builder: Query<Entity, With<EncounterBuilderEntity>>,
+    query_effects: Query<(Entity, &Effect)>,
+    mut commands: Commands,
+) {
+    for (entity, _) in query_effects
+        .iter()
+        .filter(|(_, effect)| query_builder.contains(effect.target))
+    {
+        commands.entity(entity).despawn();
+    }
+    for entity in query_builder.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct EncounterBuilderPlugin;
+
+impl Plugin for EncounterBuilderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EncounterBuilder>()
+            .init_resource::<CustomAssetLoadState<CreatureAsset>>()
+            .add_systems(
+                OnEnter(AppState::EncounterBuilder),
+                (setup_encounter_builder, flex_grid::setup_flex_grid),
+            )
+            .add_systems(
+                Update,
+                exit_encounter_builder.run_if(in_state(AppState::EncounterBuilder)),
+            )
+            .add_systems(
+                Update,
+                (
+                    adjust_encounter_settings,
+                    place_encounter_creatures,
+                    save_encounter,
+                    update_encounter_builder_text,
+                )
+                    .chain()
+                    .run_if(is_custom_asset_loaded::<CreatureAsset>())
+                    .run_if(in_state(AppState::EncounterBuilder)),
+            )
+            .add_systems(
+                OnExit(AppState::EncounterBuilder),
+                (cleanup_encounter_builder, flex_grid::cleanup_flex_grid),
+            );
+    }
+}
@@ -143,6 +143,28 @@ pub fn setup_main_menu(
                             ));
                         });
                 })
+                .with_children(|parent| {
+                    // create Encounter Builder button
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: NAV_BUTTON_MIDDLE_STYLE,
+                                background_color: Color::rgb(0.2, 0.2, 0.8).into(),
+                                ..Default::default()
+                            },
+                            NavBarButtonType::EncounterBuilder,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Encounter Builder",
+                                TextStyle {
+                                    font: shared_font.clone(),
+                                    font_size: 30.,
+                                    color: Color::WHITE,
+                                },
+                            ));
+                        });
+                })
                 .with_children(|parent| {
                     // create Exit button
                     parent
@@ -201,6 +223,11 @@ pub fn button_system(
                     println!("--> set new app state to Character creation in crate::systems::main_menu::button_system");
                     return;
                 }
+                NavBarButtonType::EncounterBuilder => {
+                    next_state.set(AppState::EncounterBuilder);
+                    info!("Opening the encounter builder");
+                    return;
+                }
                 NavBarButtonType::Exit => {
                     println!("--> sending exit event in crate::systems::main_menu::button_system");
                     app_exit_event_writer.send(AppExit);
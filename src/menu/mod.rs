@@ -3,6 +3,7 @@ pub mod mouse;
 pub mod styles;
 
 pub mod character_creation;
+pub mod encounter_builder;
 pub mod main_menu;
 
 pub mod character_sheet;
@@ -106,6 +106,8 @@ pub const STAGES_OF_CREATION_BUTTON_COLOR: Color = Color::PURPLE;
 pub const RACE_BUTTON_COLOR_HOVERED: Color = Color::GRAY;
 pub const RACE_BUTTON_COLOR_SELECTED: Color = Color::SEA_GREEN;
 pub const RACE_BUTTON_COLOR: Color = Color::DARK_GRAY;
+// Bottom buttons that can't be used right now, like Next on a stage with issues.
+pub const DISABLED_BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
 
 // List button for central area, used for things like Alt traits select
 pub const LIST_BUTTON_COLOR: Color = Color::DARK_GREEN;
@@ -0,0 +1,278 @@
+use crate::{
+    system_scheduling::states::AppState,
+    systems::{
+        game::{
+            ai::{take_ai_turns, track_damage_taken, AiTurnEndedEvent},
+            attack::{resolve_attacks, AttackEvent, AttackResultEvent},
+            attack_of_opportunity::{
+                detect_ranged_provocations, detect_spell_provocations, resolve_provocations,
+                ProvokeEvent,
+            },
+            class::{update_base_attack, update_base_saves},
+            combat_maneuver::{
+                apply_maneuver_conditions, resolve_combat_maneuvers, CombatManeuverEvent,
+                CombatManeuverResultEvent,
+            },
+            creature::{roll_creature_damage, spawn_creatures, SpawnCreatureEvent},
+            damage::{apply_damage, DamageEvent, DamageResultEvent},
+            defenses::{resolve_spell_effects, SpellEffectEvent, SpellEffectResultEvent},
+            dice::GameRng,
+            effects::{
+                rebuild_bonuses, sync_armor_effects, sync_class_feature_effects,
+                sync_condition_effects, Effect,
+            },
+            encounter::{
+                load_default_encounter, queue_encounter, spawn_encounter, PendingEncounter,
+                SelectedEncounter,
+            },
+            experience::{
+                award_experience, check_level_up, detect_defeats, reset_encounter_experience,
+                CreatureDefeatedEvent, EncounterExperience, ExperienceAwardedEvent,
+            },
+            saving_throw::{resolve_saving_throws, SavingThrowEvent, SavingThrowResultEvent},
+            time::{
+                advance_clock, end_round, reset_daily_uses, reset_round_resources, tick_conditions,
+                use_spell_like_abilities, AdvanceTimeEvent, EffectExpiredEvent, EndEffectEvent,
+                GameClock, SpellLikeAbilityUsedEvent, TimeAdvancedEvent, TimedEffectAppExt,
+                UseSpellLikeAbilityEvent,
+            },
+        },
+        grid_systems::{
+            area_of_effect::{place_area, preview_area, AreaTargetedEvent, AreaTargeting},
+            fog_of_war::{spawn_fog_of_war, update_fog_of_war, ExploredSquares},
+            pathfinding::TerrainMap,
+            token::{
+                preview_path, select_or_move_token, sync_token_transforms, PlannedPath,
+                SelectedToken,
+            },
+            vision::{
+                setup_battle_area, update_visible_squares, AmbientLight, BattleArea, LightSource,
+            },
+        },
+    },
+};
+use bevy::prelude::*;
+
+pub struct BattlePlugin;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+enum BattleSet {
+    Stats,
+    Movement,
+    Targeting,
+    Vision,
+    Provocations,
+    Attacks,
+    Maneuvers,
+    Saves,
+    Damage,
+}
+
+impl Plugin for BattlePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Add Events
+            .add_event::<AttackEvent>()
+            .add_event::<AttackResultEvent>()
+            .add_event::<ProvokeEvent>()
+            .add_event::<SpawnCreatureEvent>()
+            .add_event::<AreaTargetedEvent>()
+            .add_event::<CombatManeuverEvent>()
+            .add_event::<CombatManeuverResultEvent>()
+            .add_event::<SavingThrowEvent>()
+            .add_event::<SpellEffectEvent>()
+            .add_event::<SpellEffectResultEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<DamageResultEvent>()
+            .add_event::<CreatureDefeatedEvent>()
+            .add_event::<ExperienceAwardedEvent>()
+            .add_event::<SavingThrowResultEvent>()
+            .add_event::<AiTurnEndedEvent>()
+            .add_event::<AdvanceTimeEvent>()
+            .add_event::<TimeAdvancedEvent>()
+            .add_event::<EndEffectEvent>()
+            .add_event::<EffectExpiredEvent>()
+            .add_event::<UseSpellLikeAbilityEvent>()
+            .add_event::<SpellLikeAbilityUsedEvent>()
+            .init_resource::<GameRng>()
+            .init_resource::<GameClock>()
+            .init_resource::<SelectedToken>()
+            .init_resource::<PlannedPath>()
+            .init_resource::<AreaTargeting>()
+            .init_resource::<TerrainMap>()
+            .init_resource::<AmbientLight>()
+            .init_resource::<BattleArea>()
+            .init_resource::<ExploredSquares>()
+            .init_resource::<SelectedEncounter>()
+            .init_resource::<EncounterExperience>()
+            .add_systems(Startup, load_default_encounter)
+            // Time passes outside of battles too, so the clock isn't tied to
+            // the battle state.
+            .add_systems(
+                Update,
+                (
+                    end_round.run_if(in_state(AppState::Battle)),
+                    advance_clock,
+                    (reset_round_resources, tick_conditions, reset_daily_uses),
+                    use_spell_like_abilities,
+                )
+                    .chain(),
+            )
+            .add_timed_effect::<LightSource>()
+            .add_timed_effect::<Effect>()
+            .add_systems(Update, spawn_creatures)
+            .add_systems(
+                Update,
+                spawn_encounter
+                    .before(spawn_creatures)
+                    .run_if(resource_exists::<PendingEncounter>())
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        sync_class_feature_effects,
+                        sync_condition_effects,
+                        sync_armor_effects,
+                    ),
+                    apply_deferred,
+                    rebuild_bonuses,
+                )
+                    .chain()
+                    .after(advance_clock)
+                    .before(BattleSet::Stats),
+            )
+            .add_systems(
+                OnEnter(AppState::Battle),
+                (
+                    setup_battle_area,
+                    spawn_fog_of_war,
+                    reset_encounter_experience,
+                    queue_encounter,
+                )
+                    .chain(),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Stats
+                    .before(BattleSet::Maneuvers)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Movement
+                    .before(BattleSet::Vision)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Vision
+                    .before(BattleSet::Attacks)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Targeting
+                    .after(BattleSet::Movement)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Provocations
+                    .after(BattleSet::Movement)
+                    .after(BattleSet::Targeting)
+                    .before(BattleSet::Attacks)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Attacks
+                    .before(BattleSet::Maneuvers)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Maneuvers.run_if(in_state(AppState::Battle)),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Saves
+                    .after(BattleSet::Stats)
+                    .after(BattleSet::Targeting)
+                    .after(BattleSet::Attacks)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .add_systems(
+                Update,
+                (update_base_attack, update_base_saves).in_set(BattleSet::Stats),
+            )
+            .add_systems(
+                Update,
+                (
+                    take_ai_turns.after(reset_round_resources),
+                    preview_path,
+                    select_or_move_token,
+                    sync_token_transforms,
+                )
+                    .chain()
+                    .in_set(BattleSet::Movement),
+            )
+            .add_systems(
+                Update,
+                (update_visible_squares, update_fog_of_war)
+                    .chain()
+                    .in_set(BattleSet::Vision),
+            )
+            .add_systems(
+                Update,
+                (preview_area, place_area)
+                    .chain()
+                    .in_set(BattleSet::Targeting),
+            )
+            .add_systems(
+                Update,
+                (
+                    detect_ranged_provocations,
+                    detect_spell_provocations,
+                    resolve_provocations,
+                )
+                    .chain()
+                    .in_set(BattleSet::Provocations),
+            )
+            .add_systems(Update, resolve_attacks.in_set(BattleSet::Attacks))
+            .add_systems(
+                Update,
+                (resolve_combat_maneuvers, apply_maneuver_conditions)
+                    .chain()
+                    .in_set(BattleSet::Maneuvers),
+            )
+            .configure_set(
+                Update,
+                BattleSet::Damage
+                    .after(BattleSet::Saves)
+                    .run_if(in_state(AppState::Battle)),
+            )
+            .add_systems(
+                Update,
+                (resolve_spell_effects, resolve_saving_throws)
+                    .chain()
+                    .in_set(BattleSet::Saves),
+            )
+            .add_systems(
+                Update,
+                (
+                    roll_creature_damage.after(BattleSet::Attacks),
+                    apply_damage,
+                    track_damage_taken,
+                    detect_defeats,
+                    apply_deferred,
+                    award_experience,
+                )
+                    .chain()
+                    .in_set(BattleSet::Damage),
+            )
+            // Level ups can come from XP outside of battle too.
+            .add_systems(Update, check_level_up);
+    }
+}
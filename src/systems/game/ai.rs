@@ -0,0 +1,440 @@
+use crate::systems::{
+    game::{
+        attack::AttackEvent,
+        attack_of_opportunity::{ProvokeEvent, ProvokingAction, ThreatRange},
+        character::SpellLikeAbilities,
+        character::{CharacterSize, CombatManeuverName},
+        combat_maneuver::CombatManeuverEvent,
+        creature::CreatureAttacks,
+        damage::{DamageResultEvent, HitPoints},
+        dice::GameRng,
+        equipment::WieldedWeapon,
+        experience::Defeated,
+        magic::{SpellLikeAbility, SpellName},
+        time::{DailyUses, TimeAdvancedEvent, UseSpellLikeAbilityEvent},
+    },
+    grid_systems::{
+        grid_position::{Footprint, GridPos, FEET_PER_SQUARE},
+        pathfinding::{find_path, Occupancy, Path, TerrainMap},
+        token::{occupancy_for, Faction, MovementRemaining, Token},
+    },
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+// How many turns of movement ahead a creature looks when its target is out of
+// reach, so it heads the right way around walls.
+const ADVANCE_LOOKAHEAD: usize = 4;
+
+// Behavior Profile
+// Which opponent a creature goes after.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum TargetPriority {
+    #[default]
+    Nearest,
+    // Fewest hit points left.
+    Weakest,
+    // Has dealt the creature the most damage so far.
+    MostThreatening,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum AttackPreference {
+    #[default]
+    Melee,
+    // Keeps its distance and shoots, unless an opponent already threatens it.
+    Ranged,
+}
+
+// How a creature fights on its own, set in its stat block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(default)]
+pub struct BehaviorProfile {
+    pub target: TargetPriority,
+    pub attack: AttackPreference,
+    // A combat maneuver tried instead of a melee attack, and the percent
+    // chance of trying it each turn.
+    pub maneuver: Option<(CombatManeuverName, usize)>,
+    // The percent chance each turn of using a spell-like ability that has
+    // uses left, instead of attacking.
+    pub ability: usize,
+}
+
+// Creatures with this take their own turns at the start of each round.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AiController {
+    pub profile: BehaviorProfile,
+    // Damage dealt to the creature by each opponent.
+    pub damage_taken: HashMap<Entity, i32>,
+}
+
+impl AiController {
+    pub fn new(profile: BehaviorProfile) -> Self {
+        Self {
+            profile,
+            damage_taken: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AiAction {
+    // The name of the attack from the stat block.
+    Attack(String),
+    Maneuver(CombatManeuverName),
+    SpellLikeAbility(SpellName),
+    // Moved toward the target without getting to act against it.
+    Advance,
+    // No opponent left to act against.
+    Wait,
+}
+
+// What a creature on the battle map needs to know about everyone else.
+#[derive(Clone, Copy, Debug)]
+struct Combatant {
+    entity: Entity,
+    pos: GridPos,
+    footprint: Footprint,
+    faction: Faction,
+    reach: ThreatRange,
+    hit_points: Option<i32>,
+}
+
+impl Combatant {
+    fn threatens(&self, other: &Combatant) -> bool {
+        other
+            .pos
+            .squares(&other.footprint)
+            .iter()
+            .any(|square| self.reach.threatens(&self.pos, &self.footprint, square))
+    }
+}
+
+fn choose_target<'a>(
+    actor: &Combatant,
+    controller: &AiController,
+    combatants: &'a [Combatant],
+) -> Option<&'a Combatant> {
+    let opponents = combatants
+        .iter()
+        .filter(|other| actor.faction.is_hostile_to(&other.faction));
+    let distance = |other: &Combatant| actor.pos.distance_to(&other.pos);
+    match controller.profile.target {
+        TargetPriority::Nearest => opponents.min_by_key(|other| distance(other)),
+        TargetPriority::Weakest => {
+            opponents.min_by_key(|other| (other.hit_points.unwrap_or(i32::MAX), distance(other)))
+        }
+        TargetPriority::MostThreatening => opponents.min_by_key(|other| {
+            let damage = controller.damage_taken.get(&other.entity).copied();
+            (Reverse(damage.unwrap_or(0)), distance(other))
+        }),
+    }
+}
+
+// The cheapest path costing at most `max_cost` to a square from which the
+// actor threatens the target.
+fn path_into_reach(
+    actor: &Combatant,
+    target: &Combatant,
+    terrain: &TerrainMap,
+    occupancy: &Occupancy,
+    max_cost: usize,
+    diagonals_taken: usize,
+) -> Option<(GridPos, Path)> {
+    let span = (actor.reach.max / FEET_PER_SQUARE + actor.footprint.0 + target.footprint.0) as i32;
+    let mut best: Option<(GridPos, Path)> = None;
+    for dy in -span..=span {
+        for dx in -span..=span {
+            let goal = GridPos::new(target.pos.x + dx, target.pos.y + dy);
+            let placed = Combatant {
+                pos: goal,
+                ..*actor
+            };
+            if !placed.threatens(target) {
+                continue;
+            }
+            let Some(path) = find_path(
+                actor.pos,
+                goal,
+                &actor.footprint,
+                terrain,
+                occupancy,
+                max_cost,
+                diagonals_taken,
+            ) else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|(_, best)| path.cost < best.cost) {
+                best = Some((goal, path));
+            }
+        }
+    }
+    best
+}
+
+// The furthest square along a longer path that can be reached and ended in
+// with the movement left.
+fn partial_path(
+    actor: &Combatant,
+    path: &Path,
+    terrain: &TerrainMap,
+    occupancy: &Occupancy,
+    movement: &MovementRemaining,
+) -> Option<(GridPos, Path)> {
+    path.squares.iter().rev().find_map(|square| {
+        find_path(
+            actor.pos,
+            *square,
+            &actor.footprint,
+            terrain,
+            occupancy,
+            movement.feet,
+            movement.diagonals_taken,
+        )
+        .map(|path| (*square, path))
+    })
+}
+
+// The first spell-like ability with uses left, in a fixed order so the choice
+// doesn't depend on hash map order.
+fn usable_ability(
+    abilities: Option<&SpellLikeAbilities>,
+    daily_uses: Option<&DailyUses>,
+) -> Option<SpellLikeAbility> {
+    abilities?
+        .0
+        .values()
+        .flatten()
+        .filter(|ability| daily_uses.is_none_or(|uses| uses.can_use(ability)))
+        .min_by_key(|ability| (ability.source, ability.spell_name))
+        .copied()
+}
+
+// Events
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct AiTurnEndedEvent {
+    pub actor: Entity,
+    pub target: Option<Entity>,
+    // Where the creature moved to, if it moved.
+    pub moved_to: Option<GridPos>,
+    pub action: AiAction,
+}
+
+// Systems
+type CombatantQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut GridPos,
+        &'static Footprint,
+        &'static Faction,
+        &'static CharacterSize,
+        Option<&'static WieldedWeapon>,
+        Option<&'static HitPoints>,
+        &'static mut MovementRemaining,
+    ),
+    (With<Token>, Without<Defeated>),
+>;
+
+type AiQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static AiController,
+        Option<&'static CreatureAttacks>,
+        Option<&'static SpellLikeAbilities>,
+        Option<&'static DailyUses>,
+    ),
+>;
+
+// At the start of each round, every creature with an `AiController` takes its
+// turn before the party acts, in spawn order. It picks a target by its
+// profile, then either shoots from where it stands, or moves into reach and
+// makes a melee attack or maneuver. A creature that can't reach its target
+// this turn moves as far toward it as it can, and shoots instead if it has a
+// ranged attack. A creature with a spell-like ability left may use it instead
+// of attacking. The only random choices are whether to use an ability or try
+// a maneuver, which are rolled on `GameRng`, so a fight replays the same way
+// from the same seed.
+#[allow(clippy::too_many_arguments)]
+pub fn take_ai_turns(
+    mut event_reader: EventReader<TimeAdvancedEvent>,
+    mut query_combatant: CombatantQuery,
+    query_ai: AiQuery,
+    terrain: Res<TerrainMap>,
+    mut rng: ResMut<GameRng>,
+    mut provoke_writer: EventWriter<ProvokeEvent>,
+    mut attack_writer: EventWriter<AttackEvent>,
+    mut maneuver_writer: EventWriter<CombatManeuverEvent>,
+    mut ability_writer: EventWriter<UseSpellLikeAbilityEvent>,
+    mut turn_writer: EventWriter<AiTurnEndedEvent>,
+) {
+    if event_reader.iter().count() == 0 {
+        return;
+    }
+    let mut combatants: Vec<Combatant> = query_combatant
+        .iter()
+        .map(
+            |(entity, pos, footprint, faction, size, weapon, hit_points, _)| Combatant {
+                entity,
+                pos: *pos,
+                footprint: *footprint,
+                faction: *faction,
+                reach: ThreatRange::new(size, weapon),
+                hit_points: hit_points.map(|hit_points| hit_points.current),
+            },
+        )
+        .collect();
+    combatants.sort_by_key(|combatant| combatant.entity);
+
+    for index in 0..combatants.len() {
+        let mut actor = combatants[index];
+        let Ok((controller, attacks, abilities, daily_uses)) = query_ai.get(actor.entity) else {
+            continue;
+        };
+        let Ok((.., movement)) = query_combatant.get(actor.entity) else {
+            continue;
+        };
+        let mut movement = *movement;
+        let Some(target) = choose_target(&actor, controller, &combatants).copied() else {
+            turn_writer.send(AiTurnEndedEvent {
+                actor: actor.entity,
+                target: None,
+                moved_to: None,
+                action: AiAction::Wait,
+            });
+            continue;
+        };
+        let melee = attacks.and_then(|attacks| attacks.melee().next());
+        let ranged = attacks.and_then(|attacks| attacks.ranged().next());
+        let threatened = combatants
+            .iter()
+            .any(|other| other.faction.is_hostile_to(&actor.faction) && other.threatens(&actor));
+        let occupancy = occupancy_for(
+            actor.entity,
+            &actor.faction,
+            combatants
+                .iter()
+                .map(|other| (other.entity, &other.pos, &other.footprint, &other.faction)),
+        );
+
+        let keep_distance = controller.profile.attack == AttackPreference::Ranged
+            && ranged.is_some()
+            && !threatened;
+        let in_reach = actor.threatens(&target);
+        let mut step = None;
+        if !keep_distance && !in_reach {
+            step = path_into_reach(
+                &actor,
+                &target,
+                &terrain,
+                &occupancy,
+                movement.feet,
+                movement.diagonals_taken,
+            );
+            // Out of reach this turn, so a creature that can shoot stays put.
+            if step.is_none() && ranged.is_none() {
+                step = path_into_reach(
+                    &actor,
+                    &target,
+                    &terrain,
+                    &occupancy,
+                    movement.feet * ADVANCE_LOOKAHEAD,
+                    movement.diagonals_taken,
+                )
+                .and_then(|(_, path)| partial_path(&actor, &path, &terrain, &occupancy, &movement));
+            }
+        }
+
+        let moved_to = step.map(|(goal, path)| {
+            movement.spend(path.cost, path.diagonals);
+            let mut positions = vec![actor.pos];
+            positions.extend(path.squares.iter().copied());
+            provoke_writer.send(ProvokeEvent {
+                actor: actor.entity,
+                action: ProvokingAction::Move(positions),
+            });
+            goal
+        });
+        if let Some(goal) = moved_to {
+            actor.pos = goal;
+            combatants[index].pos = goal;
+            if let Ok((_, mut pos, .., mut remaining)) = query_combatant.get_mut(actor.entity) {
+                *pos = goal;
+                *remaining = movement;
+            }
+        }
+
+        let ability = usable_ability(abilities, daily_uses)
+            .filter(|_| controller.profile.ability > 0)
+            .filter(|_| rng.below(100) < controller.profile.ability);
+        let action = match (ability, melee, ranged) {
+            (Some(ability), ..) => {
+                ability_writer.send(UseSpellLikeAbilityEvent {
+                    caster: actor.entity,
+                    ability,
+                });
+                AiAction::SpellLikeAbility(ability.spell_name)
+            }
+            (_, Some(melee), _) if !keep_distance && actor.threatens(&target) => {
+                match controller.profile.maneuver {
+                    Some((maneuver, chance)) if rng.below(100) < chance => {
+                        maneuver_writer.send(CombatManeuverEvent {
+                            attacker: actor.entity,
+                            target: target.entity,
+                            maneuver,
+                            bonus: 0,
+                            dirty_trick_condition: None,
+                        });
+                        AiAction::Maneuver(maneuver)
+                    }
+                    _ => {
+                        attack_writer.send(AttackEvent {
+                            attacker: actor.entity,
+                            target: target.entity,
+                            ranged: false,
+                            bonus: 0,
+                            opportunity: false,
+                        });
+                        AiAction::Attack(melee.name.clone())
+                    }
+                }
+            }
+            (.., Some(ranged)) if moved_to.is_none() => {
+                attack_writer.send(AttackEvent {
+                    attacker: actor.entity,
+                    target: target.entity,
+                    ranged: true,
+                    bonus: 0,
+                    opportunity: false,
+                });
+                AiAction::Attack(ranged.name.clone())
+            }
+            _ => AiAction::Advance,
+        };
+        turn_writer.send(AiTurnEndedEvent {
+            actor: actor.entity,
+            target: Some(target.entity),
+            moved_to,
+            action,
+        });
+    }
+}
+
+// Keeps count of who has been hurting each AI controlled creature.
+pub fn track_damage_taken(
+    mut event_reader: EventReader<DamageResultEvent>,
+    mut query_controller: Query<&mut AiController>,
+) {
+    for event in event_reader.iter().filter(|event| event.dealt > 0) {
+        let (Some(source), Ok(mut controller)) =
+            (event.source, query_controller.get_mut(event.target))
+        else {
+            continue;
+        };
+        *controller.damage_taken.entry(source).or_default() += event.dealt;
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     menu::character_creation::layout::generics::list_traits::AsVec,
-    systems::game::{character::*, class::*, skills::*},
+    systems::game::{character::*, class::*, proficiency::Proficiency, skills::*},
 };
 use bevy::{prelude::*, utils::HashMap};
 use serde::Deserialize;
@@ -57,8 +57,8 @@ pub struct ArchetypeInfo {
     pub archetype_features: Option<Vec<ArchetypeFeature>>,
     pub skills: Option<Vec<SkillName>>,
     pub skill_ranks: Option<usize>,
-    pub gains_proficiency: Option<usize>,
-    pub loses_proficiency: Option<usize>,
+    pub gains_proficiency: Option<Vec<Proficiency>>,
+    pub loses_proficiency: Option<Vec<Proficiency>>,
 }
 
 /// Gets all the class features this archetype replaces in a vec
@@ -0,0 +1,265 @@
+use crate::systems::{
+    game::{
+        character::*,
+        class::BaseAttack,
+        dice::GameRng,
+        effects::{replace_cover_effect, Effect},
+        equipment::{WieldedWeapon, WornArmor},
+        proficiency::Proficiencies,
+        time::GameClock,
+    },
+    grid_systems::{
+        grid_position::{Footprint, GridPos},
+        line_of_sight::{determine_cover, Cover},
+        token::Token,
+        vision::{Concealment, Lighting, Senses},
+    },
+};
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+// Armor Class
+// AC = 10 + Dex modifier + size modifier + bonuses that apply against the
+// attacker, which includes any cover from the attacker.
+pub fn armor_class(
+    scores: &AbilityScores,
+    size: &SizeCategory,
+    bonuses: Option<&ArmorClassBonuses>,
+    attacker: Entity,
+    attacker_subtypes: Option<&CreatureSubtypes>,
+) -> i32 {
+    10 + scores.modifier(&AbilityScore::Dexterity)
+        + size.size_modifier()
+        + bonuses.map_or(0, |bonuses| bonuses.total_vs(attacker, attacker_subtypes))
+}
+
+// Attack bonus = BAB + Str modifier (melee) or Dex modifier (ranged) + size
+// modifier + bonuses that apply against the target.
+pub fn attack_bonus(
+    base_attack: &BaseAttack,
+    scores: &AbilityScores,
+    size: &SizeCategory,
+    ranged: bool,
+    bonuses: Option<&AttackRollBonuses>,
+    target_type: Option<&CreatureType>,
+    target_subtypes: Option<&CreatureSubtypes>,
+) -> i32 {
+    let ability = if ranged {
+        AbilityScore::Dexterity
+    } else {
+        AbilityScore::Strength
+    };
+    base_attack.base as i32
+        + scores.modifier(&ability)
+        + size.size_modifier()
+        + bonuses.map_or(0, |bonuses| bonuses.total_vs(target_type, target_subtypes))
+}
+
+// Events
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct AttackEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub ranged: bool,
+    // Situational modifiers to the attack roll.
+    pub bonus: i32,
+    // Attacks of opportunity are resolved before the other attacks sent the
+    // same frame, since they interrupt the action that provoked them.
+    pub opportunity: bool,
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct AttackResultEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub ranged: bool,
+    // The natural d20 roll, which is 0 when no attack could be made.
+    pub roll: i32,
+    pub total: i32,
+    pub armor_class: i32,
+    pub cover: Cover,
+    pub concealment: Concealment,
+    pub hit: bool,
+}
+
+// Systems
+type AttackStatsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static BaseAttack>,
+        Option<&'static AbilityScores>,
+        Option<&'static CharacterSize>,
+        Option<&'static AttackRollBonuses>,
+        Option<&'static CreatureType>,
+        Option<&'static CreatureSubtypes>,
+    ),
+>;
+
+type ProficiencyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Proficiencies,
+        Option<&'static WieldedWeapon>,
+        Option<&'static WornArmor>,
+    ),
+>;
+
+type SensesQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static NormalVision>,
+        Option<&'static LowLightVision>,
+        Option<&'static DarkVision>,
+    ),
+>;
+
+// Works out the cover between the attacker and target, records it as a cover
+// effect on the target against that attacker for the round, then rolls
+// the attack. A natural 20 always hits and a natural 1 always misses. A hit
+// against a concealed target can still miss on the concealment miss chance.
+// Creatures missing stat components use the same defaults as combat
+// maneuvers, and creatures not on the grid never have cover. Attackers with
+// `Proficiencies` take the penalties for a weapon or armor they aren't
+// proficient with; other creatures are assumed to be.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_attacks(
+    mut event_reader: EventReader<AttackEvent>,
+    mut event_writer: EventWriter<AttackResultEvent>,
+    query_stats: AttackStatsQuery,
+    query_armor_class: Query<Option<&ArmorClassBonuses>>,
+    query_effects: Query<(Entity, &Effect)>,
+    query_token: Query<(Entity, &GridPos, &Footprint), With<Token>>,
+    query_senses: SensesQuery,
+    query_proficiency: ProficiencyQuery,
+    lighting: Lighting,
+    clock: Res<GameClock>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+) {
+    let default_scores = AbilityScores::new();
+    let mut events: Vec<&AttackEvent> = event_reader.iter().collect();
+    events.sort_by_key(|event| !event.opportunity);
+    for event in events {
+        let (Ok(attacker), Ok(target)) = (
+            query_stats.get(event.attacker),
+            query_stats.get(event.target),
+        ) else {
+            continue;
+        };
+        let (attacker_bab, attacker_scores, attacker_size, attack_bonuses, _, attacker_subtypes) =
+            attacker;
+        let (_, target_scores, target_size, _, target_type, target_subtypes) = target;
+
+        let positions = (
+            query_token.get(event.attacker),
+            query_token.get(event.target),
+        );
+        let cover = match positions {
+            (Ok((_, attacker_pos, attacker_footprint)), Ok((_, target_pos, target_footprint))) => {
+                let creatures: HashSet<GridPos> = query_token
+                    .iter()
+                    .filter(|(entity, _, _)| *entity != event.attacker && *entity != event.target)
+                    .flat_map(|(_, pos, footprint)| pos.squares(footprint))
+                    .collect();
+                determine_cover(
+                    attacker_pos,
+                    attacker_footprint,
+                    target_pos,
+                    target_footprint,
+                    &lighting.terrain,
+                    &creatures,
+                    event.ranged,
+                )
+            }
+            _ => Cover::None,
+        };
+        let concealment = match (positions, query_senses.get(event.attacker)) {
+            (
+                (Ok((_, attacker_pos, _)), Ok((_, target_pos, _))),
+                Ok((normal, low_light, darkvision)),
+            ) => Senses::new(normal, low_light, darkvision).concealment_of(
+                attacker_pos,
+                target_pos,
+                &lighting,
+            ),
+            _ => Concealment::None,
+        };
+
+        // The cover effect only reaches the container next frame, so this
+        // attack uses a copy with the cover already swapped in.
+        let mut armor_class_bonuses = query_armor_class
+            .get(event.target)
+            .ok()
+            .flatten()
+            .cloned()
+            .unwrap_or_else(|| ArmorClassBonuses::from(Vec::new()));
+        armor_class_bonuses.set_cover_vs(event.attacker, cover.ac_bonus());
+        replace_cover_effect(
+            event.target,
+            event.attacker,
+            cover.ac_bonus(),
+            &clock,
+            &query_effects,
+            &mut commands,
+        );
+
+        let target_ac = armor_class(
+            target_scores.unwrap_or(&default_scores),
+            &target_size.map_or(SizeCategory::Medium, |size| size.category),
+            Some(&armor_class_bonuses),
+            event.attacker,
+            attacker_subtypes,
+        );
+        if cover == Cover::Total {
+            event_writer.send(AttackResultEvent {
+                attacker: event.attacker,
+                target: event.target,
+                ranged: event.ranged,
+                roll: 0,
+                total: 0,
+                armor_class: target_ac,
+                cover,
+                concealment,
+                hit: false,
+            });
+            continue;
+        }
+
+        let roll = rng.d20();
+        let total = roll
+            + event.bonus
+            + attack_bonus(
+                attacker_bab.unwrap_or(&BaseAttack::default()),
+                attacker_scores.unwrap_or(&default_scores),
+                &attacker_size.map_or(SizeCategory::Medium, |size| size.category),
+                event.ranged,
+                attack_bonuses,
+                target_type,
+                target_subtypes,
+            )
+            + query_proficiency
+                .get(event.attacker)
+                .map_or(0, |(proficiencies, weapon, armor)| {
+                    proficiencies.attack_penalty(weapon, armor)
+                });
+        let hit = match roll {
+            20 => true,
+            1 => false,
+            _ => total >= target_ac,
+        } && rng.below(100) >= concealment.miss_chance();
+        event_writer.send(AttackResultEvent {
+            attacker: event.attacker,
+            target: event.target,
+            ranged: event.ranged,
+            roll,
+            total,
+            armor_class: target_ac,
+            cover,
+            concealment,
+            hit,
+        });
+    }
+}
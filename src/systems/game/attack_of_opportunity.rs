@@ -0,0 +1,233 @@
+use crate::systems::{
+    game::{
+        attack::AttackEvent,
+        character::{AbilityScore, AbilityScores, CharacterSize, SizeCategory, SizeType},
+        class::{ClassFeature, ClassFeatures, FighterFeature},
+        equipment::WieldedWeapon,
+        time::SpellLikeAbilityUsedEvent,
+    },
+    grid_systems::{
+        area_of_effect::AreaTargetedEvent,
+        grid_position::{Footprint, GridPos, FEET_PER_SQUARE},
+        token::{Faction, Token},
+    },
+};
+use bevy::prelude::*;
+
+// Reach
+// Natural reach in feet. Creatures smaller than Small have no reach, and only
+// threaten their own square, which isn't tracked on the grid.
+pub fn natural_reach(size: &CharacterSize) -> usize {
+    use SizeCategory::*;
+    match (size.category, size.size_type) {
+        (Fine | Diminutive | Tiny, _) => 0,
+        (Small | Medium, _) => 5,
+        (Large, SizeType::Tall) => 10,
+        (Large, SizeType::Long) => 5,
+        (Huge, SizeType::Tall) => 15,
+        (Huge, SizeType::Long) => 10,
+        (Gargantuan, SizeType::Tall) => 20,
+        (Gargantuan, SizeType::Long) => 15,
+        (Colossal, SizeType::Tall) => 30,
+        (Colossal, SizeType::Long) => 20,
+    }
+}
+
+// The distances in feet at which a creature threatens squares. A reach weapon
+// doubles natural reach, but can't be used against anything within natural
+// reach.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThreatRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl ThreatRange {
+    pub fn new(size: &CharacterSize, weapon: Option<&WieldedWeapon>) -> Self {
+        let reach = natural_reach(size);
+        if weapon.is_some_and(|weapon| weapon.0.is_reach()) {
+            Self {
+                min: reach + FEET_PER_SQUARE,
+                max: reach * 2,
+            }
+        } else {
+            Self {
+                min: FEET_PER_SQUARE,
+                max: reach,
+            }
+        }
+    }
+
+    // Whether a square is threatened by a creature at `pos`.
+    pub fn threatens(&self, pos: &GridPos, footprint: &Footprint, square: &GridPos) -> bool {
+        pos.squares(footprint).iter().any(|own| {
+            let distance = threat_distance(own, square);
+            distance >= self.min && distance <= self.max
+        })
+    }
+}
+
+// Distance for working out reach. The second diagonal is counted as 10 feet
+// rather than 15, so that 10 feet of reach threatens it.
+fn threat_distance(from: &GridPos, to: &GridPos) -> usize {
+    if from.steps_to(to) == (0, 2) {
+        2 * FEET_PER_SQUARE
+    } else {
+        from.distance_to(to)
+    }
+}
+
+// Attacks of opportunity
+// The Combat Reflexes feat.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct CombatReflexes;
+
+// Attacks of opportunity made this round.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AttacksOfOpportunity {
+    pub made: usize,
+}
+
+impl AttacksOfOpportunity {
+    // One per round, or one plus the Dex modifier with Combat Reflexes.
+    pub fn per_round(scores: Option<&AbilityScores>, combat_reflexes: bool) -> usize {
+        if combat_reflexes {
+            let dexterity = scores.map_or(0, |scores| scores.modifier(&AbilityScore::Dexterity));
+            1 + dexterity.max(0) as usize
+        } else {
+            1
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.made = 0;
+    }
+}
+
+// Actions that provoke attacks of opportunity from creatures threatening the
+// actor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProvokingAction {
+    // Every position of a move in order, including the start. Leaving any of
+    // them while threatened provokes, but only once from each opponent.
+    Move(Vec<GridPos>),
+    CastSpell,
+    RangedAttack,
+}
+
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct ProvokeEvent {
+    pub actor: Entity,
+    pub action: ProvokingAction,
+}
+
+// Systems
+// Ranged attacks made while threatened provoke, except with a bow for an
+// archer with Safe Shot.
+pub fn detect_ranged_provocations(
+    mut event_reader: EventReader<AttackEvent>,
+    mut event_writer: EventWriter<ProvokeEvent>,
+    query_attacker: Query<(Option<&ClassFeatures>, Option<&WieldedWeapon>)>,
+) {
+    let safe_shot = ClassFeature::Fighter(FighterFeature::SafeShot);
+    for event in event_reader.iter().filter(|event| event.ranged) {
+        let exempt = query_attacker
+            .get(event.attacker)
+            .is_ok_and(|(features, weapon)| {
+                features.is_some_and(|features| features.has(&safe_shot))
+                    && weapon.is_some_and(|weapon| weapon.0.is_bow())
+            });
+        if !exempt {
+            event_writer.send(ProvokeEvent {
+                actor: event.attacker,
+                action: ProvokingAction::RangedAttack,
+            });
+        }
+    }
+}
+
+// Casting a spell while threatened provokes. Placing an area is the casting
+// path, with the caster as the area's source, and using a spell-like ability
+// provokes the same way.
+pub fn detect_spell_provocations(
+    mut area_reader: EventReader<AreaTargetedEvent>,
+    mut ability_reader: EventReader<SpellLikeAbilityUsedEvent>,
+    mut event_writer: EventWriter<ProvokeEvent>,
+) {
+    let casters = area_reader
+        .iter()
+        .filter_map(|event| event.source)
+        .chain(ability_reader.iter().map(|event| event.caster));
+    for caster in casters {
+        event_writer.send(ProvokeEvent {
+            actor: caster,
+            action: ProvokingAction::CastSpell,
+        });
+    }
+}
+
+type ThreatQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static GridPos,
+        &'static Footprint,
+        &'static Faction,
+        &'static CharacterSize,
+        Option<&'static WieldedWeapon>,
+        Option<&'static AbilityScores>,
+        Option<&'static CombatReflexes>,
+        &'static mut AttacksOfOpportunity,
+    ),
+    With<Token>,
+>;
+
+// Every hostile creature threatening the actor when it acts takes an attack
+// of opportunity against it, if it has any left this round.
+pub fn resolve_provocations(
+    mut event_reader: EventReader<ProvokeEvent>,
+    mut event_writer: EventWriter<AttackEvent>,
+    mut query_token: ThreatQuery,
+) {
+    for event in event_reader.iter() {
+        let Ok((_, actor_pos, actor_footprint, actor_faction, ..)) = query_token.get(event.actor)
+        else {
+            continue;
+        };
+        let (actor_footprint, actor_faction) = (*actor_footprint, *actor_faction);
+        let positions = match &event.action {
+            ProvokingAction::Move(positions) => {
+                positions[..positions.len().saturating_sub(1)].to_vec()
+            }
+            ProvokingAction::CastSpell | ProvokingAction::RangedAttack => vec![*actor_pos],
+        };
+
+        for (entity, pos, footprint, faction, size, weapon, scores, combat_reflexes, mut aoo) in
+            query_token.iter_mut()
+        {
+            if entity == event.actor || !faction.is_hostile_to(&actor_faction) {
+                continue;
+            }
+            let range = ThreatRange::new(size, weapon);
+            let threatened = positions.iter().any(|position| {
+                position
+                    .squares(&actor_footprint)
+                    .iter()
+                    .any(|square| range.threatens(pos, footprint, square))
+            });
+            if threatened
+                && aoo.made < AttacksOfOpportunity::per_round(scores, combat_reflexes.is_some())
+            {
+                aoo.made += 1;
+                event_writer.send(AttackEvent {
+                    attacker: entity,
+                    target: event.actor,
+                    ranged: false,
+                    bonus: 0,
+                    opportunity: true,
+                });
+            }
+        }
+    }
+}
@@ -8,7 +8,7 @@ use std::fmt;
 
 use crate::systems::game::magic;
 use crate::systems::game::race::IntoHashMapVecBuilder;
-use crate::systems::game::{class::*, magic::*, skills::*};
+use crate::systems::game::{class::*, magic::*, saving_throw::SaveEffect, skills::*};
 
 ////////////////////////////////////////////////////////
 //// Things that should probably go somewhere else
@@ -26,11 +26,45 @@ pub struct Magic;
 #[derive(Component, Clone, Debug, PartialEq, PartialOrd, Copy, Eq, Hash)]
 pub struct Poison;
 
-#[derive(Component, Clone, Debug, PartialEq, PartialOrd, Copy, Eq, Hash)]
+#[derive(Component, Clone, Debug, PartialEq, PartialOrd, Copy, Eq, Hash, Deserialize)]
 pub enum CombatManeuverName {
     BullRush,
+    DirtyTrick,
+    Disarm,
+    Drag,
+    Grapple,
+    Overrun,
+    Reposition,
+    Steal,
+    Sunder,
     Trip,
-    /* more here */
+}
+
+impl CombatManeuverName {
+    pub fn array() -> [CombatManeuverName; 10] {
+        use CombatManeuverName::*;
+        [
+            BullRush, DirtyTrick, Disarm, Drag, Grapple, Overrun, Reposition, Steal, Sunder, Trip,
+        ]
+    }
+}
+
+impl Display for CombatManeuverName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CombatManeuverName::*;
+        match self {
+            BullRush => write!(f, "Bull Rush"),
+            DirtyTrick => write!(f, "Dirty Trick"),
+            Disarm => write!(f, "Disarm"),
+            Drag => write!(f, "Drag"),
+            Grapple => write!(f, "Grapple"),
+            Overrun => write!(f, "Overrun"),
+            Reposition => write!(f, "Reposition"),
+            Steal => write!(f, "Steal"),
+            Sunder => write!(f, "Sunder"),
+            Trip => write!(f, "Trip"),
+        }
+    }
 }
 
 #[derive(Component, Clone, Debug, PartialEq, PartialOrd, Copy, Eq, Hash)]
@@ -61,6 +95,57 @@ pub struct Character {
 #[derive(Component, Clone, Debug)]
 pub struct ClassLevels(HashMap<PlayableClass, usize>);
 
+impl ClassLevels {
+    pub fn inner_ref(&self) -> &HashMap<PlayableClass, usize> {
+        &self.0
+    }
+    pub fn inner_ref_mut(&mut self) -> &mut HashMap<PlayableClass, usize> {
+        &mut self.0
+    }
+    pub fn level(&self, class: &PlayableClass) -> usize {
+        self.0.get(class).copied().unwrap_or(0)
+    }
+    pub fn total_level(&self) -> usize {
+        self.0.values().sum()
+    }
+    // Multiclass base attack bonuses are added together, each one found
+    // from the progression of its own class.
+    pub fn base_attack(&self, class_map: &ClassMap) -> BaseAttack {
+        BaseAttack::from(
+            self.0
+                .iter()
+                .filter_map(|(class, level)| {
+                    class_map.inner_ref().get(class).and_then(|info| {
+                        if info.bab_progression == BABProgression::None {
+                            None
+                        } else {
+                            Some(BaseAttack::from_progression(&info.bab_progression, *level).base)
+                        }
+                    })
+                })
+                .sum::<usize>(),
+        )
+    }
+    // Base saves from each class are added together in the same way.
+    pub fn base_saves(&self, class_map: &ClassMap) -> BaseSaves {
+        let mut base_saves = BaseSaves::default();
+        for (class, level) in self.0.iter() {
+            if let Some(info) = class_map.inner_ref().get(class) {
+                base_saves.fort += info.saving_throw_at_level(&SavingThrowName::Fort, *level);
+                base_saves.reflex += info.saving_throw_at_level(&SavingThrowName::Reflex, *level);
+                base_saves.will += info.saving_throw_at_level(&SavingThrowName::Will, *level);
+            }
+        }
+        base_saves
+    }
+}
+
+impl From<HashMap<PlayableClass, usize>> for ClassLevels {
+    fn from(other: HashMap<PlayableClass, usize>) -> Self {
+        Self(other)
+    }
+}
+
 #[derive(Component, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct PlayerName(String);
 
@@ -98,6 +183,25 @@ impl BonusesContainer<BonusType, CMDBonus> for CMDBonuses {
     }
 }
 
+impl CMDBonuses {
+    // Total of the bonuses that apply against the given maneuver, which are
+    // those without a limitation and those limited to that maneuver.
+    pub fn total_vs(&self, maneuver: CombatManeuverName) -> i32 {
+        self.0
+            .iter()
+            .map(|(bonus_type, bonuses)| {
+                stack_bonuses(
+                    bonus_type,
+                    bonuses
+                        .iter()
+                        .filter(|bonus| bonus.applies_vs(maneuver))
+                        .map(|bonus| bonus.bonus),
+                )
+            })
+            .sum()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Hash)]
 pub struct CMDBonus {
     pub bonus: i32,
@@ -112,6 +216,17 @@ impl NewBonusKey<BonusType> for CMDBonus {
     }
 }
 
+impl CMDBonus {
+    pub fn applies_vs(&self, maneuver: CombatManeuverName) -> bool {
+        self.limitation.is_empty()
+            || self.limitation.iter().any(|limitation| match limitation {
+                LimitationEnum::CombatManeuverName(name) => *name == maneuver,
+                LimitationEnum::None => true,
+                _ => false,
+            })
+    }
+}
+
 #[derive(Component, Clone, Debug, Eq, PartialEq)]
 pub struct AttackRollBonuses(pub HashMap<BonusType, Vec<AttackRollBonus>>);
 
@@ -147,6 +262,50 @@ impl NewBonusKey<BonusType> for AttackRollBonus {
     }
 }
 
+impl AttackRollBonus {
+    pub fn applies_vs(
+        &self,
+        target_type: Option<&CreatureType>,
+        target_subtypes: Option<&CreatureSubtypes>,
+    ) -> bool {
+        self.limitation.is_empty()
+            || self.limitation.iter().any(|limitation| match limitation {
+                LimitationEnum::None => true,
+                LimitationEnum::AttackingTargetTypeAndSubtype(creature_type, subtype) => {
+                    target_type == Some(creature_type)
+                        && target_subtypes.is_some_and(|subtypes| subtypes.0.contains(subtype))
+                }
+                LimitationEnum::TargetingType(creature_type) => target_type == Some(creature_type),
+                LimitationEnum::TargetingSubtype(subtype) => {
+                    target_subtypes.is_some_and(|subtypes| subtypes.0.contains(subtype))
+                }
+                _ => false,
+            })
+    }
+}
+
+impl AttackRollBonuses {
+    // Total of the bonuses that apply when attacking the given target.
+    pub fn total_vs(
+        &self,
+        target_type: Option<&CreatureType>,
+        target_subtypes: Option<&CreatureSubtypes>,
+    ) -> i32 {
+        self.0
+            .iter()
+            .map(|(bonus_type, bonuses)| {
+                stack_bonuses(
+                    bonus_type,
+                    bonuses
+                        .iter()
+                        .filter(|bonus| bonus.applies_vs(target_type, target_subtypes))
+                        .map(|bonus| bonus.bonus),
+                )
+            })
+            .sum()
+    }
+}
+
 #[derive(Component, Clone, Debug, Eq, PartialEq)]
 pub struct SpellLikeAbilities(pub HashMap<SlaSource, Vec<SpellLikeAbility>>);
 
@@ -290,6 +449,57 @@ impl NewBonusKey<BonusType> for ArmorClassBonus {
     }
 }
 
+impl ArmorClassBonus {
+    pub fn applies_vs(
+        &self,
+        attacker: Entity,
+        attacker_subtypes: Option<&CreatureSubtypes>,
+    ) -> bool {
+        match self.limitation {
+            LimitationEnum::None => true,
+            LimitationEnum::AttacksFrom(entity) => entity == attacker,
+            LimitationEnum::AttacksByCreatureSubtype(subtype) => {
+                attacker_subtypes.is_some_and(|subtypes| subtypes.0.contains(&subtype))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ArmorClassBonuses {
+    // Total of the bonuses that apply against attacks from `attacker`.
+    pub fn total_vs(&self, attacker: Entity, attacker_subtypes: Option<&CreatureSubtypes>) -> i32 {
+        self.0
+            .iter()
+            .map(|(bonus_type, bonuses)| {
+                stack_bonuses(
+                    bonus_type,
+                    bonuses
+                        .iter()
+                        .filter(|bonus| bonus.applies_vs(attacker, attacker_subtypes))
+                        .map(|bonus| bonus.bonus),
+                )
+            })
+            .sum()
+    }
+
+    // Replaces any cover bonus against `attacker` with one for the given
+    // amount.
+    pub fn set_cover_vs(&mut self, attacker: Entity, bonus: i32) {
+        let limitation = LimitationEnum::AttacksFrom(attacker);
+        if let Some(bonuses) = self.0.get_mut(&BonusType::Cover) {
+            bonuses.retain(|existing| existing.limitation != limitation);
+        }
+        if bonus > 0 {
+            self.add_or_insert(ArmorClassBonus {
+                bonus,
+                bonus_type: BonusType::Cover,
+                limitation,
+            });
+        }
+    }
+}
+
 #[derive(Component, Clone, Debug, Eq, PartialEq)]
 pub struct CharacterWeaponProficiency {
     pub simple: HashMap<WeaponName, bool>,
@@ -332,6 +542,23 @@ impl From<Vec<CasterLevelBonus>> for CasterLevelBonuses {
         Self(map)
     }
 }
+impl CasterLevelBonuses {
+    // Total of the bonuses to caster level for the given use.
+    pub fn total_vs(&self, caster_level_use: CasterLevelUse) -> i32 {
+        self.0
+            .iter()
+            .map(|(bonus_type, bonuses)| {
+                stack_bonuses(
+                    bonus_type,
+                    bonuses
+                        .iter()
+                        .filter(|bonus| bonus.limitation == caster_level_use)
+                        .map(|bonus| bonus.bonus),
+                )
+            })
+            .sum()
+    }
+}
 impl BonusesContainer<BonusType, CasterLevelBonus> for CasterLevelBonuses {
     fn get_hashmap(&mut self) -> &mut HashMap<BonusType, Vec<CasterLevelBonus>> {
         &mut self.0
@@ -470,6 +697,15 @@ impl From<FloatingSkillBonus> for FloatingSkillBonuses {
     }
 }
 
+// The abilities and skills picked for the floating bonuses, one ability per
+// `choices_num` of each `FloatingAbilityBonus` and one skill per
+// `FloatingSkillBonus`, in the same order.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FloatingBonusPicks {
+    pub abilities: Vec<AbilityScore>,
+    pub skills: Vec<SkillName>,
+}
+
 // Bonus Skills each level and on character creation
 #[derive(Component, Clone, Debug, PartialEq, PartialOrd, Copy)]
 pub struct BonusSkillPerLevel {
@@ -479,7 +715,7 @@ pub struct BonusSkillPerLevel {
 // Wraps the SavingThrowBonus values, which are not Components, so they can
 // be applied all together to come to a total, taking into account the
 // bonus type.
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, PartialEq)]
 pub struct SavingThrowBonuses(pub HashMap<SavingThrowName, Vec<SavingThrowBonus>>);
 // impl Iterator for SavingThrow
 // //// iterator impls
@@ -526,6 +762,29 @@ impl From<Vec<SavingThrowBonus>> for SavingThrowBonuses {
     }
 }
 
+impl SavingThrowBonuses {
+    // Total of the bonuses to a save that apply against the given effect.
+    pub fn total_vs(&self, saving_throw: &SavingThrowName, effect: &SaveEffect) -> i32 {
+        let mut by_type: HashMap<BonusType, Vec<i32>> = HashMap::new();
+        for bonus in self
+            .0
+            .get(saving_throw)
+            .into_iter()
+            .flatten()
+            .filter(|bonus| bonus.applies_vs(effect))
+        {
+            by_type
+                .entry(bonus.bonus_type)
+                .or_default()
+                .push(bonus.bonus);
+        }
+        by_type
+            .iter()
+            .map(|(bonus_type, bonuses)| stack_bonuses(bonus_type, bonuses.iter().copied()))
+            .sum()
+    }
+}
+
 impl BonusesContainer<SavingThrowName, SavingThrowBonus> for SavingThrowBonuses {
     fn get_hashmap(&mut self) -> &mut HashMap<SavingThrowName, Vec<SavingThrowBonus>> {
         &mut self.0
@@ -547,7 +806,19 @@ pub struct SavingThrowBonus {
 }
 
 impl SavingThrowBonus {
-    fn to_all(&self) -> [SavingThrowBonus; 3] {
+    // Whether the bonus applies to a save against the given effect.
+    pub fn applies_vs(&self, effect: &SaveEffect) -> bool {
+        match self.limitation {
+            LimitationEnum::None => true,
+            LimitationEnum::SpellSchool(school) => effect.school == Some(school),
+            LimitationEnum::SpellDescriptor(descriptor) => effect.descriptors.contains(&descriptor),
+            LimitationEnum::SpellCauses(causes) => effect.causes.contains(&causes),
+            LimitationEnum::PoisonAndSpells(_, _) => effect.poison || effect.school.is_some(),
+            _ => false,
+        }
+    }
+
+    pub fn to_all(&self) -> [SavingThrowBonus; 3] {
         [
             SavingThrowBonus {
                 bonus: self.bonus,
@@ -574,7 +845,7 @@ impl SavingThrowBonus {
 // Wraps the SkillBonus values, which are not Components, so they can
 // be applied all together to come to a total, taking into account the
 // bonus type.
-#[derive(Component, Debug)]
+#[derive(Component, Clone, Debug, PartialEq)]
 pub struct SkillBonuses(pub HashMap<SkillName, Vec<SkillBonus>>);
 impl BonusesContainer<SkillName, SkillBonus> for SkillBonuses {
     fn get_hashmap(&mut self) -> &mut HashMap<SkillName, Vec<SkillBonus>> {
@@ -629,12 +900,46 @@ pub enum LimitationEnum {
     // Poison and magic are placeholder until I get around to those systems
     PoisonAndSpells(Poison, Magic),
     CombatManeuverName(CombatManeuverName),
+    // Only against attacks made by this creature, e.g. cover from a wall
+    // between the two.
+    AttacksFrom(Entity),
     ItemContains(ItemContains),
     SkillUse(SkillUse),
     ClassSkill,
     None,
 }
 
+// How a limitation reads after a bonus on the character sheet, as in
+// "+2 racial against enchantment spells".
+impl Display for LimitationEnum {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::CasterLevelBonus(caster_level_use) => write!(f, "for {caster_level_use:?}"),
+            Self::Spellcraft(spellcraft_use) => write!(f, "to {spellcraft_use:?}"),
+            Self::AttacksByCreatureSubtype(subtype) => write!(f, "against {subtype} attacks"),
+            Self::SpellSchool(school) => write!(f, "against {school:?} spells"),
+            Self::SpellDescriptor(descriptor) => write!(f, "against {descriptor:?} effects"),
+            Self::SpellCauses(causes) => write!(f, "against spells causing {causes:?}"),
+            Self::PlayerState(state) => write!(f, "while {state:?}"),
+            Self::AbilityScoreAbove(ability, score) => {
+                write!(f, "with {ability} above {score}")
+            }
+            Self::AttackingTargetTypeAndSubtype(creature_type, subtype) => {
+                write!(f, "against {creature_type} ({subtype})")
+            }
+            Self::TargetingType(creature_type) => write!(f, "against {creature_type}"),
+            Self::TargetingSubtype(subtype) => write!(f, "against {subtype}"),
+            Self::PoisonAndSpells(_, _) => write!(f, "against poison and spells"),
+            Self::CombatManeuverName(maneuver) => write!(f, "against {maneuver}"),
+            Self::AttacksFrom(_) => write!(f, "against one attacker"),
+            Self::ItemContains(contains) => write!(f, "with items containing {contains:?}"),
+            Self::SkillUse(skill_use) => write!(f, "to {skill_use:?}"),
+            Self::ClassSkill => write!(f, "as a class skill"),
+            Self::None => Ok(()),
+        }
+    }
+}
+
 // Bonus types that may be applied to any given stat. Most bonuses of the same
 // type do not stack, and even those that do often do not stack if they come
 // from the same source.
@@ -672,6 +977,8 @@ pub enum BonusType {
     NaturalArmor,
     // AC, checks, damage, DCs, saves
     Profane,
+    // AC, Reflex saves
+    Cover,
     // Lots, check later
     Racial,
     // Saves
@@ -688,8 +995,49 @@ pub enum BonusType {
     Untyped,
 }
 
+impl Display for BonusType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Alchemical => "alchemical",
+            Self::Armor => "armor",
+            Self::BaseAttackBonus => "base attack",
+            Self::Circumstance => "circumstance",
+            Self::Competence => "competence",
+            Self::Deflection => "deflection",
+            Self::Dodge => "dodge",
+            Self::Enhancement => "enhancement",
+            Self::Inherent => "inherent",
+            Self::Insight => "insight",
+            Self::Luck => "luck",
+            Self::Morale => "morale",
+            Self::NaturalArmor => "natural armor",
+            Self::Profane => "profane",
+            Self::Cover => "cover",
+            Self::Racial => "racial",
+            Self::Resistance => "resistance",
+            Self::Sacred => "sacred",
+            Self::Shield => "shield",
+            Self::Size => "size",
+            Self::Trait => "trait",
+            Self::Untyped => "untyped",
+        };
+        write!(f, "{name}")
+    }
+}
+
+// Adds up bonuses of the same type. Self-stackable types add together, for
+// all others only the highest bonus counts, but penalties always stack.
+pub fn stack_bonuses(bonus_type: &BonusType, bonuses: impl Iterator<Item = i32>) -> i32 {
+    if bonus_type.is_self_stackable() {
+        bonuses.sum()
+    } else {
+        let (penalties, bonuses): (Vec<i32>, Vec<i32>) = bonuses.partition(|value| *value < 0);
+        penalties.iter().sum::<i32>() + bonuses.into_iter().max().unwrap_or(0)
+    }
+}
+
 impl BonusType {
-    fn is_self_stackable(&self) -> bool {
+    pub fn is_self_stackable(&self) -> bool {
         use BonusType::*;
         match self {
             Alchemical => true,
@@ -700,6 +1048,7 @@ impl BonusType {
 
             BaseAttackBonus => false,
             Competence => false,
+            Cover => false,
             Deflection => false,
             Enhancement => false,
             Inherent => false,
@@ -810,6 +1159,25 @@ pub struct BaseLanguages {
     pub choices: Vec<Language>,
 }
 
+impl BaseLanguages {
+    // The languages `choices` stands for, with the meta members expanded and
+    // the languages already given left out.
+    pub fn options(&self) -> Vec<Language> {
+        let mut options: Vec<Language> = Vec::new();
+        for choice in self.choices.iter() {
+            match choice {
+                Language::AnyNotSecret => options.extend(Language::not_secret()),
+                Language::AnyWithSecret => options.extend(Language::array()),
+                language => options.push(*language),
+            }
+        }
+        options.sort();
+        options.dedup();
+        options.retain(|language| !self.given.contains(language));
+        options
+    }
+}
+
 // Normal speed over ground, as opposed to fly, burrow, and swim speed.
 #[derive(Component, Clone, Debug, PartialEq, PartialOrd, Copy)]
 pub struct GroundSpeed(pub f32);
@@ -856,8 +1224,6 @@ pub trait CharacterModifier<T: Component> // where
 
 // All languages, plus two meta enum members to indicate any with secrets and
 // any without secrets.
-// --> refactor this later, and impl something to just give back those secret
-//      and non-secret as arrays
 #[derive(Component, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Copy)]
 pub enum Language {
     Aboleth,
@@ -903,6 +1269,79 @@ pub enum Language {
     AnyWithSecret,
 }
 
+impl Language {
+    // Every actual language, leaving out the meta members.
+    pub fn array() -> [Language; 38] {
+        use Language::*;
+        [
+            Aboleth,
+            Abyssal,
+            Aklo,
+            Aquan,
+            Auran,
+            Boggard,
+            Catfolk,
+            Celestial,
+            Common,
+            Cyclops,
+            DarkFolk,
+            Draconic,
+            DrowSignLanguage,
+            Druidic,
+            Dwarven,
+            Dziriak(SpeaksLanguage::UnderstandOnly),
+            Dziriak(SpeaksLanguage::Speak),
+            Elven,
+            Giant,
+            Gnoll,
+            Gnome,
+            Goblin,
+            Grippli,
+            Halfling,
+            Ignan,
+            Infernal,
+            Necril,
+            Orc,
+            Protean,
+            Rougarou,
+            RegionalHuman(RegionalHumanLanguage::Any),
+            Sphinx,
+            Sylvan,
+            Tengu,
+            Terran,
+            Treant,
+            Undercommon,
+            Vegepygmy,
+        ]
+    }
+    // Languages only taught to members of a group, like Druidic to druids.
+    pub fn is_secret(&self) -> bool {
+        matches!(self, Language::DrowSignLanguage | Language::Druidic)
+    }
+    pub fn not_secret() -> Vec<Language> {
+        Self::array()
+            .into_iter()
+            .filter(|language| !language.is_secret())
+            .collect()
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Language::*;
+        match self {
+            DarkFolk => write!(f, "Dark Folk"),
+            DrowSignLanguage => write!(f, "Drow Sign Language"),
+            Dziriak(SpeaksLanguage::UnderstandOnly) => write!(f, "D'ziriak (understand only)"),
+            Dziriak(SpeaksLanguage::Speak) => write!(f, "D'ziriak"),
+            RegionalHuman(_) => write!(f, "Regional Human"),
+            AnyNotSecret => write!(f, "Any (not secret)"),
+            AnyWithSecret => write!(f, "Any"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
 #[derive(Component, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Copy)]
 pub enum RegionalHumanLanguage {
     /* More here, maybe */
@@ -942,6 +1381,40 @@ impl AbilityScores {
         let hashmap: HashMap<AbilityScore, i32> = HashMap::new();
         AbilityScores(hashmap)
     }
+    // Scores that haven't been set yet are treated as an average 10.
+    pub fn get(&self, ability: &AbilityScore) -> i32 {
+        self.0.get(ability).copied().unwrap_or(10)
+    }
+    pub fn set(&mut self, ability: AbilityScore, value: i32) {
+        self.0.insert(ability, value);
+    }
+    pub fn modifier(&self, ability: &AbilityScore) -> i32 {
+        (self.get(ability) - 10).div_euclid(2)
+    }
+}
+
+// Points available to buy the base ability scores with.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PointBuy(pub i32);
+
+// What a base score costs in a point buy, or `None` for scores that can't be
+// bought.
+pub fn point_buy_cost(score: i32) -> Option<i32> {
+    match score {
+        7 => Some(-4),
+        8 => Some(-2),
+        9 => Some(-1),
+        10 => Some(0),
+        11 => Some(1),
+        12 => Some(2),
+        13 => Some(3),
+        14 => Some(5),
+        15 => Some(7),
+        16 => Some(10),
+        17 => Some(13),
+        18 => Some(17),
+        _ => None,
+    }
 }
 
 #[derive(Component, Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -973,9 +1446,52 @@ pub struct Alignment {
     order: AlignmentOrder,
     moral: AlignmentMoral,
 }
+impl Alignment {
+    pub fn new(order: AlignmentOrder, moral: AlignmentMoral) -> Self {
+        Self { order, moral }
+    }
+    pub fn order(&self) -> AlignmentOrder {
+        self.order
+    }
+    pub fn moral(&self) -> AlignmentMoral {
+        self.moral
+    }
+    pub fn as_array() -> [Alignment; 9] {
+        let mut array = [Alignment::default(); 9];
+        for (i, order) in AlignmentOrder::as_array().into_iter().enumerate() {
+            for (j, moral) in AlignmentMoral::as_array().into_iter().enumerate() {
+                array[i * 3 + j] = Alignment::new(order, moral);
+            }
+        }
+        array
+    }
+    // How many steps apart two alignments are on the alignment grid, counting
+    // each step along the law-chaos and good-evil axes, so lawful good is one
+    // step from lawful neutral and two from true neutral.
+    pub fn steps_from(&self, other: &Alignment) -> usize {
+        let index_of = |order: &AlignmentOrder| {
+            AlignmentOrder::as_array()
+                .iter()
+                .position(|other| other == order)
+                .unwrap_or(0)
+        };
+        let moral_index_of = |moral: &AlignmentMoral| {
+            AlignmentMoral::as_array()
+                .iter()
+                .position(|other| other == moral)
+                .unwrap_or(0)
+        };
+        index_of(&self.order).abs_diff(index_of(&other.order))
+            + moral_index_of(&self.moral).abs_diff(moral_index_of(&other.moral))
+    }
+}
 impl Display for Alignment {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.order, self.moral)
+        if self.order == AlignmentOrder::Neutral && self.moral == AlignmentMoral::Neutral {
+            write!(f, "True Neutral")
+        } else {
+            write!(f, "{} {}", self.order, self.moral)
+        }
     }
 }
 
@@ -1058,7 +1574,7 @@ pub struct CharacterSize {
     pub size_type: SizeType,
 }
 
-#[derive(Component, Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Component, Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Deserialize)]
 pub enum SizeCategory {
     Fine,
     Diminutive,
@@ -1071,6 +1587,29 @@ pub enum SizeCategory {
     Colossal,
 }
 
+impl SizeCategory {
+    // The modifier to attack rolls and AC.
+    pub fn size_modifier(&self) -> i32 {
+        -self.special_size_modifier()
+    }
+    // The modifier used for CMB and CMD, which is the reverse of the size
+    // modifier to attack rolls and AC.
+    pub fn special_size_modifier(&self) -> i32 {
+        use SizeCategory::*;
+        match self {
+            Fine => -8,
+            Diminutive => -4,
+            Tiny => -2,
+            Small => -1,
+            Medium => 0,
+            Large => 1,
+            Huge => 2,
+            Gargantuan => 4,
+            Colossal => 8,
+        }
+    }
+}
+
 #[derive(Component, Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Hash)]
 pub enum SizeType {
     Tall,
@@ -1575,4 +2114,13 @@ impl SavingThrowName {
         use SavingThrowName::*;
         [Fort, Reflex, Will].iter().copied()
     }
+    // The ability score whose modifier is added to the save.
+    pub fn ability(&self) -> Option<AbilityScore> {
+        match self {
+            SavingThrowName::Fort => Some(AbilityScore::Constitution),
+            SavingThrowName::Reflex => Some(AbilityScore::Dexterity),
+            SavingThrowName::Will => Some(AbilityScore::Wisdom),
+            SavingThrowName::None => None,
+        }
+    }
 }
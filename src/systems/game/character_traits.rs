@@ -0,0 +1,191 @@
+use crate::systems::game::{
+    character::{
+        ArmorClassBonus, AttackRollBonus, BonusType, CasterLevelBonus, LimitationEnum,
+        PlayableRace, SavingThrowBonus, SavingThrowName, SkillBonus, SpellDCBonus,
+    },
+    effects::{Effect, EffectSource, Modifier},
+    magic::CasterLevelUse,
+    skills::SkillName,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+
+// Characters take two traits, from different categories.
+pub const TRAITS_PER_CHARACTER: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+pub enum TraitCategory {
+    Combat,
+    Faith,
+    Magic,
+    Social,
+    Regional,
+    Race,
+    // A flaw taken in exchange for a third trait.
+    Drawback,
+}
+
+impl TraitCategory {
+    pub fn array() -> [TraitCategory; 7] {
+        use TraitCategory::*;
+        [Combat, Faith, Magic, Social, Regional, Race, Drawback]
+    }
+}
+
+impl fmt::Display for TraitCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// A single bonus granted by a trait, or a penalty for a drawback. These all
+// become `BonusType::Trait` bonuses, so two traits boosting the same thing
+// don't stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum TraitBonus {
+    Skill(SkillName, i32),
+    SavingThrow(SavingThrowName, i32),
+    AttackRoll(i32),
+    ArmorClass(i32),
+    CasterLevel(CasterLevelUse, i32),
+    SpellDC(i32),
+}
+
+impl TraitBonus {
+    pub fn into_modifier(self) -> Modifier {
+        match self {
+            TraitBonus::Skill(skill_name, bonus) => Modifier::Skill(vec![SkillBonus {
+                bonus,
+                bonus_type: BonusType::Trait,
+                skill_name,
+                limitation: vec![LimitationEnum::None],
+            }]),
+            TraitBonus::SavingThrow(saving_throw, bonus) => {
+                Modifier::SavingThrow(vec![SavingThrowBonus {
+                    bonus,
+                    bonus_type: BonusType::Trait,
+                    saving_throw,
+                    limitation: LimitationEnum::None,
+                }])
+            }
+            TraitBonus::AttackRoll(bonus) => Modifier::AttackRoll(vec![AttackRollBonus {
+                bonus,
+                bonus_type: BonusType::Trait,
+                key: BonusType::Trait,
+                limitation: vec![LimitationEnum::None],
+            }]),
+            TraitBonus::ArmorClass(bonus) => Modifier::ArmorClass(vec![ArmorClassBonus {
+                bonus,
+                bonus_type: BonusType::Trait,
+                limitation: LimitationEnum::None,
+            }]),
+            TraitBonus::CasterLevel(limitation, bonus) => {
+                Modifier::CasterLevel(vec![CasterLevelBonus {
+                    bonus,
+                    bonus_type: BonusType::Trait,
+                    limitation,
+                }])
+            }
+            TraitBonus::SpellDC(bonus) => Modifier::SpellDC(vec![SpellDCBonus {
+                bonus,
+                bonus_type: BonusType::Trait,
+                key: BonusType::Trait,
+                limitation: vec![LimitationEnum::None],
+            }]),
+        }
+    }
+}
+
+// One entry of the trait catalog.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct CharacterTrait {
+    pub name: String,
+    pub category: TraitCategory,
+    pub description: String,
+    pub bonuses: Vec<TraitBonus>,
+    // Race traits can only be taken by members of this race.
+    #[serde(default)]
+    pub race: Option<PlayableRace>,
+}
+
+impl Effect {
+    pub fn from_character_trait(target: Entity, character_trait: &CharacterTrait) -> Self {
+        Self::new(
+            target,
+            EffectSource::CharacterTrait(character_trait.name.clone()),
+            character_trait
+                .bonuses
+                .iter()
+                .map(|bonus| bonus.into_modifier())
+                .collect(),
+        )
+    }
+}
+
+// Names of the traits a character has taken.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CharacterTraits(pub Vec<String>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TraitIssue {
+    TooMany { allowed: usize, chosen: usize },
+    SameCategory(TraitCategory),
+    WrongRace(String),
+}
+
+impl fmt::Display for TraitIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooMany { allowed, chosen } => {
+                write!(f, "{chosen} traits chosen, only {allowed} allowed")
+            }
+            Self::SameCategory(category) => {
+                write!(f, "More than one {category} trait chosen")
+            }
+            Self::WrongRace(name) => write!(f, "{name} is for another race"),
+        }
+    }
+}
+
+// How many traits, not counting drawbacks, can be taken alongside the chosen
+// ones. A drawback buys one more.
+pub fn traits_allowed(chosen: &[&CharacterTrait]) -> usize {
+    let has_drawback = chosen
+        .iter()
+        .any(|chosen| chosen.category == TraitCategory::Drawback);
+    TRAITS_PER_CHARACTER + usize::from(has_drawback)
+}
+
+// Everything wrong with a set of traits for a character of the given race.
+// An empty list means the selection is legal.
+pub fn trait_issues(chosen: &[&CharacterTrait], race: &PlayableRace) -> Vec<TraitIssue> {
+    let mut issues = Vec::new();
+    let traits = chosen
+        .iter()
+        .filter(|chosen| chosen.category != TraitCategory::Drawback)
+        .count();
+    let allowed = traits_allowed(chosen);
+    if traits > allowed {
+        issues.push(TraitIssue::TooMany {
+            allowed,
+            chosen: traits,
+        });
+    }
+    for category in TraitCategory::array() {
+        if chosen
+            .iter()
+            .filter(|chosen| chosen.category == category)
+            .count()
+            > 1
+        {
+            issues.push(TraitIssue::SameCategory(category));
+        }
+    }
+    for chosen in chosen.iter() {
+        if chosen.race.is_some_and(|trait_race| trait_race != *race) {
+            issues.push(TraitIssue::WrongRace(chosen.name.clone()));
+        }
+    }
+    issues
+}
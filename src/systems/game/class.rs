@@ -1,6 +1,9 @@
 use crate::menu::character_creation::layout::generics::list_traits::{AsVec, HasArray};
 use crate::systems::game::character::*;
+use crate::systems::game::effects::BaseBonuses;
+use crate::systems::game::favored_class::FavoredClassBonus;
 use crate::systems::game::skills::SkillName;
+use crate::systems::grid_systems::area_of_effect::AreaShape;
 use bevy::prelude::*;
 use serde::Deserialize;
 use std::fmt;
@@ -11,6 +14,9 @@ pub struct FavoredClass {
     pub race: PlayableRace,
     pub description: String,
     pub source: String,
+    // The bonus written out, for the options the game can apply.
+    #[serde(default)]
+    pub bonus: Option<FavoredClassBonus>,
 }
 
 #[derive(
@@ -194,6 +200,51 @@ impl fmt::Display for PlayableClass {
     }
 }
 
+// The ability a class relies on most, which random characters put their
+// best score in.
+pub fn key_ability(class: &PlayableClass) -> AbilityScore {
+    use AbilityScore::*;
+    use PlayableClass::*;
+    match class {
+        Alchemist | Arcanist | Investigator | Magus | Occultist | Psychic | Witch | Wizard => {
+            Intelligence
+        }
+        Bard | Medium | Mesmerist | Oracle | Skald | Sorcerer | Summoner => Charisma,
+        Cleric | Druid | Hunter | Inquisitor | Monk | Shaman | Spiritualist | Warpriest => Wisdom,
+        Gunslinger | Ranger | Rogue | Swashbuckler => Dexterity,
+        Kineticist => Constitution,
+        Barbarian
+        | Bloodrager
+        | Cavalier
+        | Fighter
+        | Paladin
+        | Slayer
+        | Vigilante
+        | PlayableClass::None => Strength,
+    }
+}
+
+// Alignments a class can be taken with, or `None` when any alignment will do.
+pub fn class_alignment_restriction(class: &PlayableClass) -> Option<Vec<Alignment>> {
+    let allowed = |keep: fn(&Alignment) -> bool| {
+        Some(Alignment::as_array().into_iter().filter(keep).collect())
+    };
+    match class {
+        PlayableClass::Paladin => allowed(|alignment| {
+            alignment.order() == AlignmentOrder::Lawful && alignment.moral() == AlignmentMoral::Good
+        }),
+        PlayableClass::Monk => allowed(|alignment| alignment.order() == AlignmentOrder::Lawful),
+        PlayableClass::Barbarian => {
+            allowed(|alignment| alignment.order() != AlignmentOrder::Lawful)
+        }
+        PlayableClass::Druid => allowed(|alignment| {
+            alignment.order() == AlignmentOrder::Neutral
+                || alignment.moral() == AlignmentMoral::Neutral
+        }),
+        _ => None,
+    }
+}
+
 #[derive(Default, Deserialize, Clone, Debug)]
 pub enum PlayableClassDetails {
     // Alchemist(AlchemistClass),
@@ -261,7 +312,6 @@ pub struct ClassInfo {
     pub class_skills: Vec<SkillName>,
     pub skill_ranks_per_level: usize,
     pub hit_die: Dice,
-    pub alignment_restriction: Option<Vec<Alignment>>,
     pub bab_progression: BABProgression,
     // The name of each primary saving throw
     pub save_progression: [SavingThrowName; 3],
@@ -316,7 +366,9 @@ pub enum BABProgression {
     None,
 }
 
-#[derive(Copy, Default, Deserialize, Clone, Debug, PartialEq, PartialOrd, Eq, Hash)]
+#[derive(
+    Component, Copy, Default, Deserialize, Clone, Debug, PartialEq, PartialOrd, Eq, Hash,
+)]
 pub struct BaseAttack {
     pub base: usize,
 }
@@ -332,6 +384,23 @@ impl BaseAttack {
         }
     }
 }
+// Base saving throw bonuses from class levels.
+#[derive(Component, Copy, Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BaseSaves {
+    pub fort: usize,
+    pub reflex: usize,
+    pub will: usize,
+}
+impl BaseSaves {
+    pub fn get(&self, saving_throw: &SavingThrowName) -> usize {
+        match saving_throw {
+            SavingThrowName::Fort => self.fort,
+            SavingThrowName::Reflex => self.reflex,
+            SavingThrowName::Will => self.will,
+            SavingThrowName::None => 0,
+        }
+    }
+}
 impl From<usize> for BaseAttack {
     fn from(value: usize) -> Self {
         Self { base: value }
@@ -365,6 +434,22 @@ impl ClassFeature {
     }
 }
 
+// The class features a creature has gained.
+#[derive(Component, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ClassFeatures(pub Vec<ClassFeature>);
+
+impl ClassFeatures {
+    pub fn inner_ref(&self) -> &Vec<ClassFeature> {
+        &self.0
+    }
+    // Whether a feature has been gained at any rank, so `BonusFeat(Some(2))`
+    // counts as having `BonusFeat(None)`.
+    pub fn has(&self, feature: &ClassFeature) -> bool {
+        let feature = feature.as_default();
+        self.0.iter().any(|gained| gained.as_default() == feature)
+    }
+}
+
 #[derive(Default, Deserialize, Clone, Debug, PartialEq, PartialOrd, Eq, Hash, Copy)]
 pub enum FighterFeature {
     BonusFeat(Option<usize>),
@@ -431,6 +516,14 @@ impl FighterFeature {
         }
     }
 
+    /// The area of effect of features that target an area rather than a single creature.
+    pub fn area(&self) -> Option<AreaShape> {
+        match self {
+            Self::Volley => Some(AreaShape::Burst { radius: 15 }),
+            _ => None,
+        }
+    }
+
     /// Used to find the bonus or version of a feat at a given level,
     /// where `level` is the level in question,
     /// div is the number of levels required for the feature to upgrade,
@@ -514,6 +607,19 @@ pub enum Dice {
     D20,
 }
 
+impl Dice {
+    pub fn sides(&self) -> i32 {
+        match self {
+            Dice::D4 => 4,
+            Dice::D6 => 6,
+            Dice::D8 => 8,
+            Dice::D10 => 10,
+            Dice::D12 => 12,
+            Dice::D20 => 20,
+        }
+    }
+}
+
 // Into FloatingBonusFeats
 impl TryFrom<&ClassInfo> for FloatingBonusFeats {
     type Error = &'static str;
@@ -601,8 +707,10 @@ pub fn add_class_bonusfeats(
     }
 }
 
+// Saving throw bonuses from class go into the base bonuses, which the
+// container is rebuilt from along with the character's effects.
 pub fn add_class_savingthrowbonuses(
-    mut query_character: Query<(Entity, &mut SavingThrowBonuses, &ClassLevels)>,
+    mut query_character: Query<(Entity, &mut BaseBonuses<SavingThrowBonuses>, &ClassLevels)>,
     selected: Res<SelectedCharacter>,
     class_map: Res<ClassMap>,
     mut commands: Commands,
@@ -615,13 +723,41 @@ pub fn add_class_savingthrowbonuses(
                 .next()
             {
                 for (_, bonuses) in class_bonuses.0.iter() {
-                    existing_bonuses.add_or_insert_all(bonuses.clone());
+                    existing_bonuses.0.add_or_insert_all(bonuses.clone());
                 }
             } else {
                 if let Some(mut entity_commands) = commands.get_entity(selected.inner()) {
-                    entity_commands.insert(class_bonuses);
+                    entity_commands.insert(BaseBonuses(class_bonuses));
                 }
             }
         }
     }
 }
+
+// Keeps each character's `BaseAttack` in line with their class levels.
+pub fn update_base_attack(
+    query_character: Query<(Entity, &ClassLevels), Changed<ClassLevels>>,
+    class_map: Option<Res<ClassMap>>,
+    mut commands: Commands,
+) {
+    let Some(class_map) = class_map else { return };
+    for (entity, class_levels) in query_character.iter() {
+        commands
+            .entity(entity)
+            .insert(class_levels.base_attack(&class_map));
+    }
+}
+
+// Keeps each character's `BaseSaves` in line with their class levels.
+pub fn update_base_saves(
+    query_character: Query<(Entity, &ClassLevels), Changed<ClassLevels>>,
+    class_map: Option<Res<ClassMap>>,
+    mut commands: Commands,
+) {
+    let Some(class_map) = class_map else { return };
+    for (entity, class_levels) in query_character.iter() {
+        commands
+            .entity(entity)
+            .insert(class_levels.base_saves(&class_map));
+    }
+}
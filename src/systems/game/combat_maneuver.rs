@@ -0,0 +1,276 @@
+use crate::systems::game::{
+    character::*,
+    class::BaseAttack,
+    conditions::{Condition, Conditions},
+    dice::GameRng,
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// Combat Maneuver Bonus and Defense
+
+// CMB = BAB + Str modifier + special size modifier
+// Creatures of size Tiny or smaller use their Dex modifier instead of Str.
+pub fn cmb(base_attack: &BaseAttack, scores: &AbilityScores, size: &SizeCategory) -> i32 {
+    let ability = if *size <= SizeCategory::Tiny {
+        AbilityScore::Dexterity
+    } else {
+        AbilityScore::Strength
+    };
+    base_attack.base as i32 + scores.modifier(&ability) + size.special_size_modifier()
+}
+
+// CMD = 10 + BAB + Str modifier + Dex modifier + special size modifier
+// Bonuses from `CMDBonuses`, like the dwarf's Stability, are only added when
+// their limitation matches the maneuver being attempted.
+pub fn cmd(
+    base_attack: &BaseAttack,
+    scores: &AbilityScores,
+    size: &SizeCategory,
+    bonuses: Option<&CMDBonuses>,
+    maneuver: CombatManeuverName,
+) -> i32 {
+    10 + base_attack.base as i32
+        + scores.modifier(&AbilityScore::Strength)
+        + scores.modifier(&AbilityScore::Dexterity)
+        + size.special_size_modifier()
+        + bonuses.map_or(0, |bonuses| bonuses.total_vs(maneuver))
+}
+
+// Results
+
+// What happens to the participants of a maneuver. Distances are in feet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ManeuverEffect {
+    // Target is moved directly away from the attacker.
+    PushTarget(usize),
+    // Target is moved toward the attacker, who moves with it.
+    DragTarget(usize),
+    // Target is moved to a square of the attacker's choosing within reach.
+    RepositionTarget(usize),
+    // Attacker moves through the target's square.
+    MoveThrough,
+    TargetCondition(Condition, Option<usize>),
+    AttackerCondition(Condition, Option<usize>),
+    // Target drops a held item, or every held item when `true`.
+    TargetDropsItem(bool),
+    AttackerDropsItem,
+    // Attacker takes an item from the target.
+    TargetLosesItem,
+    // Attacker deals damage to an item held or worn by the target.
+    DamageItem,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManeuverResult {
+    pub maneuver: CombatManeuverName,
+    // The natural d20 roll
+    pub roll: i32,
+    pub total: i32,
+    pub cmd: i32,
+    pub success: bool,
+    pub effects: Vec<ManeuverEffect>,
+}
+
+impl ManeuverResult {
+    // How far the check beat the CMD, negative when it fell short.
+    pub fn margin(&self) -> i32 {
+        self.total - self.cmd
+    }
+}
+
+// 5 feet, plus another 5 for every 5 by which the check beat the CMD.
+fn distance_for_margin(margin: i32) -> usize {
+    5 + 5 * (margin.max(0) as usize / 5)
+}
+
+// A natural 20 always succeeds and a natural 1 always fails.
+pub fn resolve_maneuver(
+    maneuver: CombatManeuverName,
+    roll: i32,
+    cmb: i32,
+    cmd: i32,
+    dirty_trick_condition: Option<Condition>,
+) -> ManeuverResult {
+    use CombatManeuverName::*;
+    use ManeuverEffect::*;
+    let total = roll + cmb;
+    let margin = total - cmd;
+    let success = match roll {
+        20 => true,
+        1 => false,
+        _ => margin >= 0,
+    };
+
+    let mut effects = Vec::new();
+    if success {
+        match maneuver {
+            BullRush => effects.push(PushTarget(distance_for_margin(margin))),
+            DirtyTrick => {
+                // Lasts 1 round, plus 1 round for every 5 by which the check
+                // beat the CMD.
+                let rounds = 1 + margin.max(0) as usize / 5;
+                effects.push(TargetCondition(
+                    dirty_trick_condition.unwrap_or(Condition::Dazzled),
+                    Some(rounds),
+                ));
+            }
+            Disarm => {
+                effects.push(TargetDropsItem(margin >= 10));
+                effects.push(TargetCondition(Condition::Disarmed, None));
+            }
+            Drag => effects.push(DragTarget(distance_for_margin(margin))),
+            Grapple => {
+                effects.push(TargetCondition(Condition::Grappled, None));
+                effects.push(AttackerCondition(Condition::Grappled, None));
+            }
+            Overrun => {
+                effects.push(MoveThrough);
+                if margin >= 5 {
+                    effects.push(TargetCondition(Condition::Prone, None));
+                }
+            }
+            Reposition => effects.push(RepositionTarget(distance_for_margin(margin))),
+            Steal => effects.push(TargetLosesItem),
+            Sunder => effects.push(DamageItem),
+            Trip => effects.push(TargetCondition(Condition::Prone, None)),
+        }
+    } else if margin <= -10 {
+        // Failing some maneuvers by 10 or more backfires on the attacker.
+        match maneuver {
+            Disarm => {
+                effects.push(AttackerDropsItem);
+                effects.push(AttackerCondition(Condition::Disarmed, None));
+            }
+            Trip => effects.push(AttackerCondition(Condition::Prone, None)),
+            _ => (),
+        }
+    }
+
+    ManeuverResult {
+        maneuver,
+        roll,
+        total,
+        cmd,
+        success,
+        effects,
+    }
+}
+
+// Events
+
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct CombatManeuverEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub maneuver: CombatManeuverName,
+    // Situational modifiers to the check, like flanking.
+    pub bonus: i32,
+    // Which condition to inflict with a dirty trick.
+    pub dirty_trick_condition: Option<Condition>,
+}
+
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct CombatManeuverResultEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub result: ManeuverResult,
+}
+
+// Systems
+
+type CombatantQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static BaseAttack>,
+        Option<&'static AbilityScores>,
+        Option<&'static CharacterSize>,
+        Option<&'static CMDBonuses>,
+    ),
+>;
+
+// Creatures missing any of these components are treated as having a BAB of
+// +0, 10 in every ability score and being Medium.
+pub fn resolve_combat_maneuvers(
+    mut event_reader: EventReader<CombatManeuverEvent>,
+    mut event_writer: EventWriter<CombatManeuverResultEvent>,
+    query_combatant: CombatantQuery,
+    mut rng: ResMut<GameRng>,
+) {
+    let default_scores = AbilityScores::new();
+    for event in event_reader.iter() {
+        let (Ok(attacker), Ok(target)) = (
+            query_combatant.get(event.attacker),
+            query_combatant.get(event.target),
+        ) else {
+            continue;
+        };
+        let (attacker_bab, attacker_scores, attacker_size, _) = attacker;
+        let (target_bab, target_scores, target_size, target_bonuses) = target;
+
+        let attacker_cmb = cmb(
+            attacker_bab.unwrap_or(&BaseAttack::default()),
+            attacker_scores.unwrap_or(&default_scores),
+            &attacker_size.map_or(SizeCategory::Medium, |size| size.category),
+        ) + event.bonus;
+        let target_cmd = cmd(
+            target_bab.unwrap_or(&BaseAttack::default()),
+            target_scores.unwrap_or(&default_scores),
+            &target_size.map_or(SizeCategory::Medium, |size| size.category),
+            target_bonuses,
+            event.maneuver,
+        );
+
+        let result = resolve_maneuver(
+            event.maneuver,
+            rng.d20(),
+            attacker_cmb,
+            target_cmd,
+            event.dirty_trick_condition,
+        );
+        event_writer.send(CombatManeuverResultEvent {
+            attacker: event.attacker,
+            target: event.target,
+            result,
+        });
+    }
+}
+
+// Adds the conditions from maneuver results. Movement and items are left to
+// the systems that own those.
+pub fn apply_maneuver_conditions(
+    mut event_reader: EventReader<CombatManeuverResultEvent>,
+    mut query_conditions: Query<Option<&mut Conditions>>,
+    mut commands: Commands,
+) {
+    // Entities that don't have `Conditions` yet are collected first, so that
+    // several results in the same frame don't overwrite each other.
+    let mut new_conditions: HashMap<Entity, Conditions> = HashMap::new();
+    for event in event_reader.iter() {
+        for effect in event.result.effects.iter() {
+            let (entity, condition, rounds) = match effect {
+                ManeuverEffect::TargetCondition(condition, rounds) => {
+                    (event.target, *condition, *rounds)
+                }
+                ManeuverEffect::AttackerCondition(condition, rounds) => {
+                    (event.attacker, *condition, *rounds)
+                }
+                _ => continue,
+            };
+            match query_conditions.get_mut(entity) {
+                Ok(Some(mut conditions)) => conditions.add(condition, rounds),
+                Ok(None) => new_conditions
+                    .entry(entity)
+                    .or_default()
+                    .add(condition, rounds),
+                Err(_) => (),
+            }
+        }
+    }
+    for (entity, conditions) in new_conditions {
+        if let Some(mut entity_commands) = commands.get_entity(entity) {
+            entity_commands.insert(conditions);
+        }
+    }
+}
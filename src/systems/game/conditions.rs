@@ -0,0 +1,96 @@
+use bevy::prelude::Component;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+
+// Conditions that may be applied to a creature during play. Only the ones
+// currently used by game systems are here for now.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+pub enum Condition {
+    Blinded,
+    Dazzled,
+    Deafened,
+    Disarmed,
+    Entangled,
+    Grappled,
+    Prone,
+    Shaken,
+    Sickened,
+}
+
+impl Condition {
+    pub fn array() -> [Condition; 9] {
+        use Condition::*;
+        [
+            Blinded, Dazzled, Deafened, Disarmed, Entangled, Grappled, Prone, Shaken, Sickened,
+        ]
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Condition::*;
+        match self {
+            Blinded => write!(f, "Blinded"),
+            Dazzled => write!(f, "Dazzled"),
+            Deafened => write!(f, "Deafened"),
+            Disarmed => write!(f, "Disarmed"),
+            Entangled => write!(f, "Entangled"),
+            Grappled => write!(f, "Grappled"),
+            Prone => write!(f, "Prone"),
+            Shaken => write!(f, "Shaken"),
+            Sickened => write!(f, "Sickened"),
+        }
+    }
+}
+
+// The conditions currently on a creature. The value is the number of rounds
+// left, with `None` for conditions that last until something removes them,
+// like standing up from prone.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Conditions(HashMap<Condition, Option<usize>>);
+
+impl Conditions {
+    pub fn inner_ref(&self) -> &HashMap<Condition, Option<usize>> {
+        &self.0
+    }
+    pub fn has(&self, condition: &Condition) -> bool {
+        self.0.contains_key(condition)
+    }
+    // Applying a condition that is already present keeps whichever duration
+    // lasts longer.
+    pub fn add(&mut self, condition: Condition, rounds: Option<usize>) {
+        self.0
+            .entry(condition)
+            .and_modify(|existing| {
+                *existing = match (*existing, rounds) {
+                    (Some(old), Some(new)) => Some(old.max(new)),
+                    _ => None,
+                }
+            })
+            .or_insert(rounds);
+    }
+    pub fn remove(&mut self, condition: &Condition) {
+        self.0.remove(condition);
+    }
+    // Count down one round, removing timed conditions that run out.
+    pub fn tick_round(&mut self) -> Vec<Condition> {
+        self.tick_rounds(1)
+    }
+    // Count down a number of rounds, returning the timed conditions that ran
+    // out.
+    pub fn tick_rounds(&mut self, rounds: usize) -> Vec<Condition> {
+        let mut expired = Vec::new();
+        self.0.retain(|condition, remaining| match remaining {
+            Some(left) => {
+                *left = left.saturating_sub(rounds);
+                if *left == 0 {
+                    expired.push(*condition);
+                }
+                *left > 0
+            }
+            None => true,
+        });
+        expired
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{
+    systems::{
+        game::{
+            ai::AiController,
+            attack::AttackResultEvent,
+            character::{
+                AbilityScore, AbilityScores, ArmorClassBonus, BonusType, CreatureSubtypes,
+                DarkVision, GroundSpeed, LimitationEnum, LowLightVision, NormalVision,
+            },
+            class::{BaseSaves, Dice},
+            damage::{DamageEvent, DamageType, HitPoints},
+            defenses::{DamageReduction, EnergyResistance, SpellResistanceRating},
+            dice::GameRng,
+            effects::{Effect, EffectSource, Modifier},
+            equipment::{WeaponDamageType, WeaponName, WieldedWeapon},
+            magic::SpellLikeAbility,
+        },
+        grid_systems::{
+            grid_position::GridPos,
+            token::{Faction, TokenBundle},
+        },
+    },
+    technical::creature::{ArmorClassDescription, CreatureAsset},
+    AsciiSheet,
+};
+use bevy::{asset::LoadState, prelude::*};
+use serde::Deserialize;
+use std::fmt;
+
+// Challenge Rating
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+pub enum ChallengeRating {
+    OneEighth,
+    OneSixth,
+    OneFourth,
+    OneThird,
+    OneHalf,
+    Whole(u32),
+}
+
+impl ChallengeRating {
+    // The CR for a number that may be below 1, as when working out an easy
+    // encounter for a 1st level party. Each step below 1 goes down one of the
+    // fractional CRs.
+    pub fn from_level(level: i32) -> Self {
+        match level {
+            1.. => ChallengeRating::Whole(level as u32),
+            0 => ChallengeRating::OneHalf,
+            -1 => ChallengeRating::OneThird,
+            -2 => ChallengeRating::OneFourth,
+            -3 => ChallengeRating::OneSixth,
+            _ => ChallengeRating::OneEighth,
+        }
+    }
+    // The XP award for defeating a creature of this CR. From CR 1 the award
+    // doubles every two steps, starting from 400 for odd CRs and 600 for even
+    // ones.
+    pub fn experience(&self) -> u32 {
+        match self {
+            ChallengeRating::OneEighth => 50,
+            ChallengeRating::OneSixth => 65,
+            ChallengeRating::OneFourth => 100,
+            ChallengeRating::OneThird => 135,
+            ChallengeRating::OneHalf => 200,
+            ChallengeRating::Whole(0) => 0,
+            ChallengeRating::Whole(cr) if cr % 2 == 1 => 400 << ((cr - 1) / 2),
+            ChallengeRating::Whole(cr) => 600 << ((cr - 2) / 2),
+        }
+    }
+}
+
+impl fmt::Display for ChallengeRating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChallengeRating::OneEighth => write!(f, "1/8"),
+            ChallengeRating::OneSixth => write!(f, "1/6"),
+            ChallengeRating::OneFourth => write!(f, "1/4"),
+            ChallengeRating::OneThird => write!(f, "1/3"),
+            ChallengeRating::OneHalf => write!(f, "1/2"),
+            ChallengeRating::Whole(cr) => write!(f, "{}", cr),
+        }
+    }
+}
+
+// XP awarded for defeating the creature.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExperienceValue(pub u32);
+
+// Stat Block Components
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct HitDice {
+    pub count: usize,
+    pub die: Dice,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct CreatureAttack {
+    pub name: String,
+    #[serde(default)]
+    pub weapon: Option<WeaponName>,
+    // Number of dice and the die, e.g. (1, D4) for 1d4.
+    pub damage: (usize, Dice),
+    pub damage_type: WeaponDamageType,
+    #[serde(default)]
+    pub ranged: bool,
+}
+
+// The attacks listed in a creature's stat block.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CreatureAttacks(pub Vec<CreatureAttack>);
+
+impl CreatureAttacks {
+    pub fn inner_ref(&self) -> &Vec<CreatureAttack> {
+        &self.0
+    }
+    pub fn melee(&self) -> impl Iterator<Item = &CreatureAttack> {
+        self.0.iter().filter(|attack| !attack.ranged)
+    }
+    pub fn ranged(&self) -> impl Iterator<Item = &CreatureAttack> {
+        self.0.iter().filter(|attack| attack.ranged)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct SpecialAbility {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpecialAbilities(pub Vec<SpecialAbility>);
+
+impl ArmorClassDescription {
+    // The AC bonuses in the stat block, leaving out the ones that are 0.
+    pub fn bonuses(&self) -> Vec<ArmorClassBonus> {
+        [
+            (self.armor, BonusType::Armor),
+            (self.shield, BonusType::Shield),
+            (self.natural, BonusType::NaturalArmor),
+            (self.deflection, BonusType::Deflection),
+            (self.dodge, BonusType::Dodge),
+        ]
+        .into_iter()
+        .filter(|(bonus, _)| *bonus != 0)
+        .map(|(bonus, bonus_type)| ArmorClassBonus {
+            bonus,
+            bonus_type,
+            limitation: LimitationEnum::None,
+        })
+        .collect()
+    }
+}
+
+// Spawns a token for a stat block, with the same stat components characters
+// use, so attacks, saves and racial limitations work the same against it.
+pub fn spawn_creature(
+    commands: &mut Commands,
+    stat_block: &CreatureAsset,
+    ascii: &AsciiSheet,
+    grid_pos: GridPos,
+    faction: Faction,
+) -> Entity {
+    let (red, green, blue) = stat_block.color;
+    let mut entity_commands = commands.spawn(TokenBundle::new(
+        ascii,
+        stat_block.glyph as usize,
+        Color::rgb(red, green, blue),
+        grid_pos,
+        stat_block.size,
+        faction,
+        GroundSpeed(stat_block.speed),
+    ));
+    entity_commands.insert((
+        Name::new(stat_block.name.clone()),
+        stat_block.creature_type,
+        CreatureSubtypes(stat_block.subtypes.clone()),
+        stat_block.hit_dice.clone(),
+        HitPoints::new(stat_block.hit_points),
+        AbilityScores::from(stat_block.ability_scores),
+        stat_block.base_attack(),
+        BaseSaves::from(stat_block.base_saves),
+        CreatureAttacks(stat_block.attacks.clone()),
+        SpecialAbilities(stat_block.special_abilities.clone()),
+        stat_block.challenge_rating,
+        ExperienceValue(
+            stat_block
+                .experience
+                .unwrap_or(stat_block.challenge_rating.experience()),
+        ),
+        NormalVision(true),
+    ));
+    if stat_block.senses.low_light_vision {
+        entity_commands.insert(LowLightVision(60.));
+    }
+    if let Some(distance) = stat_block.senses.darkvision {
+        entity_commands.insert(DarkVision(distance));
+    }
+    if faction != Faction::Party {
+        entity_commands.insert(AiController::new(stat_block.behavior));
+    }
+    if let Some(weapon) = stat_block
+        .attacks
+        .iter()
+        .find(|attack| !attack.ranged)
+        .and_then(|attack| attack.weapon)
+    {
+        entity_commands.insert(WieldedWeapon(weapon));
+    }
+    if !stat_block.damage_reduction.is_empty() {
+        entity_commands.insert(DamageReduction(stat_block.damage_reduction.clone()));
+    }
+    if let Some(base) = stat_block.spell_resistance {
+        entity_commands.insert(SpellResistanceRating {
+            base,
+            plus_level: false,
+        });
+    }
+    if !stat_block.energy_resistance.is_empty() {
+        entity_commands.insert(EnergyResistance::from(stat_block.energy_resistance.clone()));
+    }
+    let entity = entity_commands.id();
+    // Armor, natural armor and spell-like abilities go through effects like
+    // any other bonus, so they survive the bonus containers being rebuilt.
+    let armor_class = stat_block.armor_class.bonuses();
+    let spell_like_abilities: Vec<SpellLikeAbility> = stat_block
+        .spell_like_abilities
+        .iter()
+        .copied()
+        .map(SpellLikeAbility::from)
+        .collect();
+    let modifiers: Vec<Modifier> = [
+        (!armor_class.is_empty()).then_some(Modifier::ArmorClass(armor_class)),
+        (!spell_like_abilities.is_empty())
+            .then_some(Modifier::SpellLikeAbility(spell_like_abilities)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !modifiers.is_empty() {
+        commands.spawn(Effect::new(
+            entity,
+            EffectSource::StatBlock(stat_block.name.clone()),
+            modifiers,
+        ));
+    }
+    entity
+}
+
+// Events
+// Puts a creature from a `*.creature.ron` stat block on the grid. The path is
+// relative to the assets folder.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct SpawnCreatureEvent {
+    pub path: String,
+    pub grid_pos: GridPos,
+    pub faction: Faction,
+}
+
+// Systems
+// Starts loading the stat block for each spawn request, and spawns the
+// creature once it has loaded.
+pub fn spawn_creatures(
+    mut event_reader: EventReader<SpawnCreatureEvent>,
+    mut pending: Local<Vec<(Handle<CreatureAsset>, SpawnCreatureEvent)>>,
+    asset_server: Res<AssetServer>,
+    stat_blocks: Res<Assets<CreatureAsset>>,
+    ascii: Res<AsciiSheet>,
+    mut commands: Commands,
+) {
+    for event in event_reader.iter() {
+        pending.push((asset_server.load(event.path.as_str()), event.clone()));
+    }
+    pending.retain(|(handle, event)| {
+        if let Some(stat_block) = stat_blocks.get(handle) {
+            spawn_creature(
+                &mut commands,
+                stat_block,
+                &ascii,
+                event.grid_pos,
+                event.faction,
+            );
+            return false;
+        }
+        if asset_server.get_load_state(handle) == LoadState::Failed {
+            error!("Failed to load creature stat block {}", event.path);
+            return false;
+        }
+        true
+    });
+}
+
+// Rolls damage for hits made by creatures with stat block attacks, using the
+// first melee or ranged attack listed. Strength adds to melee damage, and a
+// hit always deals at least 1 damage.
+pub fn roll_creature_damage(
+    mut event_reader: EventReader<AttackResultEvent>,
+    mut event_writer: EventWriter<DamageEvent>,
+    query_attacker: Query<(&CreatureAttacks, Option<&AbilityScores>)>,
+    mut rng: ResMut<GameRng>,
+) {
+    for event in event_reader.iter().filter(|event| event.hit) {
+        let Ok((attacks, scores)) = query_attacker.get(event.attacker) else {
+            continue;
+        };
+        let attack = if event.ranged {
+            attacks.ranged().next()
+        } else {
+            attacks.melee().next()
+        };
+        let Some(attack) = attack else {
+            continue;
+        };
+        let strength = match scores {
+            Some(scores) if !event.ranged => scores.modifier(&AbilityScore::Strength),
+            _ => 0,
+        };
+        let (number, dice) = &attack.damage;
+        event_writer.send(DamageEvent {
+            source: Some(event.attacker),
+            target: event.target,
+            amount: (rng.roll_many(*number, dice) + strength).max(1),
+            damage_type: DamageType::Physical(attack.damage_type),
+            bypasses: Vec::new(),
+            ranged: event.ranged,
+        });
+    }
+}
@@ -0,0 +1,122 @@
+use crate::systems::game::{
+    class::ClassFeatures,
+    defenses::{
+        damage_reduction_entries, DamageReduction, DamageReductionEntry, DrBypass,
+        EnergyResistance, EnergyType, Immunities, Immunity,
+    },
+    equipment::WeaponDamageType,
+};
+use bevy::prelude::*;
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HitPoints {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl HitPoints {
+    pub fn new(max: i32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageType {
+    Physical(WeaponDamageType),
+    Energy(EnergyType),
+    // Damage that no reduction or resistance applies to, like force.
+    Untyped,
+}
+
+// Damage after the target's defenses. Immunity stops energy damage entirely,
+// energy resistance reduces energy damage, and damage reduction reduces
+// physical damage. Damage is never reduced below 0.
+pub fn reduced_damage(
+    amount: i32,
+    damage_type: &DamageType,
+    bypasses: &[DrBypass],
+    ranged: bool,
+    damage_reduction: &[DamageReductionEntry],
+    resistance: Option<&EnergyResistance>,
+    immunities: Option<&Immunities>,
+) -> i32 {
+    let reduction = match damage_type {
+        DamageType::Physical(physical) => {
+            let mut bypasses = bypasses.to_vec();
+            bypasses.push(DrBypass::DamageType(*physical));
+            DamageReduction::best_vs(damage_reduction.iter(), &bypasses, ranged)
+        }
+        DamageType::Energy(energy) => {
+            if immunities.is_some_and(|immunities| immunities.has(&Immunity::Energy(*energy))) {
+                return 0;
+            }
+            resistance.map_or(0, |resistance| resistance.get(energy))
+        }
+        DamageType::Untyped => 0,
+    };
+    (amount - reduction).max(0)
+}
+
+// Events
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct DamageEvent {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub amount: i32,
+    pub damage_type: DamageType,
+    // Properties of the weapon or attack that get through damage reduction.
+    pub bypasses: Vec<DrBypass>,
+    pub ranged: bool,
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DamageResultEvent {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub dealt: i32,
+    // Damage stopped by reduction, resistance or immunity.
+    pub prevented: i32,
+}
+
+// Systems
+type DamageTargetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut HitPoints,
+        Option<&'static DamageReduction>,
+        Option<&'static ClassFeatures>,
+        Option<&'static EnergyResistance>,
+        Option<&'static Immunities>,
+    ),
+>;
+
+pub fn apply_damage(
+    mut event_reader: EventReader<DamageEvent>,
+    mut event_writer: EventWriter<DamageResultEvent>,
+    mut query_target: DamageTargetQuery,
+) {
+    for event in event_reader.iter() {
+        let Ok((mut hit_points, damage_reduction, features, resistance, immunities)) =
+            query_target.get_mut(event.target)
+        else {
+            continue;
+        };
+        let dealt = reduced_damage(
+            event.amount,
+            &event.damage_type,
+            &event.bypasses,
+            event.ranged,
+            &damage_reduction_entries(damage_reduction, features),
+            resistance,
+            immunities,
+        );
+        hit_points.current -= dealt;
+        event_writer.send(DamageResultEvent {
+            source: event.source,
+            target: event.target,
+            dealt,
+            prevented: event.amount.max(0) - dealt,
+        });
+    }
+}
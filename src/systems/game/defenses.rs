@@ -0,0 +1,317 @@
+use crate::systems::game::{
+    character::{AlignmentMoral, AlignmentOrder, CasterLevelBonuses, ClassLevels, SleepImmunity},
+    class::{ClassFeature, ClassFeatures, FighterFeature},
+    dice::GameRng,
+    equipment::WeaponDamageType,
+    magic::{CasterLevelUse, SpellCauses, SpellDescriptor, SpellResistance},
+    saving_throw::SaveEffect,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+// Energy Types
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+pub enum EnergyType {
+    Acid,
+    Cold,
+    Electricity,
+    Fire,
+    Sonic,
+}
+
+// Damage Reduction
+// What gets through damage reduction, e.g. DR 5/silver is bypassed by silver
+// weapons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum DrBypass {
+    Magic,
+    Silver,
+    ColdIron,
+    Adamantine,
+    Order(AlignmentOrder),
+    Moral(AlignmentMoral),
+    DamageType(WeaponDamageType),
+}
+
+// Whether an attack needs any one of the listed bypasses, as in DR 5/silver
+// or good, or all of them, as in DR 10/cold iron and magic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum DrBypassMode {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct DamageReductionEntry {
+    pub amount: i32,
+    // Empty for DR x/—, which nothing bypasses.
+    #[serde(default)]
+    pub bypass: Vec<DrBypass>,
+    #[serde(default)]
+    pub mode: DrBypassMode,
+    // Only reduces damage from ranged attacks, like the archer's Ranged
+    // Defense.
+    #[serde(default)]
+    pub ranged_only: bool,
+}
+
+impl DamageReductionEntry {
+    pub fn bypassed_by(&self, bypasses: &[DrBypass]) -> bool {
+        let has = |bypass: &DrBypass| bypasses.contains(bypass);
+        !self.bypass.is_empty()
+            && match self.mode {
+                DrBypassMode::Any => self.bypass.iter().any(has),
+                DrBypassMode::All => self.bypass.iter().all(has),
+            }
+    }
+
+    pub fn applies_vs(&self, bypasses: &[DrBypass], ranged: bool) -> bool {
+        (ranged || !self.ranged_only) && !self.bypassed_by(bypasses)
+    }
+}
+
+// Damage reduction from different sources doesn't stack, only the best one
+// that applies is used.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DamageReduction(pub Vec<DamageReductionEntry>);
+
+impl DamageReduction {
+    pub fn best_vs<'a>(
+        entries: impl Iterator<Item = &'a DamageReductionEntry>,
+        bypasses: &[DrBypass],
+        ranged: bool,
+    ) -> i32 {
+        entries
+            .filter(|entry| entry.applies_vs(bypasses, ranged))
+            .map(|entry| entry.amount)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl FighterFeature {
+    // Damage reduction granted by the feature, if any.
+    pub fn damage_reduction(&self) -> Option<DamageReductionEntry> {
+        match self {
+            Self::RangedDefense => Some(DamageReductionEntry {
+                amount: 5,
+                bypass: Vec::new(),
+                mode: DrBypassMode::Any,
+                ranged_only: true,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// Every damage reduction a creature has, from its components and its class
+// features.
+pub fn damage_reduction_entries(
+    damage_reduction: Option<&DamageReduction>,
+    features: Option<&ClassFeatures>,
+) -> Vec<DamageReductionEntry> {
+    let from_features = features.into_iter().flat_map(|features| {
+        features
+            .inner_ref()
+            .iter()
+            .filter_map(|feature| match feature {
+                ClassFeature::Fighter(fighter_feature) => fighter_feature.damage_reduction(),
+                ClassFeature::None => None,
+            })
+    });
+    damage_reduction
+        .into_iter()
+        .flat_map(|damage_reduction| damage_reduction.0.iter().cloned())
+        .chain(from_features)
+        .collect()
+}
+
+// Energy Resistance
+// Resistance to each energy type. Resistances of the same type don't stack.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnergyResistance(pub HashMap<EnergyType, i32>);
+
+impl EnergyResistance {
+    pub fn get(&self, energy: &EnergyType) -> i32 {
+        self.0.get(energy).copied().unwrap_or(0)
+    }
+    pub fn add(&mut self, energy: EnergyType, amount: i32) {
+        let resistance = self.0.entry(energy).or_default();
+        *resistance = (*resistance).max(amount);
+    }
+    pub fn add_all(&mut self, other: &EnergyResistance) {
+        for (energy, amount) in other.0.iter() {
+            self.add(*energy, *amount);
+        }
+    }
+}
+
+impl From<Vec<(EnergyType, i32)>> for EnergyResistance {
+    fn from(other: Vec<(EnergyType, i32)>) -> Self {
+        let mut resistance = Self::default();
+        for (energy, amount) in other {
+            resistance.add(energy, amount);
+        }
+        resistance
+    }
+}
+
+// Immunities
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Immunity {
+    Energy(EnergyType),
+    Descriptor(SpellDescriptor),
+    Causes(SpellCauses),
+    Poison,
+}
+
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Immunities(pub HashSet<Immunity>);
+
+impl Immunities {
+    pub fn has(&self, immunity: &Immunity) -> bool {
+        self.0.contains(immunity)
+    }
+}
+
+// Whether a creature is immune to an effect. `SleepImmunity` only covers
+// magical sleep, so it only counts against spells.
+pub fn immune_to(
+    immunities: Option<&Immunities>,
+    sleep_immunity: Option<&SleepImmunity>,
+    effect: &SaveEffect,
+) -> bool {
+    let sleep = effect.school.is_some()
+        && effect.causes.contains(&SpellCauses::Sleep)
+        && sleep_immunity.is_some();
+    sleep
+        || immunities.is_some_and(|immunities| {
+            (effect.poison && immunities.has(&Immunity::Poison))
+                || effect
+                    .descriptors
+                    .iter()
+                    .any(|descriptor| immunities.has(&Immunity::Descriptor(*descriptor)))
+                || effect
+                    .causes
+                    .iter()
+                    .any(|causes| immunities.has(&Immunity::Causes(*causes)))
+        })
+}
+
+// Spell Resistance
+// A creature's spell resistance, which is `base` plus its character level for
+// creatures like drow.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SpellResistanceRating {
+    pub base: i32,
+    pub plus_level: bool,
+}
+
+impl SpellResistanceRating {
+    pub fn value(&self, class_levels: Option<&ClassLevels>) -> i32 {
+        if self.plus_level {
+            self.base + class_levels.map_or(0, |levels| levels.total_level() as i32)
+        } else {
+            self.base
+        }
+    }
+}
+
+// Caster level check = d20 + caster level + bonuses to overcoming spell
+// resistance.
+pub fn caster_level_check(
+    rng: &mut GameRng,
+    caster_level: usize,
+    bonuses: Option<&CasterLevelBonuses>,
+) -> i32 {
+    rng.d20()
+        + caster_level as i32
+        + bonuses.map_or(0, |bonuses| {
+            bonuses.total_vs(CasterLevelUse::OvercomeSpellResistance)
+        })
+}
+
+// Events
+// A spell or spell-like ability reaching a target, before any saving throw.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SpellEffectEvent {
+    pub caster: Option<Entity>,
+    pub target: Entity,
+    pub caster_level: usize,
+    pub effect: SaveEffect,
+    pub spell_resistance: SpellResistance,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpellEffectOutcome {
+    Affected,
+    Immune,
+    Resisted,
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpellEffectResultEvent {
+    pub caster: Option<Entity>,
+    pub target: Entity,
+    pub outcome: SpellEffectOutcome,
+}
+
+// Systems
+type SpellDefensesQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Immunities>,
+        Option<&'static SleepImmunity>,
+        Option<&'static SpellResistanceRating>,
+        Option<&'static ClassLevels>,
+    ),
+>;
+
+// Checks immunities, then spell resistance, for each spell reaching a target.
+// Creatures can't resist their own spells.
+pub fn resolve_spell_effects(
+    mut event_reader: EventReader<SpellEffectEvent>,
+    mut event_writer: EventWriter<SpellEffectResultEvent>,
+    query_defenses: SpellDefensesQuery,
+    query_caster: Query<Option<&CasterLevelBonuses>>,
+    mut rng: ResMut<GameRng>,
+) {
+    for event in event_reader.iter() {
+        let Ok((immunities, sleep_immunity, spell_resistance, class_levels)) =
+            query_defenses.get(event.target)
+        else {
+            continue;
+        };
+        let outcome = if immune_to(immunities, sleep_immunity, &event.effect) {
+            SpellEffectOutcome::Immune
+        } else {
+            match spell_resistance {
+                Some(rating)
+                    if event.spell_resistance != SpellResistance::No
+                        && event.caster != Some(event.target) =>
+                {
+                    let bonuses = event
+                        .caster
+                        .and_then(|caster| query_caster.get(caster).ok())
+                        .flatten();
+                    if caster_level_check(&mut rng, event.caster_level, bonuses)
+                        >= rating.value(class_levels)
+                    {
+                        SpellEffectOutcome::Affected
+                    } else {
+                        SpellEffectOutcome::Resisted
+                    }
+                }
+                _ => SpellEffectOutcome::Affected,
+            }
+        };
+        event_writer.send(SpellEffectResultEvent {
+            caster: event.caster,
+            target: event.target,
+            outcome,
+        });
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    systems::game::{
+        character::{Alignment, AlignmentMoral, AlignmentOrder},
+        class::{class_alignment_restriction, PlayableClass},
+    },
+    technical::deity::DeityAsset,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+
+// Cleric domains
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+pub enum Domain {
+    Air,
+    Animal,
+    Artifice,
+    Chaos,
+    Charm,
+    Community,
+    Darkness,
+    Death,
+    Destruction,
+    Earth,
+    Evil,
+    Fire,
+    Glory,
+    Good,
+    Healing,
+    Knowledge,
+    Law,
+    Liberation,
+    Luck,
+    Madness,
+    Magic,
+    Nobility,
+    Plant,
+    Protection,
+    Repose,
+    Rune,
+    Strength,
+    Sun,
+    Travel,
+    Trickery,
+    War,
+    Water,
+    Weather,
+}
+
+impl Domain {
+    pub fn array() -> [Domain; 33] {
+        use Domain::*;
+        [
+            Air,
+            Animal,
+            Artifice,
+            Chaos,
+            Charm,
+            Community,
+            Darkness,
+            Death,
+            Destruction,
+            Earth,
+            Evil,
+            Fire,
+            Glory,
+            Good,
+            Healing,
+            Knowledge,
+            Law,
+            Liberation,
+            Luck,
+            Madness,
+            Magic,
+            Nobility,
+            Plant,
+            Protection,
+            Repose,
+            Rune,
+            Strength,
+            Sun,
+            Travel,
+            Trickery,
+            War,
+            Water,
+            Weather,
+        ]
+    }
+    // The aligned domains can only be taken by characters sharing that
+    // alignment.
+    pub fn allows(&self, alignment: &Alignment) -> bool {
+        match self {
+            Domain::Chaos => alignment.order() == AlignmentOrder::Chaotic,
+            Domain::Law => alignment.order() == AlignmentOrder::Lawful,
+            Domain::Good => alignment.moral() == AlignmentMoral::Good,
+            Domain::Evil => alignment.moral() == AlignmentMoral::Evil,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// The name of the deity a character worships.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Deity(pub String);
+
+// Domains chosen from the character's deity.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Domains(pub Vec<Domain>);
+
+// Divine casters whose power comes from a deity, and so must be within one
+// step of its alignment.
+pub fn worships_deity(class: &PlayableClass) -> bool {
+    matches!(
+        class,
+        PlayableClass::Cleric | PlayableClass::Inquisitor | PlayableClass::Warpriest
+    )
+}
+
+// How many of its deity's domains a class picks.
+pub fn domain_count(class: &PlayableClass) -> usize {
+    match class {
+        PlayableClass::Cleric => 2,
+        _ => 0,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AlignmentIssue {
+    NoAlignment,
+    Class(PlayableClass),
+    NoDeity(PlayableClass),
+    // The alignment is more than one step from the deity's.
+    FarFromDeity(String),
+    DomainNotGranted(Domain, String),
+    // An aligned domain the character's alignment doesn't share.
+    OpposedDomain(Domain),
+    DomainCount { needed: usize, chosen: usize },
+}
+
+impl fmt::Display for AlignmentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoAlignment => write!(f, "No alignment chosen"),
+            Self::Class(class) => write!(f, "{class} doesn't allow this alignment"),
+            Self::NoDeity(class) => write!(f, "{class} must worship a deity"),
+            Self::FarFromDeity(deity) => {
+                write!(f, "Alignment must be within one step of {deity}'s")
+            }
+            Self::DomainNotGranted(domain, deity) => {
+                write!(f, "{deity} doesn't grant the {domain} domain")
+            }
+            Self::OpposedDomain(domain) => {
+                write!(f, "The {domain} domain doesn't match this alignment")
+            }
+            Self::DomainCount { needed, chosen } => {
+                write!(f, "{chosen} of {needed} domains chosen")
+            }
+        }
+    }
+}
+
+// Everything wrong with an alignment, deity and domains for the given classes.
+// An empty list means the combination is legal.
+pub fn alignment_issues(
+    alignment: Option<&Alignment>,
+    deity: Option<&DeityAsset>,
+    domains: &[Domain],
+    classes: &[PlayableClass],
+) -> Vec<AlignmentIssue> {
+    let mut issues = Vec::new();
+    let Some(alignment) = alignment else {
+        issues.push(AlignmentIssue::NoAlignment);
+        return issues;
+    };
+    for class in classes.iter() {
+        let restricted =
+            class_alignment_restriction(class).is_some_and(|allowed| !allowed.contains(alignment));
+        if restricted {
+            issues.push(AlignmentIssue::Class(*class));
+        }
+        if worships_deity(class) && deity.is_none() {
+            issues.push(AlignmentIssue::NoDeity(*class));
+        }
+    }
+    let Some(deity) = deity else {
+        return issues;
+    };
+    if classes.iter().any(worships_deity) && alignment.steps_from(&deity.alignment) > 1 {
+        issues.push(AlignmentIssue::FarFromDeity(deity.name.clone()));
+    }
+    for domain in domains.iter() {
+        if !deity.domains.contains(domain) {
+            issues.push(AlignmentIssue::DomainNotGranted(
+                *domain,
+                deity.name.clone(),
+            ));
+        }
+        if !domain.allows(alignment) {
+            issues.push(AlignmentIssue::OpposedDomain(*domain));
+        }
+    }
+    let needed = classes.iter().map(domain_count).max().unwrap_or(0);
+    if domains.len() != needed {
+        issues.push(AlignmentIssue::DomainCount {
+            needed,
+            chosen: domains.len(),
+        });
+    }
+    issues
+}
+
+// Problems with the character's alignment, deity and domains, kept up to date
+// during character creation.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AlignmentIssues(pub Vec<AlignmentIssue>);
@@ -0,0 +1,66 @@
+use crate::systems::game::class::Dice;
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// The single source of randomness for anything rolled during play.
+// Every roll goes through this resource so that a fight can be replayed by
+// inserting a `GameRng` made with the same seed before the rolls start.
+#[derive(Resource, Clone, Debug)]
+pub struct GameRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::random::<u64>())
+    }
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn roll(&mut self, dice: &Dice) -> i32 {
+        self.rng.gen_range(1..=dice.sides())
+    }
+
+    // Roll `number` of the same die and add them together, e.g. 3d6.
+    pub fn roll_many(&mut self, number: usize, dice: &Dice) -> i32 {
+        (0..number).map(|_| self.roll(dice)).sum()
+    }
+
+    pub fn d20(&mut self) -> i32 {
+        self.roll(&Dice::D20)
+    }
+
+    // Uniform value in `0..upper`, for choices that aren't a die roll, like
+    // picking an item out of a list.
+    pub fn below(&mut self, upper: usize) -> usize {
+        self.rng.gen_range(0..upper)
+    }
+
+    // A random item of the slice, or `None` when it's empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.choose(&mut self.rng)
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        items.shuffle(&mut self.rng);
+    }
+
+    // A seed for a generator of its own, like the random character generator,
+    // so a seeded `GameRng` also reproduces what that generator makes.
+    pub fn next_seed(&mut self) -> u64 {
+        self.rng.gen()
+    }
+}
@@ -0,0 +1,584 @@
+use crate::systems::game::{
+    character::{
+        AbilityScore, AbilityScoreBonus, AbilityScoreBonuses, ArmorClassBonus, ArmorClassBonuses,
+        AttackRollBonus, AttackRollBonuses, BonusType, BonusesContainer, CMDBonus, CMDBonuses,
+        CasterLevelBonus, CasterLevelBonuses, LimitationEnum, NewBonusKey, SavingThrowBonus,
+        SavingThrowBonuses, SavingThrowName, SkillBonus, SkillBonuses, SpellDCBonus,
+        SpellDCBonuses, SpellLikeAbilities,
+    },
+    class::{ClassFeature, ClassFeatures, FighterFeature, PlayableClass},
+    conditions::{Condition, Conditions},
+    equipment::{ArmorPiece, WornArmor},
+    magic::{SpellCauses, SpellLikeAbility},
+    race::{IntoHashMapVecBuilder, RacialTraitName},
+    skills::SkillName,
+    time::{EffectDuration, GameClock, Timed},
+};
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// Where an effect came from, so it can be removed along with its source.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EffectSource {
+    RacialTrait(RacialTraitName),
+    ClassFeature(ClassFeature),
+    Item(String),
+    Condition(Condition),
+    // Bonuses written into a monster or NPC stat block, like natural armor.
+    StatBlock(String),
+    // A character trait from the trait catalog, by name.
+    CharacterTrait(String),
+    // Alternate bonuses taken for levels in a favored class.
+    FavoredClass(PlayableClass),
+    // Cover against attacks from one creature.
+    Cover(Entity),
+}
+
+impl EffectSource {
+    pub fn is_racial_trait(&self) -> bool {
+        matches!(self, EffectSource::RacialTrait(_))
+    }
+    pub fn is_class_feature(&self) -> bool {
+        matches!(self, EffectSource::ClassFeature(_))
+    }
+    pub fn is_condition(&self) -> bool {
+        matches!(self, EffectSource::Condition(_))
+    }
+    pub fn is_item(&self) -> bool {
+        matches!(self, EffectSource::Item(_))
+    }
+    pub fn is_cover_from(&self, attacker: Entity) -> bool {
+        *self == EffectSource::Cover(attacker)
+    }
+}
+
+// An untyped penalty to attack rolls.
+fn attack_penalty(penalty: i32) -> Modifier {
+    Modifier::AttackRoll(vec![AttackRollBonus {
+        bonus: -penalty,
+        bonus_type: BonusType::Untyped,
+        key: BonusType::Untyped,
+        limitation: Vec::new(),
+    }])
+}
+
+// An untyped penalty to every skill, or only to skills based on the given
+// abilities.
+fn skill_penalty(penalty: i32, abilities: &[AbilityScore]) -> Modifier {
+    Modifier::Skill(
+        SkillName::default()
+            .iterator()
+            .filter(|skill| abilities.is_empty() || abilities.contains(&skill.ability()))
+            .map(|skill_name| SkillBonus {
+                bonus: -penalty,
+                bonus_type: BonusType::Untyped,
+                skill_name,
+                limitation: Vec::new(),
+            })
+            .collect(),
+    )
+}
+
+// An untyped penalty to all three saving throws.
+fn save_penalty(penalty: i32) -> Modifier {
+    Modifier::SavingThrow(
+        SavingThrowBonus {
+            bonus: -penalty,
+            bonus_type: BonusType::Untyped,
+            saving_throw: SavingThrowName::None,
+            limitation: LimitationEnum::None,
+        }
+        .to_all()
+        .to_vec(),
+    )
+}
+
+fn dexterity_penalty(penalty: i32) -> Modifier {
+    Modifier::AbilityScore(vec![AbilityScoreBonus {
+        ability: AbilityScore::Dexterity,
+        bonus: -penalty,
+        bonus_type: BonusType::Untyped,
+        limitation: LimitationEnum::None,
+    }])
+}
+
+// A group of bonuses of one kind, matching one of the bonus containers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    Skill(Vec<SkillBonus>),
+    ArmorClass(Vec<ArmorClassBonus>),
+    AbilityScore(Vec<AbilityScoreBonus>),
+    SpellLikeAbility(Vec<SpellLikeAbility>),
+    SpellDC(Vec<SpellDCBonus>),
+    AttackRoll(Vec<AttackRollBonus>),
+    CasterLevel(Vec<CasterLevelBonus>),
+    SavingThrow(Vec<SavingThrowBonus>),
+    CombatManeuverDefense(Vec<CMDBonus>),
+}
+
+// Every modifier on a creature is its own effect entity pointing at the
+// creature, so removing a buff or swapping a race only means despawning its
+// effects. The creature's bonus containers are rebuilt from its effects by
+// `rebuild_bonuses`. Effects can be given a duration with `Timed<Effect>`.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct Effect {
+    pub target: Entity,
+    pub source: EffectSource,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl Effect {
+    pub fn new(target: Entity, source: EffectSource, modifiers: Vec<Modifier>) -> Self {
+        Self {
+            target,
+            source,
+            modifiers,
+        }
+    }
+    // The bonuses granted by a racial trait, or `None` for traits that don't
+    // grant any.
+    pub fn from_racial_trait(target: Entity, name: &RacialTraitName) -> Option<Self> {
+        let modifiers: Vec<Modifier> = [
+            SkillBonus::from_name(name).ok().map(Modifier::Skill),
+            ArmorClassBonus::from_name(name)
+                .ok()
+                .map(Modifier::ArmorClass),
+            AbilityScoreBonus::from_name(name)
+                .ok()
+                .map(Modifier::AbilityScore),
+            SpellLikeAbility::from_name(name)
+                .ok()
+                .map(Modifier::SpellLikeAbility),
+            SpellDCBonus::from_name(name).ok().map(Modifier::SpellDC),
+            AttackRollBonus::from_name(name)
+                .ok()
+                .map(Modifier::AttackRoll),
+            CasterLevelBonus::from_name(name)
+                .ok()
+                .map(Modifier::CasterLevel),
+            SavingThrowBonus::from_name(name)
+                .ok()
+                .map(Modifier::SavingThrow),
+            CMDBonus::from_name(name)
+                .ok()
+                .map(Modifier::CombatManeuverDefense),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if modifiers.is_empty() {
+            None
+        } else {
+            Some(Self::new(
+                target,
+                EffectSource::RacialTrait(*name),
+                modifiers,
+            ))
+        }
+    }
+}
+
+impl Effect {
+    // The bonuses granted by a class feature, or `None` for features that
+    // don't grant any.
+    pub fn from_class_feature(target: Entity, feature: &ClassFeature) -> Option<Self> {
+        let modifiers = match feature {
+            ClassFeature::Fighter(FighterFeature::Bravery(Some(bonus))) => {
+                vec![Modifier::SavingThrow(vec![SavingThrowBonus {
+                    bonus: *bonus as i32,
+                    bonus_type: BonusType::Untyped,
+                    saving_throw: SavingThrowName::Will,
+                    limitation: LimitationEnum::SpellCauses(SpellCauses::Fear),
+                }])]
+            }
+            _ => return None,
+        };
+        Some(Self::new(
+            target,
+            EffectSource::ClassFeature(*feature),
+            modifiers,
+        ))
+    }
+
+    // The penalties a condition gives, or `None` for conditions whose
+    // effects aren't bonuses, like being disarmed.
+    pub fn from_condition(target: Entity, condition: &Condition) -> Option<Self> {
+        use AbilityScore::{Dexterity, Strength};
+        let modifiers = match condition {
+            Condition::Blinded => vec![
+                Modifier::ArmorClass(vec![ArmorClassBonus {
+                    bonus: -2,
+                    bonus_type: BonusType::Untyped,
+                    limitation: LimitationEnum::None,
+                }]),
+                skill_penalty(4, &[Strength, Dexterity]),
+            ],
+            Condition::Dazzled => vec![
+                attack_penalty(1),
+                Modifier::Skill(vec![SkillBonus {
+                    bonus: -1,
+                    bonus_type: BonusType::Untyped,
+                    skill_name: SkillName::Perception,
+                    limitation: Vec::new(),
+                }]),
+            ],
+            Condition::Entangled | Condition::Grappled => {
+                vec![attack_penalty(2), dexterity_penalty(4)]
+            }
+            // Prone creatures take the penalty on melee attacks, and can't
+            // make most ranged attacks at all.
+            Condition::Prone => vec![attack_penalty(4)],
+            Condition::Shaken | Condition::Sickened => {
+                vec![attack_penalty(2), save_penalty(2), skill_penalty(2, &[])]
+            }
+            Condition::Deafened | Condition::Disarmed => return None,
+        };
+        Some(Self::new(
+            target,
+            EffectSource::Condition(*condition),
+            modifiers,
+        ))
+    }
+
+    // The armor check penalty a worn piece puts on Strength and Dexterity
+    // based skills, or `None` if it has none.
+    pub fn from_armor(target: Entity, piece: &ArmorPiece) -> Option<Self> {
+        use AbilityScore::{Dexterity, Strength};
+        if piece.check_penalty == 0 {
+            return None;
+        }
+        Some(Self::new(
+            target,
+            EffectSource::Item(piece.category.to_string()),
+            vec![skill_penalty(-piece.check_penalty, &[Strength, Dexterity])],
+        ))
+    }
+}
+
+// The bonus containers for a creature, built up from all of its effects.
+#[derive(Default)]
+pub struct ModifierTotals {
+    skill: Vec<SkillBonus>,
+    armor_class: Vec<ArmorClassBonus>,
+    ability_score: Vec<AbilityScoreBonus>,
+    spell_like_ability: Vec<SpellLikeAbility>,
+    spell_dc: Vec<SpellDCBonus>,
+    attack_roll: Vec<AttackRollBonus>,
+    caster_level: Vec<CasterLevelBonus>,
+    saving_throw: Vec<SavingThrowBonus>,
+    cmd: Vec<CMDBonus>,
+}
+
+impl ModifierTotals {
+    fn add(&mut self, modifier: &Modifier) {
+        match modifier {
+            Modifier::Skill(bonuses) => self.skill.extend(bonuses.iter().cloned()),
+            Modifier::ArmorClass(bonuses) => self.armor_class.extend(bonuses.iter().copied()),
+            Modifier::AbilityScore(bonuses) => self.ability_score.extend(bonuses.iter().copied()),
+            Modifier::SpellLikeAbility(bonuses) => {
+                self.spell_like_ability.extend(bonuses.iter().copied())
+            }
+            Modifier::SpellDC(bonuses) => self.spell_dc.extend(bonuses.iter().cloned()),
+            Modifier::AttackRoll(bonuses) => self.attack_roll.extend(bonuses.iter().cloned()),
+            Modifier::CasterLevel(bonuses) => self.caster_level.extend(bonuses.iter().copied()),
+            Modifier::SavingThrow(bonuses) => self.saving_throw.extend(bonuses.iter().copied()),
+            Modifier::CombatManeuverDefense(bonuses) => self.cmd.extend(bonuses.iter().cloned()),
+        }
+    }
+}
+
+// Bonuses put on a creature directly rather than through an effect.
+// `rebuild_bonuses` starts each container from these and adds the creature's
+// effects on top, so nothing else writes the containers themselves.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct BaseBonuses<C>(pub C);
+
+// A container holding the base bonuses and the ones from effects. Returns
+// `None` when it would be the same as the current one, or when the creature
+// has no container and there is nothing to put in one.
+fn rebuilt_container<K, U, C>(
+    current: Option<&C>,
+    base: Option<&BaseBonuses<C>>,
+    bonuses: &[U],
+) -> Option<C>
+where
+    C: BonusesContainer<K, U> + Clone + PartialEq,
+    U: NewBonusKey<K> + IntoHashMapVecBuilder<C> + Clone,
+    K: Hash + Eq + Copy,
+{
+    if current.is_none() && base.is_none() && bonuses.is_empty() {
+        return None;
+    }
+    let mut container = base.map_or_else(|| C::from(Vec::new()), |base| base.0.clone());
+    let map = container.get_hashmap();
+    for bonus in bonuses {
+        map.entry(bonus.key()).or_default().push(bonus.clone());
+    }
+    (current != Some(&container)).then_some(container)
+}
+
+type BonusContainersQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static SkillBonuses>,
+        Option<&'static ArmorClassBonuses>,
+        Option<&'static AbilityScoreBonuses>,
+        Option<&'static SpellLikeAbilities>,
+        Option<&'static SpellDCBonuses>,
+        Option<&'static AttackRollBonuses>,
+        Option<&'static CasterLevelBonuses>,
+        Option<&'static SavingThrowBonuses>,
+        Option<&'static CMDBonuses>,
+    ),
+>;
+
+type BaseBonusesQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static BaseBonuses<SkillBonuses>>,
+        Option<&'static BaseBonuses<ArmorClassBonuses>>,
+        Option<&'static BaseBonuses<AbilityScoreBonuses>>,
+        Option<&'static BaseBonuses<SpellLikeAbilities>>,
+        Option<&'static BaseBonuses<SpellDCBonuses>>,
+        Option<&'static BaseBonuses<AttackRollBonuses>>,
+        Option<&'static BaseBonuses<CasterLevelBonuses>>,
+        Option<&'static BaseBonuses<SavingThrowBonuses>>,
+        Option<&'static BaseBonuses<CMDBonuses>>,
+    ),
+>;
+
+type ChangedBaseQuery<'w, 's> = Query<
+    'w,
+    's,
+    Entity,
+    Or<(
+        Changed<BaseBonuses<SkillBonuses>>,
+        Changed<BaseBonuses<ArmorClassBonuses>>,
+        Changed<BaseBonuses<AbilityScoreBonuses>>,
+        Changed<BaseBonuses<SpellLikeAbilities>>,
+        Changed<BaseBonuses<SpellDCBonuses>>,
+        Changed<BaseBonuses<AttackRollBonuses>>,
+        Changed<BaseBonuses<CasterLevelBonuses>>,
+        Changed<BaseBonuses<SavingThrowBonuses>>,
+        Changed<BaseBonuses<CMDBonuses>>,
+    )>,
+>;
+
+// Systems
+// Rebuilds the bonus containers of every creature whose effects or base
+// bonuses were added, changed or removed this frame, from its base bonuses
+// and all of its effects. `targets` remembers which creature each effect was
+// on, since a removed effect can't be looked up anymore.
+#[allow(clippy::too_many_arguments)]
+pub fn rebuild_bonuses(
+    query_changed: Query<(Entity, &Effect), Changed<Effect>>,
+    query_changed_base: ChangedBaseQuery,
+    query_effects: Query<&Effect>,
+    query_containers: BonusContainersQuery,
+    query_base: BaseBonusesQuery,
+    mut removed_effects: RemovedComponents<Effect>,
+    mut targets: Local<HashMap<Entity, Entity>>,
+    mut commands: Commands,
+) {
+    let mut dirty: HashSet<Entity> = query_changed_base.iter().collect();
+    for entity in removed_effects.iter() {
+        if let Some(target) = targets.remove(&entity) {
+            dirty.insert(target);
+        }
+        // Expired timed effects leave an empty entity behind.
+        if query_effects.get(entity).is_err() {
+            if let Some(mut entity_commands) = commands.get_entity(entity) {
+                entity_commands.despawn();
+            }
+        }
+    }
+    for (entity, effect) in query_changed.iter() {
+        if let Some(old_target) = targets.insert(entity, effect.target) {
+            dirty.insert(old_target);
+        }
+        dirty.insert(effect.target);
+    }
+    for target in dirty {
+        let Some(mut entity_commands) = commands.get_entity(target) else {
+            continue;
+        };
+        let (Ok(current), Ok(base)) = (query_containers.get(target), query_base.get(target)) else {
+            continue;
+        };
+        let mut totals = ModifierTotals::default();
+        for modifier in query_effects
+            .iter()
+            .filter(|effect| effect.target == target)
+            .flat_map(|effect| effect.modifiers.iter())
+        {
+            totals.add(modifier);
+        }
+        if let Some(new) = rebuilt_container(current.0, base.0, &totals.skill) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.1, base.1, &totals.armor_class) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.2, base.2, &totals.ability_score) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.3, base.3, &totals.spell_like_ability) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.4, base.4, &totals.spell_dc) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.5, base.5, &totals.attack_roll) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.6, base.6, &totals.caster_level) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.7, base.7, &totals.saving_throw) {
+            entity_commands.insert(new);
+        }
+        if let Some(new) = rebuilt_container(current.8, base.8, &totals.cmd) {
+            entity_commands.insert(new);
+        }
+    }
+}
+
+// Swaps out the effects from one kind of source on a creature for a new set.
+fn replace_effects(
+    target: Entity,
+    is_source: impl Fn(&EffectSource) -> bool,
+    effects: impl Iterator<Item = Effect>,
+    query_effects: &Query<(Entity, &Effect)>,
+    commands: &mut Commands,
+) {
+    for (entity, _) in query_effects
+        .iter()
+        .filter(|(_, effect)| effect.target == target && is_source(&effect.source))
+    {
+        commands.entity(entity).despawn();
+    }
+    for effect in effects {
+        commands.spawn(effect);
+    }
+}
+
+// Swaps the cover a creature has against one attacker for the given AC
+// bonus. Cover changes as creatures move, so it only lasts the round it was
+// checked in.
+pub fn replace_cover_effect(
+    target: Entity,
+    attacker: Entity,
+    bonus: i32,
+    clock: &GameClock,
+    query_effects: &Query<(Entity, &Effect)>,
+    commands: &mut Commands,
+) {
+    replace_effects(
+        target,
+        |source| source.is_cover_from(attacker),
+        std::iter::empty(),
+        query_effects,
+        commands,
+    );
+    if bonus > 0 {
+        commands.spawn((
+            Effect::new(
+                target,
+                EffectSource::Cover(attacker),
+                vec![Modifier::ArmorClass(vec![ArmorClassBonus {
+                    bonus,
+                    bonus_type: BonusType::Cover,
+                    limitation: LimitationEnum::AttacksFrom(attacker),
+                }])],
+            ),
+            Timed::<Effect>::new(EffectDuration::Rounds(1), clock),
+        ));
+    }
+}
+
+// Keeps an effect for each class feature that grants bonuses.
+pub fn sync_class_feature_effects(
+    query_features: Query<(Entity, &ClassFeatures), Changed<ClassFeatures>>,
+    query_effects: Query<(Entity, &Effect)>,
+    mut commands: Commands,
+) {
+    for (target, features) in query_features.iter() {
+        let effects = features
+            .inner_ref()
+            .iter()
+            .filter_map(|feature| Effect::from_class_feature(target, feature));
+        replace_effects(
+            target,
+            EffectSource::is_class_feature,
+            effects,
+            &query_effects,
+            &mut commands,
+        );
+    }
+}
+
+// Keeps an effect for each condition on a creature, so its penalties go away
+// when the condition ends.
+pub fn sync_condition_effects(
+    query_conditions: Query<(Entity, &Conditions), Changed<Conditions>>,
+    query_effects: Query<(Entity, &Effect)>,
+    mut removed_conditions: RemovedComponents<Conditions>,
+    mut commands: Commands,
+) {
+    for target in removed_conditions.iter() {
+        replace_effects(
+            target,
+            EffectSource::is_condition,
+            std::iter::empty(),
+            &query_effects,
+            &mut commands,
+        );
+    }
+    for (target, conditions) in query_conditions.iter() {
+        let effects = conditions
+            .inner_ref()
+            .keys()
+            .filter_map(|condition| Effect::from_condition(target, condition));
+        replace_effects(
+            target,
+            EffectSource::is_condition,
+            effects,
+            &query_effects,
+            &mut commands,
+        );
+    }
+}
+
+// Keeps an effect for each piece of worn armor with a check penalty.
+pub fn sync_armor_effects(
+    query_armor: Query<(Entity, &WornArmor), Changed<WornArmor>>,
+    query_effects: Query<(Entity, &Effect)>,
+    mut removed_armor: RemovedComponents<WornArmor>,
+    mut commands: Commands,
+) {
+    for target in removed_armor.iter() {
+        replace_effects(
+            target,
+            EffectSource::is_item,
+            std::iter::empty(),
+            &query_effects,
+            &mut commands,
+        );
+    }
+    for (target, armor) in query_armor.iter() {
+        let effects = armor
+            .0
+            .iter()
+            .filter_map(|piece| Effect::from_armor(target, piece));
+        replace_effects(
+            target,
+            EffectSource::is_item,
+            effects,
+            &query_effects,
+            &mut commands,
+        );
+    }
+}
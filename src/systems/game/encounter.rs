@@ -0,0 +1,131 @@
+use crate::{
+    systems::{
+        game::creature::{ChallengeRating, SpawnCreatureEvent},
+        grid_systems::token::Faction,
+    },
+    technical::{
+        creature::CREATURE_FOLDER,
+        encounter::{EncounterAsset, DEFAULT_ENCOUNTER},
+    },
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Encounter Difficulty
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Average,
+    Challenging,
+    Hard,
+    Epic,
+}
+
+impl Difficulty {
+    pub fn array() -> [Difficulty; 5] {
+        use Difficulty::*;
+        [Easy, Average, Challenging, Hard, Epic]
+    }
+    // How far the encounter's CR is from the average party level.
+    pub fn cr_offset(&self) -> i32 {
+        match self {
+            Difficulty::Easy => -1,
+            Difficulty::Average => 0,
+            Difficulty::Challenging => 1,
+            Difficulty::Hard => 2,
+            Difficulty::Epic => 3,
+        }
+    }
+    pub fn harder(&self) -> Self {
+        let array = Self::array();
+        let index = array.iter().position(|difficulty| difficulty == self);
+        array[index.map_or(0, |index| (index + 1).min(array.len() - 1))]
+    }
+    pub fn easier(&self) -> Self {
+        let array = Self::array();
+        let index = array.iter().position(|difficulty| difficulty == self);
+        array[index.map_or(0, |index| index.saturating_sub(1))]
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Average => write!(f, "Average"),
+            Difficulty::Challenging => write!(f, "Challenging"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Epic => write!(f, "Epic"),
+        }
+    }
+}
+
+// Average party level, rounded to the nearest level. The XP budget assumes a
+// party of four or five, so larger parties count as a level higher and
+// smaller ones as a level lower.
+pub fn average_party_level(levels: &[usize]) -> usize {
+    if levels.is_empty() {
+        return 1;
+    }
+    let average = (levels.iter().sum::<usize>() as f32 / levels.len() as f32).round() as usize;
+    match levels.len() {
+        6.. => average + 1,
+        1..=3 => average.saturating_sub(1).max(1),
+        _ => average,
+    }
+}
+
+// The XP to spend on creatures for an encounter, which is the XP award of a
+// single creature with the encounter's CR.
+pub fn xp_budget(party_level: usize, difficulty: &Difficulty) -> u32 {
+    ChallengeRating::from_level(party_level as i32 + difficulty.cr_offset()).experience()
+}
+
+// The encounter `AppState::Battle` sets up when it starts.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SelectedEncounter(pub Option<Handle<EncounterAsset>>);
+
+// An encounter waiting on its asset to load before it can be spawned.
+#[derive(Resource, Clone, Debug)]
+pub struct PendingEncounter(pub Handle<EncounterAsset>);
+
+// Systems
+pub fn load_default_encounter(
+    mut selected: ResMut<SelectedEncounter>,
+    asset_server: Res<AssetServer>,
+) {
+    selected.0 = Some(asset_server.load(DEFAULT_ENCOUNTER));
+}
+
+pub fn queue_encounter(selected: Res<SelectedEncounter>, mut commands: Commands) {
+    if let Some(handle) = &selected.0 {
+        commands.insert_resource(PendingEncounter(handle.clone()));
+    }
+}
+
+// Places the creatures of the selected encounter on the grid once the
+// encounter has loaded. Encounter creatures are always hostile to the party.
+pub fn spawn_encounter(
+    pending: Res<PendingEncounter>,
+    encounters: Res<Assets<EncounterAsset>>,
+    mut event_writer: EventWriter<SpawnCreatureEvent>,
+    mut commands: Commands,
+) {
+    let Some(encounter) = encounters.get(&pending.0) else {
+        return;
+    };
+    info!(
+        "Starting encounter {}: {} for a level {} party",
+        encounter.name, encounter.difficulty, encounter.party_level
+    );
+    for creature in encounter.creatures.iter() {
+        event_writer.send(SpawnCreatureEvent {
+            path: format!("{CREATURE_FOLDER}/{}", creature.stat_block),
+            grid_pos: creature.grid_pos,
+            faction: Faction::Hostile,
+        });
+    }
+    commands.remove_resource::<PendingEncounter>();
+}
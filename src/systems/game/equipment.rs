@@ -1,4 +1,6 @@
-use crate::systems::game::character::SizeCategory;
+use crate::systems::game::{character::SizeCategory, race::RacialTraitName};
+use bevy::prelude::Component;
+use serde::Deserialize;
 use std::fmt;
 
 pub struct Weapon {
@@ -12,6 +14,7 @@ pub struct Weapon {
     damage_type: WeaponDamageType,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
 pub enum WeaponDamageType {
     Bludgeoning,
     Slashing,
@@ -28,13 +31,14 @@ pub struct WeaponDamage {
     dice_sides: usize,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
 pub enum WeaponProficiency {
     Simple,
     Martial,
     Exotic,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Copy)]
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Copy, Deserialize)]
 pub enum WeaponName {
     Gauntlet,
     UnarmedStrike,
@@ -565,6 +569,19 @@ pub enum RacialWeapon {
 }
 
 impl RacialWeapon {
+    // The weapon group a racial trait grants familiarity with, if any.
+    pub fn from_trait(name: &RacialTraitName) -> Option<Self> {
+        match name {
+            RacialTraitName::BaseDwarfWeaponFamiliarity => Some(Self::Dwarf),
+            RacialTraitName::OrcWeaponFamiliarity => Some(Self::Orc),
+            RacialTraitName::BaseElfWeaponFamiliarity => Some(Self::Elf),
+            RacialTraitName::BaseGnomeWeaponFamiliarity => Some(Self::Gnome),
+            RacialTraitName::BaseHalflingWeaponFamiliarity => Some(Self::Halfling),
+            RacialTraitName::BaseTenguSwordtrained => Some(Self::Tengu),
+            RacialTraitName::BaseDrowWeaponFamiliarity => Some(Self::Drow),
+            _ => None,
+        }
+    }
     pub fn exotic_to_martial_vec(&self) -> Vec<WeaponName> {
         use WeaponName::*;
         match self {
@@ -634,6 +651,150 @@ impl RacialWeapon {
     }
 }
 
+impl WeaponName {
+    pub fn proficiency(&self) -> WeaponProficiency {
+        if WeaponName::array_simple().contains(self) {
+            WeaponProficiency::Simple
+        } else if WeaponName::array_martial().contains(self) {
+            WeaponProficiency::Martial
+        } else {
+            WeaponProficiency::Exotic
+        }
+    }
+
+    // Reach weapons threaten squares at double the wielder's natural reach,
+    // but not adjacent squares. Whips have reach but don't threaten at all,
+    // so they aren't included.
+    pub fn is_reach(&self) -> bool {
+        use WeaponName::*;
+        matches!(
+            self,
+            Bardiche
+                | BecDeCorbin
+                | Bill
+                | BoardingPike
+                | Crook
+                | ElvenBranchedSpear
+                | Fauchard
+                | Flailpole
+                | GiantStickerDwarven
+                | Glaive
+                | GlaiveGuisarme
+                | Guisarme
+                | HammerLucerne
+                | Horsechopper
+                | Kumade
+                | KumadeCollapsible
+                | Lance
+                | LongaxeDwarven
+                | LongHammerDwarven
+                | Longspear
+                | Mancatcher
+                | OgreHook
+                | Ranseur
+        )
+    }
+
+    pub fn is_bow(&self) -> bool {
+        use WeaponName::*;
+        matches!(
+            self,
+            Longbow | LongbowComposite | Shortbow | ShortbowComposite | BowThorn | HornbowOrc
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+pub enum ArmorCategory {
+    Light,
+    Medium,
+    Heavy,
+    Shield,
+    TowerShield,
+}
+
+impl fmt::Display for ArmorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TowerShield => write!(f, "Tower Shield"),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+// A suit of armor or a shield. The armor check penalty is zero or less.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct ArmorPiece {
+    pub category: ArmorCategory,
+    pub check_penalty: i32,
+}
+
+impl ArmorPiece {
+    pub fn is_shield(&self) -> bool {
+        matches!(
+            self.category,
+            ArmorCategory::Shield | ArmorCategory::TowerShield
+        )
+    }
+}
+
+// The armor and shield a creature has on.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WornArmor(pub Vec<ArmorPiece>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum ItemKind {
+    Weapon(WeaponName),
+    Armor(ArmorPiece),
+    Gear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemCategory {
+    Weapon,
+    Armor,
+    Gear,
+}
+
+impl ItemCategory {
+    pub fn array() -> [ItemCategory; 3] {
+        [Self::Weapon, Self::Armor, Self::Gear]
+    }
+}
+
+impl fmt::Display for ItemCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl ItemKind {
+    pub fn category(&self) -> ItemCategory {
+        match self {
+            Self::Weapon(_) => ItemCategory::Weapon,
+            Self::Armor(_) => ItemCategory::Armor,
+            Self::Gear => ItemCategory::Gear,
+        }
+    }
+}
+
+// Something that can be bought and carried. Costs are in copper pieces.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub kind: ItemKind,
+    pub cost: u32,
+    pub weight: f32,
+}
+
+// Everything a character carries, in the order it was gained.
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct Inventory(pub Vec<Item>);
+
+// The weapon a creature currently has in hand.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WieldedWeapon(pub WeaponName);
+
 impl fmt::Display for WeaponName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use WeaponName::*;
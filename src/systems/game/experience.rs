@@ -0,0 +1,200 @@
+use crate::systems::{
+    game::{character::ClassLevels, creature::ExperienceValue, damage::HitPoints},
+    grid_systems::token::Faction,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+pub const MAX_CHARACTER_LEVEL: usize = 20;
+
+// How quickly characters level up, chosen per campaign.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum AdvancementTrack {
+    Slow,
+    #[default]
+    Medium,
+    Fast,
+}
+
+impl AdvancementTrack {
+    // XP needed to reach each level from 2nd to 20th.
+    fn table(&self) -> [u32; MAX_CHARACTER_LEVEL - 1] {
+        match self {
+            AdvancementTrack::Slow => [
+                3_000, 7_500, 14_000, 23_000, 35_000, 53_000, 77_000, 115_000, 160_000, 235_000,
+                330_000, 475_000, 665_000, 955_000, 1_350_000, 1_900_000, 2_700_000, 3_850_000,
+                5_350_000,
+            ],
+            AdvancementTrack::Medium => [
+                2_000, 5_000, 9_000, 15_000, 23_000, 35_000, 51_000, 75_000, 105_000, 155_000,
+                220_000, 315_000, 445_000, 635_000, 890_000, 1_300_000, 1_800_000, 2_550_000,
+                3_600_000,
+            ],
+            AdvancementTrack::Fast => [
+                1_300, 3_300, 6_000, 10_000, 15_000, 23_000, 34_000, 50_000, 71_000, 105_000,
+                145_000, 210_000, 295_000, 425_000, 600_000, 850_000, 1_200_000, 1_700_000,
+                2_400_000,
+            ],
+        }
+    }
+    // XP needed to reach `level`, or `None` past the level cap.
+    pub fn threshold(&self, level: usize) -> Option<u32> {
+        match level {
+            0 | 1 => Some(0),
+            2..=MAX_CHARACTER_LEVEL => Some(self.table()[level - 2]),
+            _ => None,
+        }
+    }
+    // The highest level the XP is enough for.
+    pub fn level_for(&self, points: u32) -> usize {
+        1 + self
+            .table()
+            .iter()
+            .filter(|needed| points >= **needed)
+            .count()
+    }
+}
+
+// A character's experience points.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Experience {
+    pub points: u32,
+    pub track: AdvancementTrack,
+}
+
+impl Experience {
+    pub fn new(track: AdvancementTrack) -> Self {
+        Self { points: 0, track }
+    }
+    // Whether the XP is enough for the level after `current_level`.
+    pub fn can_level_up(&self, current_level: usize) -> bool {
+        self.track
+            .threshold(current_level + 1)
+            .is_some_and(|needed| self.points >= needed)
+    }
+}
+
+// Set on characters with enough XP for their next level, and removed once
+// they've taken it.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReadyToLevelUp;
+
+// Set on creatures brought to 0 hit points or below, so they're only counted
+// once.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Defeated;
+
+// XP from creatures defeated so far in the current encounter, given out once
+// every hostile creature is defeated.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EncounterExperience(pub u32);
+
+// Events
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatureDefeatedEvent {
+    pub entity: Entity,
+    pub experience: u32,
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExperienceAwardedEvent {
+    pub entity: Entity,
+    pub amount: u32,
+}
+
+// Systems
+pub fn reset_encounter_experience(mut encounter_xp: ResMut<EncounterExperience>) {
+    encounter_xp.0 = 0;
+}
+
+type DefeatQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static HitPoints,
+        Option<&'static ExperienceValue>,
+        Option<&'static Faction>,
+    ),
+    (Changed<HitPoints>, Without<Defeated>),
+>;
+
+// Marks creatures at 0 hit points or below as defeated, adding the XP of
+// defeated hostile creatures to the encounter's total.
+pub fn detect_defeats(
+    query_hit_points: DefeatQuery,
+    mut encounter_xp: ResMut<EncounterExperience>,
+    mut event_writer: EventWriter<CreatureDefeatedEvent>,
+    mut commands: Commands,
+) {
+    for (entity, hit_points, experience, faction) in query_hit_points.iter() {
+        if hit_points.current > 0 {
+            continue;
+        }
+        let experience = if faction == Some(&Faction::Hostile) {
+            experience.map_or(0, |experience| experience.0)
+        } else {
+            0
+        };
+        encounter_xp.0 += experience;
+        commands.entity(entity).insert(Defeated);
+        event_writer.send(CreatureDefeatedEvent { entity, experience });
+    }
+}
+
+// Once no hostile creature is left standing, the encounter's XP is split
+// evenly between the party members that track XP.
+pub fn award_experience(
+    mut encounter_xp: ResMut<EncounterExperience>,
+    query_hostile: Query<&Faction, Without<Defeated>>,
+    mut query_party: Query<(Entity, &Faction, &mut Experience)>,
+    mut event_writer: EventWriter<ExperienceAwardedEvent>,
+) {
+    if encounter_xp.0 == 0
+        || query_hostile
+            .iter()
+            .any(|faction| *faction == Faction::Hostile)
+    {
+        return;
+    }
+    let party_size = query_party
+        .iter()
+        .filter(|(_, faction, _)| **faction == Faction::Party)
+        .count();
+    if party_size == 0 {
+        return;
+    }
+    let share = encounter_xp.0 / party_size as u32;
+    for (entity, _, mut experience) in query_party
+        .iter_mut()
+        .filter(|(_, faction, _)| **faction == Faction::Party)
+    {
+        experience.points += share;
+        info!("Awarded {share} XP, now at {}", experience.points);
+        event_writer.send(ExperienceAwardedEvent {
+            entity,
+            amount: share,
+        });
+    }
+    encounter_xp.0 = 0;
+}
+
+type LevelUpQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Experience, Option<&'static ClassLevels>),
+    Or<(Changed<Experience>, Changed<ClassLevels>)>,
+>;
+
+// Keeps `ReadyToLevelUp` in line with each character's XP and current level.
+// Characters without class levels count as 1st level.
+pub fn check_level_up(query_experience: LevelUpQuery, mut commands: Commands) {
+    for (entity, experience, class_levels) in query_experience.iter() {
+        let level = class_levels.map_or(1, |levels| levels.total_level().max(1));
+        if experience.can_level_up(level) {
+            commands.entity(entity).insert(ReadyToLevelUp);
+        } else {
+            commands.entity(entity).remove::<ReadyToLevelUp>();
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use crate::systems::game::{
+    character::{
+        BonusType, CMDBonus, CasterLevelBonus, CombatManeuverName, LimitationEnum, PlayableRace,
+        SavingThrowBonus, SavingThrowName, SkillBonus,
+    },
+    class::{FavoredClass, PlayableClass},
+    effects::{Effect, EffectSource, Modifier},
+    magic::CasterLevelUse,
+    race::RacialTraitName,
+    skills::{SkillName, SkillUse},
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+
+// What a race-specific favored class bonus does, for the ones the game can
+// model. Options without one are kept as description text only.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum FavoredClassBonusKind {
+    // CMD against this many combat maneuvers of the character's choice.
+    CMDVsManeuvers(usize),
+    Skill(SkillName),
+    // Every skill check made for this use.
+    SkillUse(SkillUse),
+    SavingThrow(SavingThrowName),
+    CasterLevel(CasterLevelUse),
+    // Part of a new focus power. Occultist focus powers aren't in the game
+    // yet, so the pick is kept but grants nothing.
+    FocusPower,
+}
+
+// A race-specific favored class bonus, worth `amount` for every `per` times
+// it is taken, so +1/3 is `amount: 1, per: 3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub struct FavoredClassBonus {
+    pub kind: FavoredClassBonusKind,
+    pub amount: i32,
+    pub per: usize,
+}
+
+impl FavoredClassBonus {
+    pub fn total(&self, taken: usize) -> i32 {
+        self.amount * (taken / self.per.max(1)) as i32
+    }
+    // The modifier for a total bonus, or `None` when it has nothing to apply
+    // to yet, like a CMD bonus before its maneuvers are picked.
+    fn into_modifier(self, bonus: i32, maneuvers: &[CombatManeuverName]) -> Option<Modifier> {
+        let modifier = match self.kind {
+            FavoredClassBonusKind::CMDVsManeuvers(_) if maneuvers.is_empty() => return None,
+            FavoredClassBonusKind::CMDVsManeuvers(_) => {
+                Modifier::CombatManeuverDefense(vec![CMDBonus {
+                    bonus,
+                    bonus_type: BonusType::Untyped,
+                    key: BonusType::Untyped,
+                    limitation: maneuvers
+                        .iter()
+                        .map(|maneuver| LimitationEnum::CombatManeuverName(*maneuver))
+                        .collect(),
+                }])
+            }
+            FavoredClassBonusKind::Skill(skill_name) => Modifier::Skill(vec![SkillBonus {
+                bonus,
+                bonus_type: BonusType::Untyped,
+                skill_name,
+                limitation: vec![LimitationEnum::None],
+            }]),
+            FavoredClassBonusKind::SkillUse(skill_use) => Modifier::Skill(
+                SkillName::default()
+                    .iterator()
+                    .map(|skill_name| SkillBonus {
+                        bonus,
+                        bonus_type: BonusType::Untyped,
+                        skill_name,
+                        limitation: vec![LimitationEnum::SkillUse(skill_use)],
+                    })
+                    .collect(),
+            ),
+            FavoredClassBonusKind::SavingThrow(saving_throw) => {
+                Modifier::SavingThrow(vec![SavingThrowBonus {
+                    bonus,
+                    bonus_type: BonusType::Untyped,
+                    saving_throw,
+                    limitation: LimitationEnum::None,
+                }])
+            }
+            FavoredClassBonusKind::CasterLevel(limitation) => {
+                Modifier::CasterLevel(vec![CasterLevelBonus {
+                    bonus,
+                    bonus_type: BonusType::Untyped,
+                    limitation,
+                }])
+            }
+            FavoredClassBonusKind::FocusPower => return None,
+        };
+        Some(modifier)
+    }
+}
+
+impl Effect {
+    // The bonus for taking a favored class option `taken` times, or `None`
+    // when that isn't yet enough to be worth anything, or has nothing to
+    // apply to.
+    pub fn from_favored_class(
+        target: Entity,
+        class: PlayableClass,
+        bonus: &FavoredClassBonus,
+        taken: usize,
+        maneuvers: &[CombatManeuverName],
+    ) -> Option<Self> {
+        let total = bonus.total(taken);
+        if total == 0 {
+            return None;
+        }
+        let modifier = bonus.into_modifier(total, maneuvers)?;
+        Some(Self::new(
+            target,
+            EffectSource::FavoredClass(class),
+            vec![modifier],
+        ))
+    }
+}
+
+// What is gained for one level in a favored class. `Alternate` is the index
+// of the option among `alternate_options` for the race and class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FavoredClassChoice {
+    #[default]
+    HitPoint,
+    SkillRank,
+    Alternate(usize),
+}
+
+// The favored classes of a character, and the bonus picked for each level
+// taken in them.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FavoredClasses {
+    pub classes: Vec<PlayableClass>,
+    // One pick per level in a favored class, in the order of `favored_levels`.
+    pub choices: Vec<(PlayableClass, FavoredClassChoice)>,
+    // Maneuvers picked for `CMDVsManeuvers` bonuses.
+    pub maneuvers: Vec<CombatManeuverName>,
+}
+
+impl FavoredClasses {
+    pub fn hit_points(&self) -> usize {
+        self.choices
+            .iter()
+            .filter(|(_, choice)| *choice == FavoredClassChoice::HitPoint)
+            .count()
+    }
+    pub fn skill_ranks(&self) -> usize {
+        self.choices
+            .iter()
+            .filter(|(_, choice)| *choice == FavoredClassChoice::SkillRank)
+            .count()
+    }
+    // How many times each alternate option was taken for a class, as
+    // (index, times) pairs.
+    pub fn alternate_picks(&self, class: &PlayableClass) -> Vec<(usize, usize)> {
+        let mut taken: Vec<(usize, usize)> = Vec::new();
+        for (_, choice) in self.choices.iter().filter(|(c, _)| c == class) {
+            let FavoredClassChoice::Alternate(index) = choice else {
+                continue;
+            };
+            match taken.iter_mut().find(|(i, _)| i == index) {
+                Some((_, times)) => *times += 1,
+                None => taken.push((*index, 1)),
+            }
+        }
+        taken
+    }
+    // Keeps one pick per favored class level, dropping picks for levels
+    // that are gone and taking the hit point for new ones.
+    pub fn fit_to_levels(&mut self, levels: &[PlayableClass]) {
+        let old = std::mem::take(&mut self.choices);
+        self.choices = levels
+            .iter()
+            .enumerate()
+            .map(|(slot, class)| match old.get(slot) {
+                Some((old_class, choice)) if old_class == class => (*class, *choice),
+                _ => (*class, FavoredClassChoice::HitPoint),
+            })
+            .collect();
+    }
+}
+
+// Half-elves with Multitalented pick two favored classes, everyone else one.
+pub fn favored_class_count(traits: &[RacialTraitName]) -> usize {
+    if traits.contains(&RacialTraitName::BaseHalfElfMultitalented) {
+        2
+    } else {
+        1
+    }
+}
+
+// One entry per level taken in a favored class, naming the class.
+pub fn favored_levels(
+    classes: &[PlayableClass],
+    level_of: impl Fn(&PlayableClass) -> usize,
+) -> Vec<PlayableClass> {
+    classes
+        .iter()
+        .flat_map(|class| std::iter::repeat_n(*class, level_of(class)))
+        .collect()
+}
+
+// The race-specific options for a class that the game can apply.
+pub fn alternate_options<'a>(
+    options: &[&'a FavoredClass],
+    race: &PlayableRace,
+    class: &PlayableClass,
+) -> Vec<&'a FavoredClass> {
+    options
+        .iter()
+        .copied()
+        .filter(|option| option.race == *race && option.class == *class && option.bonus.is_some())
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FavoredClassIssue {
+    Count { allowed: usize, chosen: usize },
+    NoAlternate(PlayableClass),
+    Maneuvers { needed: usize, chosen: usize },
+}
+
+impl fmt::Display for FavoredClassIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Count { allowed, chosen } => {
+                write!(f, "{chosen} of {allowed} favored classes chosen")
+            }
+            Self::NoAlternate(class) => {
+                write!(f, "A {class} bonus isn't offered for this race")
+            }
+            Self::Maneuvers { needed, chosen } => {
+                write!(f, "{chosen} of {needed} combat maneuvers chosen")
+            }
+        }
+    }
+}
+
+// Each alternate option taken, with its bonus and how many times it was
+// taken. The bonus is `None` when the race doesn't offer that option.
+pub fn alternates_taken(
+    favored: &FavoredClasses,
+    options: &[&FavoredClass],
+    race: &PlayableRace,
+) -> Vec<(PlayableClass, Option<FavoredClassBonus>, usize)> {
+    favored
+        .classes
+        .iter()
+        .flat_map(|class| {
+            let alternates = alternate_options(options, race, class);
+            favored
+                .alternate_picks(class)
+                .into_iter()
+                .map(move |(index, taken)| {
+                    let bonus = alternates.get(index).and_then(|option| option.bonus);
+                    (*class, bonus, taken)
+                })
+        })
+        .collect()
+}
+
+// How many combat maneuvers have to be picked for the CMD bonuses taken.
+pub fn maneuvers_needed(taken: &[(PlayableClass, Option<FavoredClassBonus>, usize)]) -> usize {
+    taken
+        .iter()
+        .filter_map(|(_, bonus, _)| match bonus.map(|bonus| bonus.kind) {
+            Some(FavoredClassBonusKind::CMDVsManeuvers(count)) => Some(count),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+// Everything wrong with a set of favored classes, given the race's options.
+// An empty list means the choice is legal.
+pub fn favored_class_issues(
+    favored: &FavoredClasses,
+    allowed: usize,
+    options: &[&FavoredClass],
+    race: &PlayableRace,
+) -> Vec<FavoredClassIssue> {
+    let mut issues = Vec::new();
+    if favored.classes.len() != allowed {
+        issues.push(FavoredClassIssue::Count {
+            allowed,
+            chosen: favored.classes.len(),
+        });
+    }
+    let taken = alternates_taken(favored, options, race);
+    for (class, _, _) in taken.iter().filter(|(_, bonus, _)| bonus.is_none()) {
+        issues.push(FavoredClassIssue::NoAlternate(*class));
+    }
+    let needed = maneuvers_needed(&taken);
+    if favored.maneuvers.len() != needed {
+        issues.push(FavoredClassIssue::Maneuvers {
+            needed,
+            chosen: favored.maneuvers.len(),
+        });
+    }
+    issues
+}
+
+// Problems with the character's favored classes, kept up to date during
+// character creation.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FavoredClassIssues(pub Vec<FavoredClassIssue>);
@@ -0,0 +1,83 @@
+use crate::systems::game::{
+    character::{AbilityScore, AbilityScores, BaseLanguages, Language},
+    class::PlayableClass,
+    skills::{SkillName, SkillRanks},
+};
+use bevy::prelude::*;
+use std::collections::BTreeSet;
+
+// Every language a character speaks, from their race, class, Intelligence and
+// ranks in Linguistics.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KnownLanguages(pub BTreeSet<Language>);
+
+impl KnownLanguages {
+    pub fn new(base: &BaseLanguages, class: &PlayableClass, picks: &[Language]) -> Self {
+        let mut known: BTreeSet<Language> = base.given.iter().copied().collect();
+        known.extend(class_languages(class));
+        known.extend(picks.iter().copied());
+        Self(known)
+    }
+    pub fn knows(&self, language: &Language) -> bool {
+        self.0.contains(language)
+    }
+}
+
+// Languages a class teaches at 1st level, on top of the racial ones.
+pub fn class_languages(class: &PlayableClass) -> Vec<Language> {
+    match class {
+        PlayableClass::Druid => vec![Language::Druidic],
+        _ => Vec::new(),
+    }
+}
+
+// How many languages a character picks beyond the ones they are given.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BonusLanguages {
+    // One per point of Intelligence modifier, from the race's choices.
+    pub intelligence: usize,
+    // One per rank in Linguistics, from any language that isn't secret.
+    pub linguistics: usize,
+}
+
+impl BonusLanguages {
+    pub fn new(scores: Option<&AbilityScores>, ranks: Option<&SkillRanks>) -> Self {
+        let modifier = scores.map_or(0, |scores| scores.modifier(&AbilityScore::Intelligence));
+        Self {
+            intelligence: modifier.max(0) as usize,
+            linguistics: ranks.map_or(0, |ranks| ranks.get(&SkillName::Linguistics)),
+        }
+    }
+    pub fn total(&self) -> usize {
+        self.intelligence + self.linguistics
+    }
+    // Whether a language could be picked at all, ignoring how many are picked.
+    pub fn offers(&self, base: &BaseLanguages, class: &PlayableClass, language: &Language) -> bool {
+        if base.given.contains(language) || class_languages(class).contains(language) {
+            return false;
+        }
+        let from_race = self.intelligence > 0 && base.options().contains(language);
+        let from_linguistics = self.linguistics > 0 && !language.is_secret();
+        from_race || from_linguistics
+    }
+    // Whether the picks can be split between the two sources. Picks the race
+    // doesn't offer have to come from Linguistics, and secret ones the race
+    // does offer from Intelligence.
+    pub fn allows(&self, base: &BaseLanguages, class: &PlayableClass, picks: &[Language]) -> bool {
+        let options = base.options();
+        let (mut race_only, mut linguistics_only) = (0, 0);
+        for pick in picks.iter() {
+            if !self.offers(base, class, pick) {
+                return false;
+            }
+            match (options.contains(pick), pick.is_secret()) {
+                (true, true) => race_only += 1,
+                (false, _) => linguistics_only += 1,
+                (true, false) => (),
+            }
+        }
+        race_only <= self.intelligence
+            && linguistics_only <= self.linguistics
+            && picks.len() <= self.total()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::systems::game::character;
+use crate::systems::{game::character, grid_systems::area_of_effect::AreaShape};
 
 //// How Spells will work
 // Spells will each have their own `system` and `Event`. When the spell is
@@ -32,6 +32,8 @@ use crate::systems::game::character;
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Ord)]
 pub enum SlaSource {
     GnomeMagic,
+    // Listed in a creature's stat block.
+    StatBlock,
 }
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq)]
@@ -53,7 +55,7 @@ pub enum CastFrequency {
 }
 
 // Ways in which Caster Level can be used, useful for setting limitations
-#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Ord)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Ord, serde::Deserialize)]
 pub enum CasterLevelUse {
     Cast,
     Dispel,
@@ -61,7 +63,7 @@ pub enum CasterLevelUse {
     Concentration,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Ord)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Ord, serde::Deserialize)]
 pub enum SpellName {
     //// Spells from Gnome Magic trait, used to implement the racial trait.
     // Level 0
@@ -79,6 +81,8 @@ pub struct Spell {
     sub_school: SpellSubSchool,
     descriptor: SpellDescriptor,
     range: SpellRange,
+    // `None` for spells that target creatures or objects instead of an area.
+    area: Option<AreaShape>,
     /* more fields */
 }
 
@@ -101,6 +105,7 @@ pub enum SpellSchool {
 pub enum SpellCauses {
     Fear,
     Confusion,
+    Sleep,
     /* more here */
 }
 
@@ -1,9 +1,31 @@
+pub mod ai;
 pub mod archetype;
+pub mod attack;
+pub mod attack_of_opportunity;
 pub mod character;
+pub mod character_traits;
 pub mod class;
+pub mod combat_maneuver;
+pub mod conditions;
 pub mod constants;
+pub mod creature;
+pub mod damage;
+pub mod defenses;
+pub mod deity;
+pub mod dice;
+pub mod effects;
+pub mod encounter;
 pub mod equipment;
+pub mod experience;
+pub mod favored_class;
+pub mod languages;
 pub mod magic;
+pub mod proficiency;
 pub mod race;
+pub mod random_character;
 pub mod resources;
+pub mod saving_throw;
 pub mod skills;
+pub mod time;
+pub mod validation;
+pub mod wealth;
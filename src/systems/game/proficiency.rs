@@ -0,0 +1,221 @@
+use crate::systems::game::{
+    archetype::ArchetypeInfo,
+    character::{CharacterWeaponProficiency, ClassLevels},
+    class::PlayableClass,
+    equipment::{
+        ArmorCategory, Item, ItemKind, WeaponName, WeaponProficiency, WieldedWeapon, WornArmor,
+    },
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::{collections::HashSet, fmt};
+
+// Attack penalty for using a weapon without proficiency.
+pub const NON_PROFICIENT_WEAPON_PENALTY: i32 = -4;
+
+// Something a character can be proficient with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum Proficiency {
+    // Every weapon in the group.
+    Weapons(WeaponProficiency),
+    Weapon(WeaponName),
+    Armor(ArmorCategory),
+}
+
+// Feats that grant a proficiency, named without their "Proficiency" suffix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum ProficiencyFeat {
+    SimpleWeapon,
+    MartialWeapon(WeaponName),
+    ExoticWeapon(WeaponName),
+    LightArmor,
+    MediumArmor,
+    HeavyArmor,
+    Shield,
+    TowerShield,
+}
+
+impl ProficiencyFeat {
+    pub fn grants(&self) -> Proficiency {
+        match self {
+            Self::SimpleWeapon => Proficiency::Weapons(WeaponProficiency::Simple),
+            Self::MartialWeapon(weapon) | Self::ExoticWeapon(weapon) => {
+                Proficiency::Weapon(*weapon)
+            }
+            Self::LightArmor => Proficiency::Armor(ArmorCategory::Light),
+            Self::MediumArmor => Proficiency::Armor(ArmorCategory::Medium),
+            Self::HeavyArmor => Proficiency::Armor(ArmorCategory::Heavy),
+            Self::Shield => Proficiency::Armor(ArmorCategory::Shield),
+            Self::TowerShield => Proficiency::Armor(ArmorCategory::TowerShield),
+        }
+    }
+}
+
+impl fmt::Display for ProficiencyFeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::SimpleWeapon => write!(f, "Simple Weapon Proficiency"),
+            Self::MartialWeapon(weapon) => write!(f, "Martial Weapon Proficiency ({weapon})"),
+            Self::ExoticWeapon(weapon) => write!(f, "Exotic Weapon Proficiency ({weapon})"),
+            Self::LightArmor => write!(f, "Armor Proficiency, Light"),
+            Self::MediumArmor => write!(f, "Armor Proficiency, Medium"),
+            Self::HeavyArmor => write!(f, "Armor Proficiency, Heavy"),
+            Self::Shield => write!(f, "Shield Proficiency"),
+            Self::TowerShield => write!(f, "Tower Shield Proficiency"),
+        }
+    }
+}
+
+// Proficiency feats a character has taken.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProficiencyFeats(pub Vec<ProficiencyFeat>);
+
+// How many feats a character picks: one at 1st level and every odd level
+// after, a fighter's bonus feats at 1st level and every even fighter level,
+// and the bonus feats from racial traits. Only the proficiency feats are
+// modelled. They are all combat feats, so they fill the fighter's bonus feats
+// too. Every other feat, like Power Attack or Weapon Focus, is out of scope.
+pub fn feat_slots(class_levels: &ClassLevels, racial_bonus_feats: usize) -> usize {
+    let fighter = class_levels.level(&PlayableClass::Fighter);
+    let fighter_bonus = if fighter > 0 { 1 + fighter / 2 } else { 0 };
+    class_levels.total_level().div_ceil(2) + fighter_bonus + racial_bonus_feats
+}
+
+// The weapon groups and armor a class is proficient with. Classes with a
+// short list of weapons of their own, like the monk or wizard, count as
+// proficient with all simple weapons.
+pub fn class_proficiencies(class: &PlayableClass) -> Vec<Proficiency> {
+    use ArmorCategory::{Heavy, Light, Shield, TowerShield};
+    use PlayableClass::*;
+    let mut weapons = vec![WeaponProficiency::Simple];
+    if matches!(
+        class,
+        Barbarian
+            | Bloodrager
+            | Cavalier
+            | Fighter
+            | Hunter
+            | Magus
+            | Paladin
+            | Ranger
+            | Skald
+            | Slayer
+            | Swashbuckler
+            | Vigilante
+            | Gunslinger
+    ) {
+        weapons.push(WeaponProficiency::Martial);
+    }
+    let armor = match class {
+        Fighter => vec![Light, ArmorCategory::Medium, Heavy, Shield, TowerShield],
+        Cavalier | Paladin | Warpriest => vec![Light, ArmorCategory::Medium, Heavy, Shield],
+        Barbarian | Bloodrager | Cleric | Druid | Hunter | Inquisitor | Oracle | Ranger | Skald
+        | Slayer | Vigilante => vec![Light, ArmorCategory::Medium, Shield],
+        Occultist | Shaman => vec![Light, ArmorCategory::Medium],
+        Bard => vec![Light, Shield],
+        Alchemist | Gunslinger | Investigator | Kineticist | Magus | Medium | Mesmerist | Rogue
+        | Spiritualist | Summoner | Swashbuckler => vec![Light],
+        _ => vec![],
+    };
+    weapons
+        .into_iter()
+        .map(Proficiency::Weapons)
+        .chain(armor.into_iter().map(Proficiency::Armor))
+        .collect()
+}
+
+// Everything a character is proficient with, merged from its classes,
+// archetypes, race and feats.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Proficiencies {
+    pub granted: HashSet<Proficiency>,
+    // Exotic weapons that racial weapon familiarity treats as martial.
+    pub familiar: HashSet<WeaponName>,
+}
+
+impl Proficiencies {
+    // Archetypes take away proficiencies from their class before adding their
+    // own, so the race and feats can still grant what an archetype lost.
+    pub fn new(
+        classes: &[PlayableClass],
+        archetypes: &[&ArchetypeInfo],
+        racial: Option<&CharacterWeaponProficiency>,
+        feats: Option<&ProficiencyFeats>,
+    ) -> Self {
+        let mut granted: HashSet<Proficiency> =
+            classes.iter().flat_map(class_proficiencies).collect();
+        for archetype in archetypes.iter() {
+            for lost in archetype.loses_proficiency.iter().flatten() {
+                granted.remove(lost);
+            }
+            granted.extend(archetype.gains_proficiency.iter().flatten().copied());
+        }
+        let mut familiar = HashSet::new();
+        if let Some(racial) = racial {
+            for (weapon, proficient) in racial
+                .simple
+                .iter()
+                .chain(racial.martial.iter())
+                .chain(racial.exotic.iter())
+            {
+                if *proficient {
+                    granted.insert(Proficiency::Weapon(*weapon));
+                }
+            }
+            familiar.extend(
+                racial
+                    .martial
+                    .keys()
+                    .filter(|weapon| weapon.proficiency() == WeaponProficiency::Exotic),
+            );
+        }
+        granted.extend(
+            feats
+                .iter()
+                .flat_map(|feats| feats.0.iter().map(|feat| feat.grants())),
+        );
+        Self { granted, familiar }
+    }
+    pub fn with_weapon(&self, weapon: &WeaponName) -> bool {
+        let group = if self.familiar.contains(weapon) {
+            WeaponProficiency::Martial
+        } else {
+            weapon.proficiency()
+        };
+        self.granted.contains(&Proficiency::Weapon(*weapon))
+            || self.granted.contains(&Proficiency::Weapons(group))
+    }
+    pub fn with_armor(&self, category: &ArmorCategory) -> bool {
+        self.granted.contains(&Proficiency::Armor(*category))
+    }
+    // Gear needs no proficiency.
+    pub fn with_item(&self, item: &Item) -> bool {
+        match &item.kind {
+            ItemKind::Weapon(weapon) => self.with_weapon(weapon),
+            ItemKind::Armor(piece) => self.with_armor(&piece.category),
+            ItemKind::Gear => true,
+        }
+    }
+    // Whether what a feat grants is already had some other way.
+    pub fn has_feat(&self, feat: &ProficiencyFeat) -> bool {
+        match feat.grants() {
+            Proficiency::Weapon(weapon) => self.with_weapon(&weapon),
+            granted => self.granted.contains(&granted),
+        }
+    }
+    // Attack roll penalty from the wielded weapon and worn armor. Armor and
+    // shields worn without proficiency add their armor check penalty.
+    pub fn attack_penalty(&self, weapon: Option<&WieldedWeapon>, armor: Option<&WornArmor>) -> i32 {
+        let weapon_penalty = match weapon {
+            Some(weapon) if !self.with_weapon(&weapon.0) => NON_PROFICIENT_WEAPON_PENALTY,
+            _ => 0,
+        };
+        let armor_penalty: i32 = armor
+            .iter()
+            .flat_map(|armor| armor.0.iter())
+            .filter(|piece| !self.with_armor(&piece.category))
+            .map(|piece| piece.check_penalty)
+            .sum();
+        weapon_penalty + armor_penalty
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::uninlined_format_args)]
 
 use crate::systems::game::character::*;
+use crate::systems::game::defenses::{EnergyResistance, EnergyType, SpellResistanceRating};
+use crate::systems::game::effects::Effect;
 use crate::systems::game::equipment::*;
 use crate::systems::game::magic::*;
 use crate::systems::game::skills::*;
@@ -97,7 +99,7 @@ pub enum RacialTraitName {
     BaseHalfElfLanguages,
     // BaseHalfElfAdaptability,
     // BaseHalfElfElfBlood,
-    // BaseHalfElfMultitalented,
+    BaseHalfElfMultitalented,
     // // Half-Orc Base Traits,
     // BaseHalfOrcType,
     BaseHalfOrcLanguages,
@@ -115,7 +117,7 @@ pub enum RacialTraitName {
     // // Aasimar Base Traits
     BaseAasimarASB, // +2 Wis, +2 Cha
     BaseAasimarLanguages,
-    // BaseAasimarCelestialResistance,
+    BaseAasimarCelestialResistance,
     // BaseAasimarSkilled,
     // BaseAasimarSLA, // Spell-like Ability
     // // Catfolk Base Traits
@@ -138,8 +140,8 @@ pub enum RacialTraitName {
     // // Drow Base Traits
     BaseDrowASB, // +2 Dex, +2 Cha, -2 Con
     BaseDrowLanguages,
-    // BaseDrowImmunities,
-    // BaseDrowSpellResistance,
+    BaseDrowImmunities,
+    BaseDrowSpellResistance,
     // BaseDrowSLA,
     // BaseDrowPoisonUse,
     // BaseDrowLightBlindness,
@@ -161,7 +163,7 @@ pub enum RacialTraitName {
     HobgoblinSneaky,
     // // Ifrit Base Traits
     BaseIfritLanguages,
-    // BaseIfritEnergyResistance, // fire 5
+    BaseIfritEnergyResistance, // fire 5
     // BaseIfritSLA,
     // BaseIfritFireAffinity,
     // // Kobold Base Traits
@@ -177,7 +179,7 @@ pub enum RacialTraitName {
     // // Oread Base Traits
     StrWisMinusChaASB,
     BaseOreadLanguages,
-    // BaseOreadEnergyResistance, // 5 Earth
+    BaseOreadEnergyResistance, // acid 5
     // BaseOreadSLA,
     // BaseOreadEarthAffinity,
     // // Ratfolk Base Traits
@@ -189,7 +191,7 @@ pub enum RacialTraitName {
     // BaseRatfolkSwarming,
     // // Sylph Base Traits
     BaseSylphLanguages,
-    // BaseSylphEnergyResistance,
+    BaseSylphEnergyResistance,
     // BaseSylphSLA,
     // BaseSylphAirAffinity,
     // // Tengu Base Traits
@@ -204,14 +206,14 @@ pub enum RacialTraitName {
     // // Tiefling Base Traits
     DexIntMinusChaASB,
     BaseTieflingLanguages,
-    // BaseTieflingFiendishResistance,
+    BaseTieflingFiendishResistance,
     // BaseTieflingSkilled,
     // BaseTieflingSLA,
     // BaseTieflingFiendishSorcery,
     // // Undine Base Traits
     DexWisMinusStrASB,
     BaseUndineLanguages,
-    // BaseUndineEnergyResistance,
+    BaseUndineEnergyResistance,
     // BaseUndineSLA,
     // BaseUndineWaterAffinity,
     AlchemicallyEnhanced,
@@ -293,7 +295,7 @@ impl RacialTraitName {
                 KeenSenses,
                 LowLightVision,
                 // BaseHalfElfElfBlood,
-                // BaseHalfElfMultitalented,
+                BaseHalfElfMultitalented,
             ],
 
             PlayableRace::HalfOrc => vec![
@@ -331,7 +333,7 @@ impl RacialTraitName {
                 SizeMedium,
                 SpeedNormal,
                 //         BaseAasimarLanguages,
-                //         BaseAasimarCelestialResistance,
+                BaseAasimarCelestialResistance,
                 //         BaseAasimarSkilled,
                 //         BaseAasimarSLA, // Spell-like Ability
                 DarkVision,
@@ -374,8 +376,8 @@ impl RacialTraitName {
                 SpeedNormal,
                 BaseDrowWeaponFamiliarity,
                 //         BaseDrowLanguages,
-                //         BaseDrowImmunities,
-                //         BaseDrowSpellResistance,
+                BaseDrowImmunities,
+                BaseDrowSpellResistance,
                 KeenSenses,
                 //         BaseDrowSLA,
                 //         BaseDrowPoisonUse,
@@ -424,7 +426,7 @@ impl RacialTraitName {
                 SizeMedium,
                 SpeedNormal,
                 //         BaseIfritLanguages,
-                //         BaseIfritEnergyResistance, // fire 5
+                BaseIfritEnergyResistance, // fire 5
                 //         BaseIfritSLA,
                 //         BaseIfritFireAffinity,
                 DarkVision,
@@ -460,7 +462,7 @@ impl RacialTraitName {
                 SizeMedium,
                 SpeedSlow,
                 //         BaseOreadLanguages,
-                //         BaseOreadEnergyResistance, // 5 Earth
+                BaseOreadEnergyResistance, // acid 5
                 //         BaseOreadSLA,
                 DarkVision,
                 //         BaseOreadEarthAffinity,
@@ -484,7 +486,7 @@ impl RacialTraitName {
                 SizeMedium,
                 SpeedNormal,
                 //         BaseSylphLanguages,
-                //         BaseSylphEnergyResistance,
+                BaseSylphEnergyResistance,
                 //         BaseSylphSLA,
                 DarkVision,
                 //         BaseSylphAirAffinity,
@@ -509,7 +511,7 @@ impl RacialTraitName {
                 SizeMedium,
                 SpeedNormal,
                 //         BaseTieflingLanguages,
-                //         BaseTieflingFiendishResistance,
+                BaseTieflingFiendishResistance,
                 //         BaseTieflingSkilled,
                 //         BaseTieflingSLA,
                 DarkVision,
@@ -522,7 +524,7 @@ impl RacialTraitName {
                 SizeMedium,
                 SpeedNormal,
                 //         BaseUndineLanguages,
-                //         BaseUndineEnergyResistance,
+                BaseUndineEnergyResistance,
                 //         BaseUndineSLA,
                 //         BaseUndineWaterAffinity,
                 DarkVision,
@@ -605,6 +607,33 @@ pub fn spawn_player(mut commands: Commands) {
 }
 //
 
+// Components only the race gives the builder, removed again by `reset_race`
+// when the race changes.
+pub type RacialComponents = (
+    (
+        CreatureType,
+        CharacterSize,
+        BaseLanguages,
+        BonusSkillPerLevel,
+        GroundSpeed,
+        NormalVision,
+        LowLightVision,
+        DarkVision,
+    ),
+    (
+        SpellResistanceRating,
+        SleepImmunity,
+        EnergyResistance,
+        CreatureSubtypes,
+    ),
+);
+
+// The racial traits the builder's race was last built from, so `reset_race`
+// can take back just what they added to the floating bonuses, which other
+// sources add to as well.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuiltRacialTraits(pub Vec<RacialTraitName>);
+
 pub fn build_race(
     race: Res<RaceBuilder>,
     mut commands: Commands,
@@ -612,15 +641,7 @@ pub fn build_race(
     mut q_floating_bonus_feats: Query<&mut FloatingBonusFeats, With<CharacterBuilder>>,
     mut q_floating_ability_bonuses: Query<&mut FloatingAbilityBonuses, With<CharacterBuilder>>,
     mut q_creature_subtypes: Query<&mut CreatureSubtypes, With<CharacterBuilder>>,
-    mut q_skill_bonuses: Query<&mut SkillBonuses, With<CharacterBuilder>>,
-    mut q_ability_score_bonuses: Query<&mut AbilityScoreBonuses, With<CharacterBuilder>>,
-    mut q_saving_throw_bonuses: Query<&mut SavingThrowBonuses, With<CharacterBuilder>>,
-    mut q_caster_level_bonuses: Query<&mut CasterLevelBonuses, With<CharacterBuilder>>,
-    mut q_ac_bonuses: Query<&mut ArmorClassBonuses, With<CharacterBuilder>>,
     mut q_floating_skill_bonuses: Query<&mut FloatingSkillBonuses, With<CharacterBuilder>>,
-    mut q_spell_like_abilities: Query<&mut SpellLikeAbilities, With<CharacterBuilder>>,
-    mut q_spell_dc_bonuses: Query<&mut SpellDCBonuses, With<CharacterBuilder>>,
-    mut q_attack_roll_bonuses: Query<&mut AttackRollBonuses, With<CharacterBuilder>>,
 ) {
     let builder_entity = query_builder.get_single().unwrap();
     let mut entity_commands = &mut commands.get_entity(builder_entity).unwrap();
@@ -635,6 +656,9 @@ pub fn build_race(
         // LowLightVision
         // DarkVision
         // CharacterWeaponProficiency
+        // SpellResistanceRating
+        // SleepImmunity
+        // EnergyResistance
         if let Ok(creature_type) = CreatureType::from_name(racial_trait_name) {
             entity_commands = entity_commands.insert(creature_type);
         };
@@ -662,6 +686,16 @@ pub fn build_race(
         if let Ok(racial_proficiency) = CharacterWeaponProficiency::from_name(racial_trait_name) {
             entity_commands = entity_commands.insert(racial_proficiency);
         };
+        if let Ok(spell_resistance) = SpellResistanceRating::from_name(racial_trait_name) {
+            entity_commands = entity_commands.insert(spell_resistance);
+        };
+        if let Ok(sleep_immunity) = SleepImmunity::from_name(racial_trait_name) {
+            entity_commands = entity_commands.insert(sleep_immunity);
+        };
+        // Each race has at most one energy resistance trait.
+        if let Ok(energy_resistance) = EnergyResistance::from_name(racial_trait_name) {
+            entity_commands = entity_commands.insert(energy_resistance);
+        };
 
         //// RacialTraitNames with a corresponding IntoVecBuilder impl
         // FloatingBonusFeat
@@ -696,70 +730,13 @@ pub fn build_race(
                 entity_commands.insert(CreatureSubtypes::from(floating_bonus_feat));
             }
         }
-        //// RacialTraitNames with a corresponding IntoHashMapVecBuilder impl
-        // SkillBonus
-        // ArmorClassBonus
-        // AbilityScoreBonus
-        // SpellLikeAbility
-        // SpellDCBonus
-        // AttackRollBonuses
-        // CasterLevelBonuses
-        // SavingThrowBonuses
-        if let Ok(skill_bonus) = SkillBonus::from_name(racial_trait_name) {
-            if let Ok(mut skill_bonuses) = q_skill_bonuses.get_mut(builder_entity) {
-                skill_bonuses.add_or_insert_all(skill_bonus);
-            } else {
-                entity_commands.insert(SkillBonus::vec_to_component(skill_bonus));
-            }
-        }
-        if let Ok(ac_bonus) = ArmorClassBonus::from_name(racial_trait_name) {
-            if let Ok(mut ac_bonuses) = q_ac_bonuses.get_mut(builder_entity) {
-                ac_bonuses.add_or_insert_all(ac_bonus);
-            } else {
-                entity_commands.insert(ArmorClassBonus::vec_to_component(ac_bonus));
-            }
-        }
-        if let Ok(ability_score_bonus) = AbilityScoreBonus::from_name(racial_trait_name) {
-            if let Ok(mut ability_score_bonuses) = q_ability_score_bonuses.get_mut(builder_entity) {
-                ability_score_bonuses.add_or_insert_all(ability_score_bonus)
-            } else {
-                entity_commands.insert(AbilityScoreBonuses::from(ability_score_bonus));
-            }
-        }
-        if let Ok(new_bonuses) = SpellLikeAbility::from_name(racial_trait_name) {
-            if let Ok(mut old_bonuses) = q_spell_like_abilities.get_mut(builder_entity) {
-                old_bonuses.add_or_insert_all(new_bonuses)
-            } else {
-                entity_commands.insert(SpellLikeAbilities::from(new_bonuses));
-            }
-        }
-        if let Ok(new_bonuses) = SpellDCBonus::from_name(racial_trait_name) {
-            if let Ok(mut old_bonuses) = q_spell_dc_bonuses.get_mut(builder_entity) {
-                old_bonuses.add_or_insert_all(new_bonuses)
-            } else {
-                entity_commands.insert(SpellDCBonuses::from(new_bonuses));
-            }
-        }
-        if let Ok(new_bonuses) = AttackRollBonus::from_name(racial_trait_name) {
-            if let Ok(mut old_bonuses) = q_attack_roll_bonuses.get_mut(builder_entity) {
-                old_bonuses.add_or_insert_all(new_bonuses)
-            } else {
-                entity_commands.insert(AttackRollBonuses::from(new_bonuses));
-            }
-        }
-        if let Ok(new_bonuses) = CasterLevelBonus::from_name(racial_trait_name) {
-            if let Ok(mut old_bonuses) = q_caster_level_bonuses.get_mut(builder_entity) {
-                old_bonuses.add_or_insert_all(new_bonuses)
-            } else {
-                entity_commands.insert(CasterLevelBonuses::from(new_bonuses));
-            }
-        }
-        if let Ok(new_bonuses) = SavingThrowBonus::from_name(racial_trait_name) {
-            if let Ok(mut old_bonuses) = q_saving_throw_bonuses.get_mut(builder_entity) {
-                old_bonuses.add_or_insert_all(new_bonuses)
-            } else {
-                entity_commands.insert(SavingThrowBonuses::from(new_bonuses));
-            }
+    }
+    entity_commands.insert(BuiltRacialTraits(race.0.clone()));
+    // Bonuses from racial traits are kept as effects on the builder, so they
+    // can be removed again when the race changes.
+    for racial_trait_name in race.0.iter() {
+        if let Some(effect) = Effect::from_racial_trait(builder_entity, racial_trait_name) {
+            commands.spawn(effect);
         }
     }
 }
@@ -864,68 +841,27 @@ use std::error::Error;
 ///////////////////////////////////////////////////////////////////////////////
 ////// IntoComponentBuilder
 //// Weapon Familiarity
-// BaseElfWeaponFamiliarity
+// Every weapon familiarity trait in `RacialWeapon`: the race's exotic weapons
+// become martial, and it is proficient with its racial weapons.
 impl IntoComponentBuilder for CharacterWeaponProficiency {
     fn from_name(racial_trait_name: &RacialTraitName) -> Result<Self, Box<dyn Error>> {
-        match racial_trait_name {
-            RacialTraitName::BaseElfWeaponFamiliarity => {
-                let mut proficiency = CharacterWeaponProficiency::new();
-                let mut elf_martial = RacialWeapon::Elf.exotic_to_martial_vec();
-                for _ in 0..elf_martial.len() {
-                    let weapon = elf_martial.pop()
-                        .expect("Invalid WeaponName in RacialWeapon::Elf.exotic_to_martial_vec(), or empty Vec, passed to from_name() method of CharacterWeaponProficiency from trait IntoComponentBuilder");
-                    proficiency.exotic.remove(&weapon);
-                    proficiency.martial.insert(weapon, false);
-                }
-                for elf_proficient in RacialWeapon::Elf.racial_proficient_vec() {
-                    proficiency.martial.insert(elf_proficient, true);
-                }
-
-                Ok(proficiency)
-            }
-            RacialTraitName::BaseGnomeWeaponFamiliarity => {
-                let mut proficiency = CharacterWeaponProficiency::new();
-                let mut race_martial = RacialWeapon::Gnome.exotic_to_martial_vec();
-                for _ in 0..race_martial.len() {
-                    let weapon = race_martial.pop()
-                        .expect("Invalid WeaponName in RacialWeapon::Gnome.exotic_to_martial_vec(), or empty Vec, passed to from_name() method of CharacterWeaponProficiency from trait IntoComponentBuilder");
-                    proficiency.exotic.remove(&weapon);
-                    proficiency.martial.insert(weapon, false);
-                }
-                Ok(proficiency)
-            }
-            RacialTraitName::BaseDrowWeaponFamiliarity => {
-                let mut proficiency = CharacterWeaponProficiency::new();
-                for drow_proficient in RacialWeapon::Drow.racial_proficient_vec() {
-                    proficiency.martial.insert(drow_proficient, true);
-                }
-                Ok(proficiency)
-            }
-            RacialTraitName::BaseTenguSwordtrained => {
-                let mut proficiency = CharacterWeaponProficiency::new();
-                for tengu_proficient in RacialWeapon::Tengu.racial_proficient_vec() {
-                    proficiency.martial.insert(tengu_proficient, true);
-                }
-                Ok(proficiency)
-            }
-            RacialTraitName::BaseDwarfWeaponFamiliarity => {
-                let mut proficiency = CharacterWeaponProficiency::new();
-                let mut race_martial = RacialWeapon::Dwarf.exotic_to_martial_vec();
-                for _ in 0..race_martial.len() {
-                    let weapon = race_martial.pop()
-                        .expect("Invalid WeaponName in RacialWeapon::Gnome.exotic_to_martial_vec(), or empty Vec, passed to from_name() method of CharacterWeaponProficiency from trait IntoComponentBuilder");
-                    proficiency.exotic.remove(&weapon);
-                    proficiency.martial.insert(weapon, false);
-                }
-                Ok(proficiency)
-            }
-            _ => Err(format!(
+        let Some(racial_weapon) = RacialWeapon::from_trait(racial_trait_name) else {
+            return Err(format!(
                 "Invalid RacialTraitName: {:?} for CharacterWeaponProficiency in \
                 from_name() method of trait IntoComponentBuilder",
                 racial_trait_name
             )
-            .into()),
+            .into());
+        };
+        let mut proficiency = CharacterWeaponProficiency::new();
+        for weapon in racial_weapon.exotic_to_martial_vec() {
+            proficiency.exotic.remove(&weapon);
+            proficiency.martial.insert(weapon, false);
         }
+        for weapon in racial_weapon.racial_proficient_vec() {
+            proficiency.martial.insert(weapon, true);
+        }
+        Ok(proficiency)
     }
 }
 //// Skills per level
@@ -1212,6 +1148,64 @@ impl IntoComponentBuilder for LowLightVision {
         }
     }
 }
+// Energy resistance from the planetouched races' traits
+impl IntoComponentBuilder for EnergyResistance {
+    fn from_name(racial_trait_name: &RacialTraitName) -> Result<Self, Box<dyn Error>> {
+        use EnergyType::*;
+        match racial_trait_name {
+            RacialTraitName::BaseAasimarCelestialResistance => {
+                Ok(Self::from(vec![(Acid, 5), (Cold, 5), (Electricity, 5)]))
+            }
+            RacialTraitName::BaseIfritEnergyResistance => Ok(Self::from(vec![(Fire, 5)])),
+            RacialTraitName::BaseOreadEnergyResistance => Ok(Self::from(vec![(Acid, 5)])),
+            RacialTraitName::BaseSylphEnergyResistance => Ok(Self::from(vec![(Electricity, 5)])),
+            RacialTraitName::BaseTieflingFiendishResistance => {
+                Ok(Self::from(vec![(Cold, 5), (Electricity, 5), (Fire, 5)]))
+            }
+            RacialTraitName::BaseUndineEnergyResistance => Ok(Self::from(vec![(Cold, 5)])),
+            _ => Err(format!(
+                "Invalid RacialTraitName: {:?} for EnergyResistance in \
+                from_name() method of trait IntoComponentBuilder",
+                racial_trait_name
+            )
+            .into()),
+        }
+    }
+}
+// Drow spell resistance is 6 + character level
+impl IntoComponentBuilder for SpellResistanceRating {
+    fn from_name(racial_trait_name: &RacialTraitName) -> Result<Self, Box<dyn Error>> {
+        match racial_trait_name {
+            RacialTraitName::BaseDrowSpellResistance => Ok(Self {
+                base: 6,
+                plus_level: true,
+            }),
+            _ => Err(format!(
+                "Invalid RacialTraitName: {:?} for SpellResistanceRating in \
+                from_name() method of trait IntoComponentBuilder",
+                racial_trait_name
+            )
+            .into()),
+        }
+    }
+}
+// Elven and drow immunities: immune to magic sleep, the bonus to saves against
+// enchantments is added as a SavingThrowBonus.
+impl IntoComponentBuilder for SleepImmunity {
+    fn from_name(racial_trait_name: &RacialTraitName) -> Result<Self, Box<dyn Error>> {
+        match racial_trait_name {
+            RacialTraitName::ElvenImmunities | RacialTraitName::BaseDrowImmunities => {
+                Ok(SleepImmunity)
+            }
+            _ => Err(format!(
+                "Invalid RacialTraitName: {:?} for SleepImmunity in \
+                from_name() method of trait IntoComponentBuilder",
+                racial_trait_name
+            )
+            .into()),
+        }
+    }
+}
 ////// IntoVecBuilder
 //// Floating Feats
 impl IntoVecBuilder for FloatingBonusFeat {
@@ -1304,6 +1298,26 @@ impl IntoHashMapVecBuilder<SavingThrowBonuses> for SavingThrowBonus {
                     limitation: LimitationEnum::SpellSchool(SpellSchool::Illusion),
                 },
             ]),
+            RacialTraitName::ElvenImmunities | RacialTraitName::BaseDrowImmunities => Ok(vec![
+                Self {
+                    bonus: 2,
+                    bonus_type: BonusType::Racial,
+                    saving_throw: SavingThrowName::Will,
+                    limitation: LimitationEnum::SpellSchool(SpellSchool::Enchantment),
+                },
+                Self {
+                    bonus: 2,
+                    bonus_type: BonusType::Racial,
+                    saving_throw: SavingThrowName::Reflex,
+                    limitation: LimitationEnum::SpellSchool(SpellSchool::Enchantment),
+                },
+                Self {
+                    bonus: 2,
+                    bonus_type: BonusType::Racial,
+                    saving_throw: SavingThrowName::Fort,
+                    limitation: LimitationEnum::SpellSchool(SpellSchool::Enchantment),
+                },
+            ]),
             RacialTraitName::BaseDwarfHardy => Ok(vec![
                 Self {
                     bonus: 2,
@@ -0,0 +1,651 @@
+use crate::{
+    systems::game::{
+        archetype::{ArchetypeInfo, ArchetypeName, Restriction},
+        character::{
+            point_buy_cost, AbilityScore, AbilityScoreBonus, AbilityScoreBonuses, AbilityScores,
+            Alignment, BaseLanguages, BonusSkillPerLevel, CharacterWeaponProficiency, ClassLevels,
+            FloatingAbilityBonus, FloatingBonusFeat, FloatingBonusPicks, FloatingSkillBonus,
+            Language, PlayableRace, PointBuy,
+        },
+        class::{key_ability, ClassMap, Dice, PlayableClass},
+        deity::{alignment_issues, domain_count, worships_deity, Domain},
+        dice::GameRng,
+        equipment::{Item, ItemKind, WeaponName},
+        favored_class::{favored_class_count, favored_levels, FavoredClassChoice, FavoredClasses},
+        languages::{class_languages, BonusLanguages},
+        proficiency::{feat_slots, Proficiencies, ProficiencyFeat, ProficiencyFeats},
+        race::{IntoComponentBuilder, IntoHashMapVecBuilder, IntoVecBuilder, RacialTraitName},
+        skills::{SkillName, SkillRanks},
+        validation::{missing_prerequisite, skill_rank_budget, CharacterRecord},
+        wealth::{starting_wealth, Wealth},
+    },
+    technical::{alternate_traits::AltTraitAsset, deity::DeityAsset},
+};
+use std::{collections::HashMap, fmt};
+
+// How the base ability scores of a random character are made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScoreMethod {
+    // 4d6 for each score, dropping the lowest die.
+    Roll,
+    // Scores bought with this many points.
+    PointBuy(i32),
+}
+
+impl fmt::Display for ScoreMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Roll => write!(f, "Rolled Scores"),
+            Self::PointBuy(points) => write!(f, "{points} Point Buy"),
+        }
+    }
+}
+
+// Choices kept instead of rolled, so a random elf fighter locks the race to
+// elf and the class to fighter. A locked archetype is only kept when it is
+// open to the race and class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RandomLocks {
+    pub race: Option<PlayableRace>,
+    pub class: Option<PlayableClass>,
+    pub archetype: Option<ArchetypeName>,
+}
+
+// The catalogs random choices are drawn from. They're sorted before use, so
+// the order they were loaded in doesn't change the character a seed makes.
+pub struct RandomCatalogs<'a> {
+    pub class_map: &'a ClassMap,
+    pub archetypes: &'a [&'a ArchetypeInfo],
+    pub alternate_traits: &'a [&'a AltTraitAsset],
+    pub deities: &'a [&'a DeityAsset],
+    pub items: &'a [&'a Item],
+}
+
+// A first level character made by `random_character`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomCharacter {
+    pub seed: u64,
+    pub race: PlayableRace,
+    pub racial_traits: Vec<RacialTraitName>,
+    pub class: PlayableClass,
+    pub archetype: Option<ArchetypeName>,
+    pub scores: AbilityScores,
+    pub point_buy: Option<PointBuy>,
+    pub floating_picks: FloatingBonusPicks,
+    pub skill_ranks: SkillRanks,
+    pub feats: ProficiencyFeats,
+    pub languages: Vec<Language>,
+    pub alignment: Alignment,
+    // Name of the deity's asset.
+    pub deity: Option<String>,
+    pub domains: Vec<Domain>,
+    pub favored: FavoredClasses,
+    pub starting_wealth: Wealth,
+    pub purchases: Vec<Item>,
+}
+
+fn random_race(rng: &mut GameRng) -> PlayableRace {
+    let races: Vec<PlayableRace> = PlayableRace::iterator()
+        .filter(|race| !RacialTraitName::default_traits(race).is_empty())
+        .collect();
+    rng.choose(&races).copied().unwrap_or_default()
+}
+
+// The race's default traits with some of its alternate traits swapped in. An
+// alternate is only taken while everything it replaces is still there, so no
+// trait is replaced twice.
+fn random_racial_traits(
+    rng: &mut GameRng,
+    race: &PlayableRace,
+    assets: &[&AltTraitAsset],
+) -> Vec<RacialTraitName> {
+    let mut traits = RacialTraitName::default_traits(race);
+    let mut alternates: Vec<_> = assets
+        .iter()
+        .filter(|asset| asset.race == *race)
+        .flat_map(|asset| asset.alternate_traits.iter())
+        .collect();
+    alternates.sort_by(|a, b| a.title.cmp(&b.title));
+    rng.shuffle(&mut alternates);
+    for alternate in alternates {
+        let fits = !traits.contains(&alternate.my_trait_name)
+            && alternate
+                .replaces_names
+                .iter()
+                .all(|name| traits.contains(name));
+        if fits && rng.below(3) == 0 {
+            traits.retain(|name| !alternate.replaces_names.contains(name));
+            traits.push(alternate.my_trait_name);
+        }
+    }
+    traits
+}
+
+// Only classes with their rules loaded can be picked, since the class skills,
+// skill ranks and base attack all come from the class map.
+fn random_class(rng: &mut GameRng, class_map: &ClassMap) -> PlayableClass {
+    let mut classes: Vec<PlayableClass> = class_map
+        .inner_ref()
+        .keys()
+        .filter(|class| **class != PlayableClass::None)
+        .copied()
+        .collect();
+    classes.sort();
+    rng.choose(&classes).copied().unwrap_or_default()
+}
+
+// Archetypes are always picked when the class has one open to the race,
+// since the selected archetype applies to a class with any.
+fn random_archetype(
+    rng: &mut GameRng,
+    locked: Option<ArchetypeName>,
+    race: &PlayableRace,
+    class: &PlayableClass,
+    archetypes: &[&ArchetypeInfo],
+) -> Option<ArchetypeName> {
+    let mut open: Vec<&ArchetypeInfo> = archetypes
+        .iter()
+        .copied()
+        .filter(|archetype| {
+            let races: Vec<&PlayableRace> = archetype
+                .restrictions
+                .iter()
+                .flatten()
+                .filter_map(|restriction| match restriction {
+                    Restriction::Race(race) => Some(race),
+                    _ => None,
+                })
+                .collect();
+            archetype.class == *class && (races.is_empty() || races.contains(&race))
+        })
+        .collect();
+    if let Some(locked) = locked.filter(|locked| open.iter().any(|open| open.name == *locked)) {
+        return Some(locked);
+    }
+    open.sort_by_key(|archetype| archetype.name.to_string());
+    rng.choose(&open).map(|archetype| archetype.name)
+}
+
+// The abilities in the order the best scores go to them: the class's key
+// ability first, the rest shuffled.
+fn ability_priority(rng: &mut GameRng, key: AbilityScore) -> Vec<AbilityScore> {
+    let mut rest: Vec<AbilityScore> = AbilityScore::as_array()
+        .into_iter()
+        .filter(|ability| *ability != key)
+        .collect();
+    rng.shuffle(&mut rest);
+    std::iter::once(key).chain(rest).collect()
+}
+
+fn rolled_scores(rng: &mut GameRng, priority: &[AbilityScore]) -> AbilityScores {
+    let mut rolls: Vec<i32> = priority
+        .iter()
+        .map(|_| {
+            let mut dice: Vec<i32> = (0..4).map(|_| rng.roll(&Dice::D6)).collect();
+            dice.sort();
+            dice[1..].iter().sum()
+        })
+        .collect();
+    rolls.sort_by(|a, b| b.cmp(a));
+    let mut scores = AbilityScores::new();
+    for (ability, score) in priority.iter().zip(rolls) {
+        scores.set(*ability, score);
+    }
+    scores
+}
+
+// Spends the budget a step at a time, leaning toward the abilities early in
+// `priority` and never raising one past the key ability. When what's left
+// can't buy another step, the last abilities are lowered below 10 for more
+// points and aren't raised again.
+fn bought_scores(rng: &mut GameRng, budget: i32, priority: &[AbilityScore]) -> AbilityScores {
+    let cost = |score: i32| point_buy_cost(score).unwrap_or(0);
+    let mut values = vec![10; priority.len()];
+    let mut lowered = vec![false; priority.len()];
+    loop {
+        let left = budget - values.iter().map(|score| cost(*score)).sum::<i32>();
+        if left == 0 {
+            break;
+        }
+        let raisable: Vec<usize> = (0..values.len())
+            .filter(|index| {
+                !lowered[*index]
+                    && (*index == 0 || values[*index] < values[0])
+                    && point_buy_cost(values[*index] + 1)
+                        .is_some_and(|next| next - cost(values[*index]) <= left)
+            })
+            .collect();
+        if left > 0 && !raisable.is_empty() {
+            // The lower of two picks is more often one of the first abilities.
+            let pick = rng.below(raisable.len()).min(rng.below(raisable.len()));
+            values[raisable[pick]] += 1;
+        } else if let Some(index) = (1..values.len())
+            .rev()
+            .find(|index| point_buy_cost(values[*index] - 1).is_some())
+        {
+            lowered[index] = true;
+            values[index] -= 1;
+        } else {
+            break;
+        }
+    }
+    let mut scores = AbilityScores::new();
+    for (ability, score) in priority.iter().zip(values) {
+        scores.set(*ability, score);
+    }
+    scores
+}
+
+// Floating ability bonuses go to the first abilities in `priority` that they
+// offer, floating skill bonuses to a random skill each offers.
+fn random_floating_picks(
+    rng: &mut GameRng,
+    traits: &[RacialTraitName],
+    priority: &[AbilityScore],
+) -> FloatingBonusPicks {
+    let mut picks = FloatingBonusPicks::default();
+    for bonus in traits
+        .iter()
+        .filter_map(|name| FloatingAbilityBonus::from_name(name).ok())
+    {
+        for _ in 0..bonus.choices_num {
+            let ability = priority
+                .iter()
+                .find(|ability| {
+                    bonus.choices.contains(ability) && !picks.abilities.contains(ability)
+                })
+                .or(bonus.choices.first());
+            picks.abilities.extend(ability);
+        }
+    }
+    for bonus in traits
+        .iter()
+        .filter_map(|name| FloatingSkillBonus::from_name(name).ok())
+    {
+        picks.skills.extend(rng.choose(&bonus.choices));
+    }
+    picks
+}
+
+// The class is always favored, with a second random class for races that
+// get two. Each favored level takes the hit point or the skill rank.
+fn random_favored_classes(
+    rng: &mut GameRng,
+    traits: &[RacialTraitName],
+    class: &PlayableClass,
+) -> FavoredClasses {
+    let mut classes = vec![*class];
+    if favored_class_count(traits) > 1 {
+        let others: Vec<PlayableClass> = PlayableClass::iterator()
+            .filter(|other| other != class)
+            .collect();
+        classes.extend(rng.choose(&others));
+    }
+    let levels = favored_levels(&classes, |favored| usize::from(favored == class));
+    let choices = levels
+        .into_iter()
+        .map(|favored| {
+            let choice = if rng.below(2) == 0 {
+                FavoredClassChoice::HitPoint
+            } else {
+                FavoredClassChoice::SkillRank
+            };
+            (favored, choice)
+        })
+        .collect();
+    FavoredClasses {
+        classes,
+        choices,
+        maneuvers: Vec::new(),
+    }
+}
+
+// Spends the budget on class skills first and then on the other skills, as
+// many ranks in each as the level allows.
+fn random_skill_ranks(
+    rng: &mut GameRng,
+    budget: usize,
+    level: usize,
+    class_skills: &[SkillName],
+) -> SkillRanks {
+    let mut first = class_skills.to_vec();
+    rng.shuffle(&mut first);
+    let mut rest: Vec<SkillName> = SkillName::default()
+        .iterator()
+        .filter(|skill| !class_skills.contains(skill))
+        .collect();
+    rng.shuffle(&mut rest);
+    let mut ranks = SkillRanks::default();
+    let mut left = budget;
+    for skill in first.into_iter().chain(rest) {
+        let taken = level.min(left);
+        if taken == 0 {
+            break;
+        }
+        ranks.0.insert(skill, taken);
+        left -= taken;
+    }
+    ranks
+}
+
+// Fills the character's feat slots, counted by `feat_slots`, with proficiency
+// feats the character qualifies for and doesn't already have the proficiency
+// from. The weapon feats offered are one random martial and one random exotic
+// weapon each time.
+fn random_feats(
+    rng: &mut GameRng,
+    needed: usize,
+    class: &PlayableClass,
+    archetypes: &[&ArchetypeInfo],
+    racial: Option<&CharacterWeaponProficiency>,
+    base_attack: usize,
+) -> ProficiencyFeats {
+    use ProficiencyFeat::*;
+    let mut feats = ProficiencyFeats::default();
+    for _ in 0..needed {
+        let proficiencies = Proficiencies::new(&[*class], archetypes, racial, Some(&feats));
+        let martial = rng.choose(&WeaponName::array_martial()).copied();
+        let exotic = rng.choose(&WeaponName::array_exotic()).copied();
+        let candidates: Vec<ProficiencyFeat> = [
+            SimpleWeapon,
+            LightArmor,
+            MediumArmor,
+            HeavyArmor,
+            Shield,
+            TowerShield,
+        ]
+        .into_iter()
+        .chain(martial.map(MartialWeapon))
+        .chain(exotic.map(ExoticWeapon))
+        .filter(|feat| {
+            !proficiencies.has_feat(feat)
+                && missing_prerequisite(feat, &proficiencies, base_attack).is_none()
+        })
+        .collect();
+        match rng.choose(&candidates) {
+            Some(feat) => feats.0.push(*feat),
+            None => break,
+        }
+    }
+    feats
+}
+
+// Bonus languages picked in a random order for as long as another one fits.
+fn random_languages(
+    rng: &mut GameRng,
+    base: Option<&BaseLanguages>,
+    class: &PlayableClass,
+    scores: &AbilityScores,
+    ranks: &SkillRanks,
+) -> Vec<Language> {
+    let Some(base) = base else {
+        return Vec::new();
+    };
+    let bonus = BonusLanguages::new(Some(scores), Some(ranks));
+    let known: Vec<Language> = base
+        .given
+        .iter()
+        .copied()
+        .chain(class_languages(class))
+        .collect();
+    let mut options: Vec<Language> = Language::array()
+        .into_iter()
+        .filter(|language| !known.contains(language))
+        .collect();
+    rng.shuffle(&mut options);
+    let mut picks = Vec::new();
+    for language in options {
+        picks.push(language);
+        if !bonus.allows(base, class, &picks) {
+            picks.pop();
+        }
+    }
+    picks
+}
+
+// The first legal alignment in a random order, with a deity and domains for
+// the classes that worship one. When nothing is legal, the one with the
+// fewest issues.
+fn random_alignment(
+    rng: &mut GameRng,
+    class: &PlayableClass,
+    deities: &[&DeityAsset],
+) -> (Alignment, Option<String>, Vec<Domain>) {
+    let mut alignments = Alignment::as_array().to_vec();
+    rng.shuffle(&mut alignments);
+    let mut deity_options: Vec<Option<&DeityAsset>> = if worships_deity(class) {
+        let mut deities = deities.to_vec();
+        deities.sort_by(|a, b| a.name.cmp(&b.name));
+        deities.into_iter().map(Some).collect()
+    } else {
+        vec![None]
+    };
+    rng.shuffle(&mut deity_options);
+    let mut best: Option<(usize, Alignment, Option<&DeityAsset>, Vec<Domain>)> = None;
+    'search: for alignment in alignments {
+        for deity in deity_options.iter().copied() {
+            let mut domains: Vec<Domain> = deity.map_or(Vec::new(), |deity| {
+                deity
+                    .domains
+                    .iter()
+                    .copied()
+                    .filter(|domain| domain.allows(&alignment))
+                    .collect()
+            });
+            rng.shuffle(&mut domains);
+            domains.truncate(domain_count(class));
+            let issues = alignment_issues(Some(&alignment), deity, &domains, &[*class]).len();
+            if best.as_ref().is_none_or(|(fewest, ..)| issues < *fewest) {
+                best = Some((issues, alignment, deity, domains));
+            }
+            if issues == 0 {
+                break 'search;
+            }
+        }
+    }
+    best.map_or(
+        (Alignment::default(), None, Vec::new()),
+        |(_, alignment, deity, domains)| {
+            (alignment, deity.map(|deity| deity.name.clone()), domains)
+        },
+    )
+}
+
+// Buys a random affordable item out of the ones `wanted` accepts.
+fn buy_one(
+    rng: &mut GameRng,
+    items: &[&Item],
+    left: &mut u32,
+    purchases: &mut Vec<Item>,
+    wanted: impl Fn(&Item) -> bool,
+) {
+    let affordable: Vec<&Item> = items
+        .iter()
+        .copied()
+        .filter(|item| item.cost <= *left && wanted(item))
+        .collect();
+    if let Some(item) = rng.choose(&affordable) {
+        *left -= item.cost;
+        purchases.push((*item).clone());
+    }
+}
+
+// A weapon and armor the character is proficient with, half the time a
+// shield, then a few pieces of gear with what's left.
+fn random_purchases(
+    rng: &mut GameRng,
+    wealth: Wealth,
+    proficiencies: &Proficiencies,
+    items: &[&Item],
+) -> Vec<Item> {
+    let mut items = items.to_vec();
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut left = wealth.0;
+    let mut purchases = Vec::new();
+    let usable = |item: &Item| proficiencies.with_item(item);
+    buy_one(rng, &items, &mut left, &mut purchases, |item| {
+        matches!(item.kind, ItemKind::Weapon(_)) && usable(item)
+    });
+    buy_one(rng, &items, &mut left, &mut purchases, |item| {
+        matches!(item.kind, ItemKind::Armor(piece) if !piece.is_shield()) && usable(item)
+    });
+    if rng.below(2) == 0 {
+        buy_one(rng, &items, &mut left, &mut purchases, |item| {
+            matches!(item.kind, ItemKind::Armor(piece) if piece.is_shield()) && usable(item)
+        });
+    }
+    for _ in 0..3 {
+        buy_one(rng, &items, &mut left, &mut purchases, |item| {
+            item.kind == ItemKind::Gear
+        });
+    }
+    purchases
+}
+
+// A legal first level character made from `seed`. The same seed, locks,
+// method and catalogs always make the same character.
+pub fn random_character(
+    seed: u64,
+    locks: &RandomLocks,
+    method: ScoreMethod,
+    catalogs: &RandomCatalogs,
+) -> RandomCharacter {
+    let mut rng = GameRng::from_seed(seed);
+    let race = locks.race.unwrap_or_else(|| random_race(&mut rng));
+    let racial_traits = random_racial_traits(&mut rng, &race, catalogs.alternate_traits);
+    let class = locks
+        .class
+        .unwrap_or_else(|| random_class(&mut rng, catalogs.class_map));
+    let archetype = random_archetype(
+        &mut rng,
+        locks.archetype,
+        &race,
+        &class,
+        catalogs.archetypes,
+    );
+    let archetypes: Vec<&ArchetypeInfo> = catalogs
+        .archetypes
+        .iter()
+        .copied()
+        .filter(|info| Some(info.name) == archetype)
+        .collect();
+
+    let priority = ability_priority(&mut rng, key_ability(&class));
+    let (scores, point_buy) = match method {
+        ScoreMethod::Roll => (rolled_scores(&mut rng, &priority), None),
+        ScoreMethod::PointBuy(budget) => (
+            bought_scores(&mut rng, budget, &priority),
+            Some(PointBuy(budget)),
+        ),
+    };
+    let floating_picks = random_floating_picks(&mut rng, &racial_traits, &priority);
+    let favored = random_favored_classes(&mut rng, &racial_traits, &class);
+
+    let class_levels = ClassLevels::from(HashMap::from([(class, 1)]));
+    let ability_bonuses = AbilityScoreBonuses::from(
+        racial_traits
+            .iter()
+            .filter_map(|name| AbilityScoreBonus::from_name(name).ok())
+            .flatten()
+            .collect::<Vec<_>>(),
+    );
+    // Like `build_race`, the last trait giving a component wins.
+    let bonus_skills = racial_traits
+        .iter()
+        .rev()
+        .find_map(|name| BonusSkillPerLevel::from_name(name).ok());
+    let record = CharacterRecord {
+        race,
+        racial_traits: &racial_traits,
+        class_levels: &class_levels,
+        archetypes: &archetypes,
+        scores: Some(&scores),
+        ability_bonuses: Some(&ability_bonuses),
+        point_buy: point_buy.as_ref(),
+        skill_ranks: None,
+        bonus_skills: bonus_skills.as_ref(),
+        floating_abilities: None,
+        floating_skills: None,
+        floating_feats: None,
+        floating_picks: None,
+        feats: None,
+        base_languages: None,
+        known_languages: None,
+        alignment: None,
+        deity: None,
+        domains: &[],
+        favored: Some(&favored),
+        traits: &[],
+    };
+    let budget = skill_rank_budget(&record, catalogs.class_map);
+    // An archetype's skill list replaces its class's.
+    let class_skills = archetypes
+        .iter()
+        .find_map(|archetype| archetype.skills.clone())
+        .or_else(|| {
+            catalogs
+                .class_map
+                .inner_ref()
+                .get(&class)
+                .map(|info| info.class_skills.clone())
+        })
+        .unwrap_or_default();
+    let skill_ranks = random_skill_ranks(&mut rng, budget, 1, &class_skills);
+
+    let racial_weapons = racial_traits
+        .iter()
+        .rev()
+        .find_map(|name| CharacterWeaponProficiency::from_name(name).ok());
+    let feats_needed = feat_slots(
+        &class_levels,
+        racial_traits
+            .iter()
+            .filter_map(|name| FloatingBonusFeat::from_name(name).ok())
+            .map(|feat| feat.number)
+            .sum(),
+    );
+    let feats = random_feats(
+        &mut rng,
+        feats_needed,
+        &class,
+        &archetypes,
+        racial_weapons.as_ref(),
+        class_levels.base_attack(catalogs.class_map).base,
+    );
+    let base_languages = racial_traits
+        .iter()
+        .rev()
+        .find_map(|name| BaseLanguages::from_name(name).ok());
+    let languages = random_languages(
+        &mut rng,
+        base_languages.as_ref(),
+        &class,
+        &scores,
+        &skill_ranks,
+    );
+    let (alignment, deity, domains) = random_alignment(&mut rng, &class, catalogs.deities);
+
+    let starting_wealth = starting_wealth(&class).roll(&mut rng);
+    let proficiencies =
+        Proficiencies::new(&[class], &archetypes, racial_weapons.as_ref(), Some(&feats));
+    let purchases = random_purchases(&mut rng, starting_wealth, &proficiencies, catalogs.items);
+    RandomCharacter {
+        seed,
+        race,
+        racial_traits,
+        class,
+        archetype,
+        scores,
+        point_buy,
+        floating_picks,
+        skill_ranks,
+        feats,
+        languages,
+        alignment,
+        deity,
+        domains,
+        favored,
+        starting_wealth,
+        purchases,
+    }
+}
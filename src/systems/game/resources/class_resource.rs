@@ -42,7 +42,6 @@ pub fn setup_classes(mut commands: Commands, class_asset: Res<Assets<ClassAsset>
         ],
         skill_ranks_per_level: 2,
         hit_die: Dice::D10,
-        alignment_restriction: Option::None,
         bab_progression: Full,
         save_progression: [Fort, SavingThrowName::None, SavingThrowName::None],
         class_features_list: vec![
@@ -0,0 +1,187 @@
+use crate::systems::{
+    game::{
+        character::{AbilityScores, SavingThrowBonuses, SavingThrowName},
+        class::BaseSaves,
+        dice::GameRng,
+        magic::{SavingThrow, SpellCauses, SpellDescriptor, SpellSchool},
+    },
+    grid_systems::{
+        area_of_effect::Intersection,
+        grid_position::{Footprint, GridPos},
+        line_of_sight::cover_from_point,
+        pathfinding::TerrainMap,
+    },
+};
+use bevy::prelude::*;
+
+// What a saving throw is made against, used to pick out the bonuses that only
+// apply against some effects, like Bravery against fear or the gnome's
+// illusion resistance.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SaveEffect {
+    // The school of the spell, or `None` when the effect isn't a spell.
+    pub school: Option<SpellSchool>,
+    pub descriptors: Vec<SpellDescriptor>,
+    pub causes: Vec<SpellCauses>,
+    pub poison: bool,
+}
+
+// How much of an effect still applies after the saving throw.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EffectOutcome {
+    #[default]
+    Full,
+    Half,
+    // The lesser effect described by the spell.
+    Partial,
+    None,
+}
+
+impl EffectOutcome {
+    // Damage after the outcome. Half damage rounds down, and partial effects
+    // describe their own damage, so they're left as they are.
+    pub fn damage(&self, damage: i32) -> i32 {
+        match self {
+            EffectOutcome::Full | EffectOutcome::Partial => damage,
+            EffectOutcome::Half => damage / 2,
+            EffectOutcome::None => 0,
+        }
+    }
+}
+
+impl SavingThrow {
+    // Harmless effects apply in full, since the target is assumed to be
+    // willing and forgo the save.
+    pub fn outcome(&self, success: bool) -> EffectOutcome {
+        if !success {
+            return EffectOutcome::Full;
+        }
+        match self {
+            SavingThrow::Negates | SavingThrow::Disbelief | SavingThrow::Object => {
+                EffectOutcome::None
+            }
+            SavingThrow::Half => EffectOutcome::Half,
+            SavingThrow::Partial => EffectOutcome::Partial,
+            SavingThrow::None | SavingThrow::Harmless => EffectOutcome::Full,
+        }
+    }
+}
+
+// Save = base save + ability modifier + bonuses that apply against the effect.
+pub fn save_bonus(
+    saving_throw: &SavingThrowName,
+    base_saves: &BaseSaves,
+    scores: &AbilityScores,
+    bonuses: Option<&SavingThrowBonuses>,
+    effect: &SaveEffect,
+) -> i32 {
+    base_saves.get(saving_throw) as i32
+        + saving_throw
+            .ability()
+            .map_or(0, |ability| scores.modifier(&ability))
+        + bonuses.map_or(0, |bonuses| bonuses.total_vs(saving_throw, effect))
+}
+
+// Events
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct SavingThrowEvent {
+    // Whatever is causing the save, so the result can be matched up with it.
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub saving_throw: SavingThrowName,
+    pub kind: SavingThrow,
+    pub dc: i32,
+    pub effect: SaveEffect,
+    // Situational modifiers to the save.
+    pub bonus: i32,
+    // Where an area effect spreads from. Cover between it and the target
+    // adds to Reflex saves.
+    pub origin: Option<Intersection>,
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SavingThrowResultEvent {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub saving_throw: SavingThrowName,
+    pub roll: i32,
+    pub total: i32,
+    pub dc: i32,
+    pub success: bool,
+    pub outcome: EffectOutcome,
+}
+
+// Systems
+type SaveStatsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static BaseSaves>,
+        Option<&'static AbilityScores>,
+        Option<&'static SavingThrowBonuses>,
+        Option<&'static Name>,
+    ),
+>;
+
+// Rolls each requested save against its DC. A natural 20 always succeeds and
+// a natural 1 always fails. Creatures missing stat components use base saves
+// of 0 and scores of 10.
+pub fn resolve_saving_throws(
+    mut event_reader: EventReader<SavingThrowEvent>,
+    mut event_writer: EventWriter<SavingThrowResultEvent>,
+    query_stats: SaveStatsQuery,
+    query_position: Query<(&GridPos, &Footprint)>,
+    terrain: Res<TerrainMap>,
+    mut rng: ResMut<GameRng>,
+) {
+    let default_scores = AbilityScores::new();
+    for event in event_reader.iter() {
+        let Ok((base_saves, scores, bonuses, name)) = query_stats.get(event.target) else {
+            continue;
+        };
+        let cover = match (event.saving_throw, event.origin) {
+            (SavingThrowName::Reflex, Some(origin)) => {
+                query_position
+                    .get(event.target)
+                    .map_or(0, |(pos, footprint)| {
+                        cover_from_point(origin.point(), pos, footprint, &terrain).reflex_bonus()
+                    })
+            }
+            _ => 0,
+        };
+        let roll = rng.d20();
+        let total = roll
+            + event.bonus
+            + cover
+            + save_bonus(
+                &event.saving_throw,
+                base_saves.unwrap_or(&BaseSaves::default()),
+                scores.unwrap_or(&default_scores),
+                bonuses,
+                &event.effect,
+            );
+        let success = match roll {
+            20 => true,
+            1 => false,
+            _ => total >= event.dc,
+        };
+        let outcome = event.kind.outcome(success);
+        info!(
+            "{} {} save: rolled {roll}, total {total} vs DC {}, {} ({outcome:?})",
+            name.map_or("Unnamed", |name| name.as_str()),
+            event.saving_throw,
+            event.dc,
+            if success { "succeeded" } else { "failed" },
+        );
+        event_writer.send(SavingThrowResultEvent {
+            source: event.source,
+            target: event.target,
+            saving_throw: event.saving_throw,
+            roll,
+            total,
+            dc: event.dc,
+            success,
+            outcome,
+        });
+    }
+}
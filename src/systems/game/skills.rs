@@ -1,5 +1,7 @@
+use crate::systems::game::character::AbilityScore;
 use bevy::prelude::*;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
@@ -54,13 +56,25 @@ pub enum SkillName {
     UseMagicDevice,
 }
 
-#[derive(Component, Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq)]
+// Ranks put into each skill, with skills not listed at 0 ranks.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SkillRanks(pub HashMap<SkillName, usize>);
+
+impl SkillRanks {
+    pub fn get(&self, skill: &SkillName) -> usize {
+        self.0.get(skill).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Component, Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Deserialize)]
 pub enum SkillUse {
     IntimidateUse(IntimidateUse),
+    // Any skill check made as part of an occult ritual.
+    OccultRitual,
     /* more here */
 }
 
-#[derive(Component, Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq)]
+#[derive(Component, Clone, Debug, PartialEq, PartialOrd, Hash, Copy, Eq, Deserialize)]
 pub enum IntimidateUse {
     Demoralize,
     /* more here */
@@ -110,6 +124,35 @@ impl SkillName {
         .copied()
     }
 
+    // The ability whose modifier is added to checks with the skill.
+    pub fn ability(&self) -> AbilityScore {
+        use SkillName::*;
+        match self {
+            Climb | Swim => AbilityScore::Strength,
+            Acrobatics | DisableDevice | Escape | Fly | Ride | SleightOfHand | Stealth => {
+                AbilityScore::Dexterity
+            }
+            Appraise
+            | Craft
+            | KnowledgeArcana
+            | KnowledgeDungeoneering
+            | KnowledgeEngineering
+            | KnowledgeGeography
+            | KnowledgeHistory
+            | KnowledgeLocal
+            | KnowledgeNature
+            | KnowledgeNobility
+            | KnowledgePlanes
+            | KnowledgeReligion
+            | Linguistics
+            | Spellcraft => AbilityScore::Intelligence,
+            Heal | Perception | Profession | SenseMotive | Survival => AbilityScore::Wisdom,
+            Bluff | Diplomacy | Disguise | HandleAnimal | Intimidate | Perform | UseMagicDevice => {
+                AbilityScore::Charisma
+            }
+        }
+    }
+
     pub fn knowledge_array() -> [SkillName; 10] {
         use SkillName::*;
         [
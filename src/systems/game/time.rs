@@ -0,0 +1,372 @@
+use crate::systems::{
+    game::{
+        attack_of_opportunity::AttacksOfOpportunity,
+        character::{GroundSpeed, SpellLikeAbilities},
+        conditions::Conditions,
+        experience::Defeated,
+        magic::{CastFrequency, SpellDuration, SpellLikeAbility, SpellName},
+    },
+    grid_systems::token::{Faction, MovementRemaining, Token},
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+pub const ROUNDS_PER_MINUTE: u64 = 10;
+pub const MINUTES_PER_HOUR: u64 = 60;
+pub const HOURS_PER_DAY: u64 = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    Round,
+    Minute,
+    Hour,
+    Day,
+}
+
+impl TimeUnit {
+    pub fn rounds(&self) -> u64 {
+        match self {
+            TimeUnit::Round => 1,
+            TimeUnit::Minute => ROUNDS_PER_MINUTE,
+            TimeUnit::Hour => ROUNDS_PER_MINUTE * MINUTES_PER_HOUR,
+            TimeUnit::Day => ROUNDS_PER_MINUTE * MINUTES_PER_HOUR * HOURS_PER_DAY,
+        }
+    }
+}
+
+// In game time, counted in 6 second rounds since the start of the first day.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GameClock {
+    pub rounds: u64,
+}
+
+impl GameClock {
+    pub fn round_of_minute(&self) -> u64 {
+        self.rounds % ROUNDS_PER_MINUTE
+    }
+    pub fn minute_of_hour(&self) -> u64 {
+        self.rounds / TimeUnit::Minute.rounds() % MINUTES_PER_HOUR
+    }
+    pub fn hour_of_day(&self) -> u64 {
+        self.rounds / TimeUnit::Hour.rounds() % HOURS_PER_DAY
+    }
+    pub fn day(&self) -> u64 {
+        self.rounds / TimeUnit::Day.rounds()
+    }
+}
+
+impl fmt::Display for GameClock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {}, {:02}:{:02}, round {}",
+            self.day() + 1,
+            self.hour_of_day(),
+            self.minute_of_hour(),
+            self.round_of_minute() + 1
+        )
+    }
+}
+
+// How long an effect lasts once it starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EffectDuration {
+    Rounds(u64),
+    // Lasts while the caster concentrates, and ends when they stop.
+    Concentration,
+    // Lasts until used, like a held touch spell.
+    UntilDischarged,
+    Permanent,
+}
+
+impl EffectDuration {
+    pub fn of(amount: u64, unit: TimeUnit) -> Self {
+        Self::Rounds(amount * unit.rounds())
+    }
+    // Durations like 1 minute/level.
+    pub fn per_level(amount: u64, unit: TimeUnit, caster_level: usize) -> Self {
+        Self::of(amount * caster_level.max(1) as u64, unit)
+    }
+    // The duration of a spell, where `timed` is used for spells with a timed
+    // duration. Instantaneous spells have no lasting effect to track.
+    pub fn from_spell(duration: &SpellDuration, timed: EffectDuration) -> Option<Self> {
+        match duration {
+            SpellDuration::Timed => Some(timed),
+            SpellDuration::Instant => None,
+            SpellDuration::Permanent => Some(Self::Permanent),
+            SpellDuration::Concentration => Some(Self::Concentration),
+            SpellDuration::ChargeTouch | SpellDuration::Discharge => Some(Self::UntilDischarged),
+        }
+    }
+}
+
+// Attached next to an effect component `T` to remove it once its duration is
+// over. Each timed component type needs registering with
+// `add_timed_effect::<T>()`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timed<T: Component> {
+    pub duration: EffectDuration,
+    // The clock round the effect started on.
+    pub started: u64,
+    effect: PhantomData<T>,
+}
+
+impl<T: Component> Timed<T> {
+    pub fn new(duration: EffectDuration, clock: &GameClock) -> Self {
+        Self {
+            duration,
+            started: clock.rounds,
+            effect: PhantomData,
+        }
+    }
+    pub fn expires_at(&self) -> Option<u64> {
+        match self.duration {
+            EffectDuration::Rounds(rounds) => Some(self.started + rounds),
+            _ => None,
+        }
+    }
+}
+
+// Uses of per day spell-like abilities since the last rest.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DailyUses(pub HashMap<SpellName, u32>);
+
+impl DailyUses {
+    pub fn can_use(&self, ability: &SpellLikeAbility) -> bool {
+        match (ability.cast_frequency, ability.uses) {
+            (CastFrequency::PerDay, Some(uses)) => {
+                self.0.get(&ability.spell_name).copied().unwrap_or(0) < uses
+            }
+            _ => true,
+        }
+    }
+    pub fn spend(&mut self, ability: &SpellLikeAbility) {
+        if ability.cast_frequency == CastFrequency::PerDay {
+            *self.0.entry(ability.spell_name).or_default() += 1;
+        }
+    }
+    pub fn reset(&mut self) {
+        self.0.clear();
+    }
+}
+
+// Events
+// Moves the clock forward. Sent once a round in combat, and for longer spans
+// like resting outside of it.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdvanceTimeEvent {
+    pub rounds: u64,
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeAdvancedEvent {
+    pub from: u64,
+    pub to: u64,
+}
+
+impl TimeAdvancedEvent {
+    pub fn rounds(&self) -> u64 {
+        self.to - self.from
+    }
+    pub fn new_day(&self) -> bool {
+        self.to / TimeUnit::Day.rounds() > self.from / TimeUnit::Day.rounds()
+    }
+}
+
+// Ends concentration or discharge effects on an entity, since those don't
+// run out with time.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EndEffectEvent {
+    pub entity: Entity,
+    pub duration: EffectDuration,
+}
+
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct EffectExpiredEvent {
+    pub entity: Entity,
+    pub effect: String,
+}
+
+// Asks to use a spell-like ability, which is refused once a per day ability
+// has no uses left today.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UseSpellLikeAbilityEvent {
+    pub caster: Entity,
+    pub ability: SpellLikeAbility,
+}
+
+// Sent for each use that went ahead, for the spell's own system to pick up.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpellLikeAbilityUsedEvent {
+    pub caster: Entity,
+    pub ability: SpellLikeAbility,
+}
+
+// Systems
+pub fn advance_clock(
+    mut event_reader: EventReader<AdvanceTimeEvent>,
+    mut event_writer: EventWriter<TimeAdvancedEvent>,
+    mut clock: ResMut<GameClock>,
+) {
+    for event in event_reader.iter().filter(|event| event.rounds > 0) {
+        let from = clock.rounds;
+        clock.rounds += event.rounds;
+        event_writer.send(TimeAdvancedEvent {
+            from,
+            to: clock.rounds,
+        });
+    }
+}
+
+type PartyMovementQuery<'w, 's> =
+    Query<'w, 's, (&'static Faction, &'static MovementRemaining), (With<Token>, Without<Defeated>)>;
+
+// The round ends once every party token still standing has used up its
+// movement, or early by pressing enter during a battle.
+pub fn end_round(
+    key_input: Res<Input<KeyCode>>,
+    query_party: PartyMovementQuery,
+    mut event_writer: EventWriter<AdvanceTimeEvent>,
+) {
+    let mut party = query_party
+        .iter()
+        .filter(|(faction, _)| **faction == Faction::Party)
+        .peekable();
+    let party_done = party.peek().is_some() && party.all(|(_, movement)| movement.feet == 0);
+    if party_done || key_input.just_pressed(KeyCode::Return) {
+        event_writer.send(AdvanceTimeEvent { rounds: 1 });
+    }
+}
+
+// A new round gives back movement and attacks of opportunity.
+pub fn reset_round_resources(
+    mut event_reader: EventReader<TimeAdvancedEvent>,
+    mut query_movement: Query<(&GroundSpeed, &mut MovementRemaining)>,
+    mut query_aoo: Query<&mut AttacksOfOpportunity>,
+) {
+    if event_reader.iter().count() == 0 {
+        return;
+    }
+    for (speed, mut movement) in query_movement.iter_mut() {
+        movement.reset(speed);
+    }
+    for mut aoo in query_aoo.iter_mut() {
+        aoo.reset();
+    }
+}
+
+pub fn tick_conditions(
+    mut event_reader: EventReader<TimeAdvancedEvent>,
+    mut event_writer: EventWriter<EffectExpiredEvent>,
+    mut query_conditions: Query<(Entity, &mut Conditions)>,
+) {
+    let rounds: u64 = event_reader.iter().map(|event| event.rounds()).sum();
+    if rounds == 0 {
+        return;
+    }
+    for (entity, mut conditions) in query_conditions.iter_mut() {
+        for condition in conditions.tick_rounds(rounds as usize) {
+            event_writer.send(EffectExpiredEvent {
+                entity,
+                effect: condition.to_string(),
+            });
+        }
+    }
+}
+
+// Per day abilities come back at the start of each day.
+pub fn reset_daily_uses(
+    mut event_reader: EventReader<TimeAdvancedEvent>,
+    mut query_uses: Query<&mut DailyUses>,
+    query_new: Query<Entity, (With<SpellLikeAbilities>, Without<DailyUses>)>,
+    mut commands: Commands,
+) {
+    for entity in query_new.iter() {
+        commands.entity(entity).insert(DailyUses::default());
+    }
+    if !event_reader.iter().any(|event| event.new_day()) {
+        return;
+    }
+    for mut uses in query_uses.iter_mut() {
+        uses.reset();
+    }
+}
+
+// Spends a daily use for each spell-like ability used, refusing per day
+// abilities that are used up. Creatures that haven't been given `DailyUses`
+// yet start with every use available.
+pub fn use_spell_like_abilities(
+    mut event_reader: EventReader<UseSpellLikeAbilityEvent>,
+    mut event_writer: EventWriter<SpellLikeAbilityUsedEvent>,
+    mut query_uses: Query<Option<&mut DailyUses>>,
+    mut commands: Commands,
+) {
+    let mut fresh: HashMap<Entity, DailyUses> = HashMap::new();
+    for event in event_reader.iter() {
+        let Ok(uses) = query_uses.get_mut(event.caster) else {
+            continue;
+        };
+        let uses = match uses {
+            Some(uses) => uses.into_inner(),
+            None => fresh.entry(event.caster).or_default(),
+        };
+        if !uses.can_use(&event.ability) {
+            info!(
+                "{:?} has no uses of {:?} left today",
+                event.caster, event.ability.spell_name
+            );
+            continue;
+        }
+        uses.spend(&event.ability);
+        event_writer.send(SpellLikeAbilityUsedEvent {
+            caster: event.caster,
+            ability: event.ability,
+        });
+    }
+    for (entity, uses) in fresh {
+        commands.entity(entity).insert(uses);
+    }
+}
+
+// Removes `T` and its `Timed<T>` once the duration is over.
+pub fn expire_timed_effects<T: Component>(
+    clock: Res<GameClock>,
+    mut event_reader: EventReader<EndEffectEvent>,
+    mut event_writer: EventWriter<EffectExpiredEvent>,
+    query_timed: Query<(Entity, &Timed<T>)>,
+    mut commands: Commands,
+) {
+    let ended: Vec<EndEffectEvent> = event_reader.iter().copied().collect();
+    for (entity, timed) in query_timed.iter() {
+        let expired = timed
+            .expires_at()
+            .is_some_and(|expires_at| clock.rounds >= expires_at)
+            || ended
+                .iter()
+                .any(|end| end.entity == entity && end.duration == timed.duration);
+        if expired {
+            commands.entity(entity).remove::<(T, Timed<T>)>();
+            event_writer.send(EffectExpiredEvent {
+                entity,
+                effect: std::any::type_name::<T>()
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+    }
+}
+
+// Registers a component type as something that can be given a duration.
+pub trait TimedEffectAppExt {
+    fn add_timed_effect<T: Component>(&mut self) -> &mut Self;
+}
+
+impl TimedEffectAppExt for App {
+    fn add_timed_effect<T: Component>(&mut self) -> &mut Self {
+        self.add_systems(Update, expire_timed_effects::<T>.after(advance_clock))
+    }
+}
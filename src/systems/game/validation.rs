@@ -0,0 +1,686 @@
+use crate::{
+    systems::game::{
+        archetype::{ArchetypeInfo, ArchetypeName, Restriction},
+        character::{
+            point_buy_cost, stack_bonuses, AbilityScore, AbilityScoreBonuses, AbilityScores,
+            Alignment, BaseLanguages, BonusSkillPerLevel, ClassLevels, FloatingAbilityBonuses,
+            FloatingBonusFeats, FloatingBonusPicks, FloatingSkillBonuses, Language, LimitationEnum,
+            PlayableRace, PointBuy,
+        },
+        character_traits::{trait_issues, CharacterTrait, TraitIssue},
+        class::{ClassFeature, ClassMap, FavoredClass, PlayableClass},
+        deity::{alignment_issues, AlignmentIssue, Domain},
+        equipment::ArmorCategory,
+        favored_class::{
+            favored_class_count, favored_class_issues, FavoredClassIssue, FavoredClasses,
+        },
+        languages::{class_languages, BonusLanguages, KnownLanguages},
+        proficiency::{feat_slots, Proficiencies, ProficiencyFeat, ProficiencyFeats},
+        race::RacialTraitName,
+        skills::{SkillName, SkillRanks},
+    },
+    technical::{alternate_traits::AltTraitDisplay, deity::DeityAsset},
+};
+use std::{collections::BTreeMap, fmt};
+
+// How much an issue matters. An unfinished character still has choices to
+// make, an illegal one breaks the rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Unfinished,
+    Illegal,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unfinished => write!(f, "Unfinished"),
+            Self::Illegal => write!(f, "Illegal"),
+        }
+    }
+}
+
+// The stage of character creation where an issue is fixed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CreationStage {
+    Race,
+    AbilityScores,
+    Class,
+    Archetype,
+    Skills,
+    Feats,
+    BonusFeats,
+    Languages,
+    Alignment,
+    Traits,
+    FavoredClass,
+}
+
+impl fmt::Display for CreationStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Race => write!(f, "Race"),
+            Self::AbilityScores => write!(f, "Ability Scores"),
+            Self::Class => write!(f, "Class"),
+            Self::Archetype => write!(f, "Archetype"),
+            Self::Skills => write!(f, "Skills"),
+            Self::Feats => write!(f, "Feats"),
+            Self::BonusFeats => write!(f, "Bonus Feats"),
+            Self::Languages => write!(f, "Languages"),
+            Self::Alignment => write!(f, "Alignment"),
+            Self::Traits => write!(f, "Traits"),
+            Self::FavoredClass => write!(f, "Favored Class"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ValidationIssue {
+    NoRacialTraits,
+    NoClass,
+    SkillRanks {
+        allowed: usize,
+        spent: usize,
+    },
+    // More ranks in one skill than the character has levels.
+    SkillRanksOverLevel {
+        skill: SkillName,
+        ranks: usize,
+        level: usize,
+    },
+    FloatingAbilities {
+        needed: usize,
+        chosen: usize,
+    },
+    // An ability picked for a floating bonus that doesn't offer it.
+    FloatingAbilityChoice(AbilityScore),
+    FloatingSkills {
+        needed: usize,
+        chosen: usize,
+    },
+    FloatingSkillChoice(SkillName),
+    // Bonus feat slots, filled by the feats taken.
+    BonusFeats {
+        needed: usize,
+        chosen: usize,
+    },
+    DuplicateFeat(ProficiencyFeat),
+    FeatPrerequisite {
+        feat: ProficiencyFeat,
+        missing: String,
+    },
+    // An alternate racial trait taken alongside a trait it replaces, named by
+    // their titles.
+    AlternateTraitConflict {
+        alternate: String,
+        replaced: String,
+    },
+    // A trait replaced by more than one alternate trait.
+    ReplacedTwice(String),
+    ArchetypeClass(ArchetypeName, PlayableClass),
+    ArchetypeRace(ArchetypeName),
+    ArchetypeConflict {
+        first: ArchetypeName,
+        second: ArchetypeName,
+        feature: ClassFeature,
+    },
+    ScoreOutOfRange {
+        ability: AbilityScore,
+        score: i32,
+    },
+    PointBuy {
+        budget: i32,
+        spent: i32,
+    },
+    BonusLanguages {
+        allowed: usize,
+        chosen: usize,
+    },
+    Alignment(AlignmentIssue),
+    FavoredClass(FavoredClassIssue),
+    Trait(TraitIssue),
+}
+
+impl ValidationIssue {
+    pub fn severity(&self) -> Severity {
+        use Severity::*;
+        match self {
+            Self::SkillRanks { allowed, spent }
+            | Self::FloatingAbilities {
+                needed: allowed,
+                chosen: spent,
+            }
+            | Self::FloatingSkills {
+                needed: allowed,
+                chosen: spent,
+            }
+            | Self::BonusFeats {
+                needed: allowed,
+                chosen: spent,
+            }
+            | Self::BonusLanguages {
+                allowed,
+                chosen: spent,
+            } if spent < allowed => Unfinished,
+            Self::NoRacialTraits | Self::NoClass => Unfinished,
+            Self::PointBuy { budget, spent } if spent < budget => Unfinished,
+            Self::Alignment(
+                AlignmentIssue::NoAlignment
+                | AlignmentIssue::NoDeity(_)
+                | AlignmentIssue::DomainCount { .. },
+            ) => Unfinished,
+            Self::FavoredClass(
+                FavoredClassIssue::Count { allowed, chosen }
+                | FavoredClassIssue::Maneuvers {
+                    needed: allowed,
+                    chosen,
+                },
+            ) if chosen < allowed => Unfinished,
+            _ => Illegal,
+        }
+    }
+    pub fn stage(&self) -> CreationStage {
+        use CreationStage::*;
+        match self {
+            Self::NoRacialTraits => Race,
+            Self::NoClass => Class,
+            Self::SkillRanks { .. }
+            | Self::SkillRanksOverLevel { .. }
+            | Self::FloatingSkills { .. }
+            | Self::FloatingSkillChoice(_) => Skills,
+            Self::FloatingAbilities { .. }
+            | Self::FloatingAbilityChoice(_)
+            | Self::ScoreOutOfRange { .. }
+            | Self::PointBuy { .. } => AbilityScores,
+            Self::BonusFeats { .. } => BonusFeats,
+            Self::DuplicateFeat(_) | Self::FeatPrerequisite { .. } => Feats,
+            Self::AlternateTraitConflict { .. } | Self::ReplacedTwice(_) => Race,
+            Self::ArchetypeClass(..) | Self::ArchetypeRace(_) | Self::ArchetypeConflict { .. } => {
+                Archetype
+            }
+            Self::BonusLanguages { .. } => Languages,
+            Self::Alignment(_) => Alignment,
+            Self::FavoredClass(_) => FavoredClass,
+            Self::Trait(_) => Traits,
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoRacialTraits => write!(f, "No racial traits taken"),
+            Self::NoClass => write!(f, "No class picked"),
+            Self::SkillRanks { allowed, spent } => {
+                write!(f, "{spent} of {allowed} skill ranks spent")
+            }
+            Self::SkillRanksOverLevel {
+                skill,
+                ranks,
+                level,
+            } => write!(f, "{ranks} ranks in {skill}, only {level} allowed"),
+            Self::FloatingAbilities { needed, chosen } => {
+                write!(f, "{chosen} of {needed} ability bonuses assigned")
+            }
+            Self::FloatingAbilityChoice(ability) => {
+                write!(f, "No ability bonus can go to {ability}")
+            }
+            Self::FloatingSkills { needed, chosen } => {
+                write!(f, "{chosen} of {needed} skill bonuses assigned")
+            }
+            Self::FloatingSkillChoice(skill) => write!(f, "No skill bonus can go to {skill}"),
+            Self::BonusFeats { needed, chosen } => {
+                write!(f, "{chosen} of {needed} feats chosen")
+            }
+            Self::DuplicateFeat(feat) => write!(f, "{feat} taken more than once"),
+            Self::FeatPrerequisite { feat, missing } => write!(f, "{feat} requires {missing}"),
+            Self::AlternateTraitConflict {
+                alternate,
+                replaced,
+            } => write!(f, "{alternate} replaces {replaced}, which is still taken"),
+            Self::ReplacedTwice(replaced) => {
+                write!(f, "{replaced} is replaced by more than one alternate trait")
+            }
+            Self::ArchetypeClass(archetype, class) => {
+                write!(f, "{archetype} needs levels in {class}")
+            }
+            Self::ArchetypeRace(archetype) => write!(f, "{archetype} isn't open to this race"),
+            Self::ArchetypeConflict {
+                first,
+                second,
+                feature,
+            } => write!(f, "{first} and {second} both replace {feature:?}"),
+            Self::ScoreOutOfRange { ability, score } => {
+                write!(f, "{ability} {score} can't be bought with points")
+            }
+            Self::PointBuy { budget, spent } => {
+                write!(f, "{spent} of {budget} ability score points spent")
+            }
+            Self::BonusLanguages { allowed, chosen } => {
+                write!(f, "{chosen} of {allowed} bonus languages picked")
+            }
+            Self::Alignment(issue) => write!(f, "{issue}"),
+            Self::FavoredClass(issue) => write!(f, "{issue}"),
+            Self::Trait(issue) => write!(f, "{issue}"),
+        }
+    }
+}
+
+// Everything the validator reads about a character. Callers fill it in from
+// the character's components, with the deity and traits it names looked up
+// in the loaded assets.
+pub struct CharacterRecord<'a> {
+    pub race: PlayableRace,
+    pub racial_traits: &'a [RacialTraitName],
+    pub class_levels: &'a ClassLevels,
+    pub archetypes: &'a [&'a ArchetypeInfo],
+    // Base scores, before racial and other bonuses.
+    pub scores: Option<&'a AbilityScores>,
+    pub ability_bonuses: Option<&'a AbilityScoreBonuses>,
+    pub point_buy: Option<&'a PointBuy>,
+    pub skill_ranks: Option<&'a SkillRanks>,
+    pub bonus_skills: Option<&'a BonusSkillPerLevel>,
+    pub floating_abilities: Option<&'a FloatingAbilityBonuses>,
+    pub floating_skills: Option<&'a FloatingSkillBonuses>,
+    pub floating_feats: Option<&'a FloatingBonusFeats>,
+    pub floating_picks: Option<&'a FloatingBonusPicks>,
+    pub feats: Option<&'a ProficiencyFeats>,
+    pub base_languages: Option<&'a BaseLanguages>,
+    pub known_languages: Option<&'a KnownLanguages>,
+    pub alignment: Option<&'a Alignment>,
+    pub deity: Option<&'a DeityAsset>,
+    pub domains: &'a [Domain],
+    pub favored: Option<&'a FavoredClasses>,
+    pub traits: &'a [&'a CharacterTrait],
+}
+
+// The catalogs a character is checked against.
+pub struct Rules<'a> {
+    pub class_map: &'a ClassMap,
+    pub alternate_traits: &'a [&'a AltTraitDisplay],
+    pub favored_options: &'a [&'a FavoredClass],
+}
+
+// Levels by class name, so issues come out in the same order every time.
+fn sorted_levels(levels: &ClassLevels) -> Vec<(PlayableClass, usize)> {
+    let mut sorted: Vec<(PlayableClass, usize)> = levels
+        .inner_ref()
+        .iter()
+        .map(|(class, level)| (*class, *level))
+        .collect();
+    sorted.sort_by_key(|(class, _)| class.to_string());
+    sorted
+}
+
+// The race needs its traits and the character a class before the rest of it
+// means anything.
+fn choice_issues(record: &CharacterRecord) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if record.racial_traits.is_empty() {
+        issues.push(ValidationIssue::NoRacialTraits);
+    }
+    if sorted_levels(record.class_levels)
+        .iter()
+        .all(|(class, level)| *class == PlayableClass::None || *level == 0)
+    {
+        issues.push(ValidationIssue::NoClass);
+    }
+    issues
+}
+
+// A score with the bonuses that always apply added in.
+fn final_score(record: &CharacterRecord, ability: &AbilityScore) -> i32 {
+    let base = record.scores.map_or(10, |scores| scores.get(ability));
+    let mut by_type = BTreeMap::new();
+    for bonus in record
+        .ability_bonuses
+        .and_then(|bonuses| bonuses.0.get(ability))
+        .into_iter()
+        .flatten()
+        .filter(|bonus| bonus.limitation == LimitationEnum::None)
+    {
+        by_type
+            .entry(bonus.bonus_type)
+            .or_insert_with(Vec::new)
+            .push(bonus.bonus);
+    }
+    base + by_type
+        .iter()
+        .map(|(bonus_type, bonuses)| stack_bonuses(bonus_type, bonuses.iter().copied()))
+        .sum::<i32>()
+}
+
+// Skill ranks the character gets. Each class level gives its skill ranks
+// plus the Intelligence modifier, at least one, with an archetype's ranks used
+// in place of its class's.
+pub fn skill_rank_budget(record: &CharacterRecord, class_map: &ClassMap) -> usize {
+    let intelligence = (final_score(record, &AbilityScore::Intelligence) - 10).div_euclid(2);
+    let total_level = record.class_levels.total_level();
+    let mut allowed: usize = sorted_levels(record.class_levels)
+        .iter()
+        .map(|(class, level)| {
+            let per_level = record
+                .archetypes
+                .iter()
+                .filter(|archetype| archetype.class == *class)
+                .find_map(|archetype| archetype.skill_ranks)
+                .or_else(|| {
+                    class_map
+                        .inner_ref()
+                        .get(class)
+                        .map(|info| info.skill_ranks_per_level)
+                })
+                .unwrap_or(0);
+            (per_level as i32 + intelligence).max(1) as usize * level
+        })
+        .sum();
+    allowed += record
+        .bonus_skills
+        .map_or(0, |bonus| bonus.bonus_size * total_level);
+    allowed += record.favored.map_or(0, |favored| favored.skill_ranks());
+    allowed
+}
+
+fn skill_rank_issues(record: &CharacterRecord, rules: &Rules) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let total_level = record.class_levels.total_level();
+    let allowed = skill_rank_budget(record, rules.class_map);
+    let mut ranks: Vec<(SkillName, usize)> = record
+        .skill_ranks
+        .map(|ranks| {
+            ranks
+                .0
+                .iter()
+                .map(|(skill, ranks)| (*skill, *ranks))
+                .collect()
+        })
+        .unwrap_or_default();
+    ranks.sort_by_key(|(skill, _)| skill.to_string());
+    let spent: usize = ranks.iter().map(|(_, ranks)| ranks).sum();
+    if spent != allowed {
+        issues.push(ValidationIssue::SkillRanks { allowed, spent });
+    }
+    for (skill, ranks) in ranks.into_iter().filter(|(_, ranks)| *ranks > total_level) {
+        issues.push(ValidationIssue::SkillRanksOverLevel {
+            skill,
+            ranks,
+            level: total_level,
+        });
+    }
+    issues
+}
+
+fn floating_bonus_issues(record: &CharacterRecord) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let no_picks = FloatingBonusPicks::default();
+    let picks = record.floating_picks.unwrap_or(&no_picks);
+    // One slot per ability to pick, each with the abilities it offers.
+    let ability_slots: Vec<&[AbilityScore]> = record
+        .floating_abilities
+        .iter()
+        .flat_map(|bonuses| bonuses.0.iter())
+        .flat_map(|bonus| std::iter::repeat_n(bonus.choices.as_slice(), bonus.choices_num))
+        .collect();
+    if picks.abilities.len() != ability_slots.len() {
+        issues.push(ValidationIssue::FloatingAbilities {
+            needed: ability_slots.len(),
+            chosen: picks.abilities.len(),
+        });
+    }
+    for (ability, slot) in picks.abilities.iter().zip(ability_slots.iter()) {
+        if !slot.contains(ability) {
+            issues.push(ValidationIssue::FloatingAbilityChoice(*ability));
+        }
+    }
+    let skill_slots: Vec<&[SkillName]> = record
+        .floating_skills
+        .iter()
+        .flat_map(|bonuses| bonuses.0.iter())
+        .map(|bonus| bonus.choices.as_slice())
+        .collect();
+    if picks.skills.len() != skill_slots.len() {
+        issues.push(ValidationIssue::FloatingSkills {
+            needed: skill_slots.len(),
+            chosen: picks.skills.len(),
+        });
+    }
+    for (skill, slot) in picks.skills.iter().zip(skill_slots.iter()) {
+        if !slot.contains(skill) {
+            issues.push(ValidationIssue::FloatingSkillChoice(*skill));
+        }
+    }
+    issues
+}
+
+// The prerequisite a feat is missing, if any.
+pub fn missing_prerequisite(
+    feat: &ProficiencyFeat,
+    proficiencies: &Proficiencies,
+    base_attack: usize,
+) -> Option<String> {
+    match feat {
+        ProficiencyFeat::ExoticWeapon(_) if base_attack < 1 => {
+            Some("base attack bonus +1".to_string())
+        }
+        ProficiencyFeat::MediumArmor if !proficiencies.with_armor(&ArmorCategory::Light) => {
+            Some(ProficiencyFeat::LightArmor.to_string())
+        }
+        ProficiencyFeat::HeavyArmor if !proficiencies.with_armor(&ArmorCategory::Medium) => {
+            Some(ProficiencyFeat::MediumArmor.to_string())
+        }
+        ProficiencyFeat::TowerShield if !proficiencies.with_armor(&ArmorCategory::Shield) => {
+            Some(ProficiencyFeat::Shield.to_string())
+        }
+        _ => None,
+    }
+}
+
+// The only feats modelled so far are the proficiency feats, so those are what
+// fill the bonus feat slots.
+fn feat_issues(record: &CharacterRecord, rules: &Rules) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let feats: &[ProficiencyFeat] = record.feats.map_or(&[], |feats| feats.0.as_slice());
+    let needed = feat_slots(
+        record.class_levels,
+        record
+            .floating_feats
+            .map_or(0, |bonus| bonus.0.iter().map(|feat| feat.number).sum()),
+    );
+    if feats.len() < needed {
+        issues.push(ValidationIssue::BonusFeats {
+            needed,
+            chosen: feats.len(),
+        });
+    }
+    for (index, feat) in feats.iter().enumerate() {
+        if feats[..index].contains(feat) {
+            issues.push(ValidationIssue::DuplicateFeat(*feat));
+        }
+    }
+    let classes: Vec<PlayableClass> = sorted_levels(record.class_levels)
+        .into_iter()
+        .map(|(class, _)| class)
+        .collect();
+    let proficiencies = Proficiencies::new(&classes, record.archetypes, None, record.feats);
+    let base_attack = record.class_levels.base_attack(rules.class_map).base;
+    for feat in feats.iter() {
+        if let Some(missing) = missing_prerequisite(feat, &proficiencies, base_attack) {
+            issues.push(ValidationIssue::FeatPrerequisite {
+                feat: *feat,
+                missing,
+            });
+        }
+    }
+    issues
+}
+
+fn alternate_trait_issues(record: &CharacterRecord, rules: &Rules) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut replaced: Vec<&RacialTraitName> = Vec::new();
+    for alternate in rules
+        .alternate_traits
+        .iter()
+        .filter(|alternate| record.racial_traits.contains(&alternate.my_trait_name))
+    {
+        for (name, title) in alternate
+            .replaces_names
+            .iter()
+            .zip(alternate.replaces_strings.iter())
+        {
+            if record.racial_traits.contains(name) {
+                issues.push(ValidationIssue::AlternateTraitConflict {
+                    alternate: alternate.title.clone(),
+                    replaced: title.clone(),
+                });
+            }
+            if replaced.contains(&name) {
+                issues.push(ValidationIssue::ReplacedTwice(title.clone()));
+            }
+            replaced.push(name);
+        }
+    }
+    issues
+}
+
+fn archetype_issues(record: &CharacterRecord) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (index, archetype) in record.archetypes.iter().enumerate() {
+        if record.class_levels.level(&archetype.class) == 0 {
+            issues.push(ValidationIssue::ArchetypeClass(
+                archetype.name,
+                archetype.class,
+            ));
+        }
+        let races: Vec<&PlayableRace> = archetype
+            .restrictions
+            .iter()
+            .flatten()
+            .filter_map(|restriction| match restriction {
+                Restriction::Race(race) => Some(race),
+                _ => None,
+            })
+            .collect();
+        if !races.is_empty() && !races.contains(&&record.race) {
+            issues.push(ValidationIssue::ArchetypeRace(archetype.name));
+        }
+        let replaces = archetype.replaces_features();
+        for other in record.archetypes[..index]
+            .iter()
+            .filter(|other| other.class == archetype.class)
+        {
+            let other_replaces = other.replaces_features();
+            if let Some(feature) = replaces
+                .iter()
+                .find(|feature| other_replaces.contains(feature))
+            {
+                issues.push(ValidationIssue::ArchetypeConflict {
+                    first: other.name,
+                    second: archetype.name,
+                    feature: *feature,
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn point_buy_issues(record: &CharacterRecord) -> Vec<ValidationIssue> {
+    let Some(PointBuy(budget)) = record.point_buy else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    let mut spent = 0;
+    for ability in AbilityScore::as_array() {
+        let score = record.scores.map_or(10, |scores| scores.get(&ability));
+        match point_buy_cost(score) {
+            Some(cost) => spent += cost,
+            None => issues.push(ValidationIssue::ScoreOutOfRange { ability, score }),
+        }
+    }
+    if spent != *budget {
+        issues.push(ValidationIssue::PointBuy {
+            budget: *budget,
+            spent,
+        });
+    }
+    issues
+}
+
+// Bonus languages are unfinished while another one could still be picked.
+// They're counted against the class with the most levels.
+fn language_issues(record: &CharacterRecord) -> Vec<ValidationIssue> {
+    let (Some(base), Some(known)) = (record.base_languages, record.known_languages) else {
+        return Vec::new();
+    };
+    let levels = sorted_levels(record.class_levels);
+    let Some((class, _)) = levels.iter().max_by_key(|(_, level)| *level) else {
+        return Vec::new();
+    };
+    let from_classes: Vec<_> = levels
+        .iter()
+        .flat_map(|(class, _)| class_languages(class))
+        .collect();
+    let picks: Vec<_> = known
+        .0
+        .iter()
+        .filter(|language| !base.given.contains(language) && !from_classes.contains(language))
+        .copied()
+        .collect();
+    let bonus = BonusLanguages::new(record.scores, record.skill_ranks);
+    let allowed = bonus.total();
+    let can_pick_more = || {
+        Language::array().iter().any(|language| {
+            let mut more = picks.clone();
+            more.push(*language);
+            !picks.contains(language) && bonus.allows(base, class, &more)
+        })
+    };
+    if !bonus.allows(base, class, &picks) || can_pick_more() {
+        vec![ValidationIssue::BonusLanguages {
+            allowed,
+            chosen: picks.len(),
+        }]
+    } else {
+        Vec::new()
+    }
+}
+
+// Everything unfinished or illegal about a character, in stage order. An
+// empty list means the character is finished and legal.
+pub fn validate(record: &CharacterRecord, rules: &Rules) -> Vec<ValidationIssue> {
+    let classes: Vec<PlayableClass> = sorted_levels(record.class_levels)
+        .into_iter()
+        .map(|(class, _)| class)
+        .collect();
+    let no_favored = FavoredClasses::default();
+    let mut issues = choice_issues(record);
+    issues.extend(alternate_trait_issues(record, rules));
+    issues.extend(point_buy_issues(record));
+    issues.extend(floating_bonus_issues(record));
+    issues.extend(archetype_issues(record));
+    issues.extend(skill_rank_issues(record, rules));
+    issues.extend(feat_issues(record, rules));
+    issues.extend(language_issues(record));
+    issues.extend(
+        alignment_issues(record.alignment, record.deity, record.domains, &classes)
+            .into_iter()
+            .map(ValidationIssue::Alignment),
+    );
+    issues.extend(
+        trait_issues(record.traits, &record.race)
+            .into_iter()
+            .map(ValidationIssue::Trait),
+    );
+    issues.extend(
+        favored_class_issues(
+            record.favored.unwrap_or(&no_favored),
+            favored_class_count(record.racial_traits),
+            rules.favored_options,
+            &record.race,
+        )
+        .into_iter()
+        .map(ValidationIssue::FavoredClass),
+    );
+    issues.sort_by_key(|issue| issue.stage());
+    issues
+}
@@ -0,0 +1,68 @@
+use crate::systems::game::{
+    class::{Dice, PlayableClass},
+    dice::GameRng,
+};
+use bevy::prelude::*;
+use std::fmt;
+
+pub const COPPER_PER_SILVER: u32 = 10;
+pub const COPPER_PER_GOLD: u32 = 100;
+
+// The coins a character carries, in copper pieces.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Wealth(pub u32);
+
+impl fmt::Display for Wealth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gold = self.0 / COPPER_PER_GOLD;
+        let silver = self.0 % COPPER_PER_GOLD / COPPER_PER_SILVER;
+        let copper = self.0 % COPPER_PER_SILVER;
+        let mut coins = Vec::new();
+        if gold > 0 || self.0 == 0 {
+            coins.push(format!("{gold} gp"));
+        }
+        if silver > 0 {
+            coins.push(format!("{silver} sp"));
+        }
+        if copper > 0 {
+            coins.push(format!("{copper} cp"));
+        }
+        write!(f, "{}", coins.join(" "))
+    }
+}
+
+// Gold a new character starts with, rolled as `dice`d6 x 10 gp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StartingWealth {
+    pub dice: usize,
+}
+
+impl StartingWealth {
+    pub fn average(&self) -> Wealth {
+        Wealth(self.dice as u32 * 35 * COPPER_PER_GOLD)
+    }
+    pub fn roll(&self, rng: &mut GameRng) -> Wealth {
+        Wealth(rng.roll_many(self.dice, &Dice::D6) as u32 * 10 * COPPER_PER_GOLD)
+    }
+}
+
+impl fmt::Display for StartingWealth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}d6 x 10 gp", self.dice)
+    }
+}
+
+pub fn starting_wealth(class: &PlayableClass) -> StartingWealth {
+    use PlayableClass::*;
+    let dice = match class {
+        Monk => 1,
+        Arcanist | Druid | Kineticist | Medium | Psychic | Shaman | Sorcerer | Summoner
+        | Wizard => 2,
+        Alchemist | Barbarian | Bard | Bloodrager | Investigator | Mesmerist | Oracle | Skald
+        | Spiritualist | Witch => 3,
+        Cleric | Hunter | Inquisitor | Magus | Occultist | Rogue | Vigilante => 4,
+        Cavalier | Fighter | Gunslinger | Paladin | Ranger | Slayer | Swashbuckler | Warpriest => 5,
+        _ => 0,
+    };
+    StartingWealth { dice }
+}
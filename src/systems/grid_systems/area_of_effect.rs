@@ -0,0 +1,395 @@
+use crate::{
+    my_camera::my_camera_systems::MainCamera,
+    systems::grid_systems::{
+        grid_position::{movement_cost, Footprint, GridPos, FEET_PER_SQUARE},
+        line_of_sight::{corners, line_blocked},
+        pathfinding::TerrainMap,
+        token::{cursor_world_pos, SelectedToken, Token},
+    },
+};
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::f32::consts::FRAC_PI_4;
+
+// Areas are worked out in the same grid units as lines of sight, where the
+// center of square (x, y) is the point (x, y).
+
+const EPSILON: f32 = 1e-4;
+const SAMPLES_PER_SQUARE: f32 = 8.;
+
+// A point where grid lines cross, named by the square it is the bottom-left
+// corner of.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Intersection(pub GridPos);
+
+impl Intersection {
+    pub fn point(self) -> Vec2 {
+        Vec2::new(self.0.x as f32 - 0.5, self.0.y as f32 - 0.5)
+    }
+
+    // The intersection closest to a point in grid units.
+    pub fn nearest(point: Vec2) -> Self {
+        Self(GridPos::new(
+            (point.x + 0.5).round() as i32,
+            (point.y + 0.5).round() as i32,
+        ))
+    }
+
+    // Distance in feet to a square, counting the squares touching the
+    // intersection as 5 feet away and diagonals as alternating 5 and 10 feet.
+    pub fn distance_to(&self, square: &GridPos) -> usize {
+        let axis = |origin: i32, square: i32| -> usize {
+            if square >= origin {
+                (square - origin) as usize + 1
+            } else {
+                (origin - square) as usize
+            }
+        };
+        let dx = axis(self.0.x, square.x);
+        let dy = axis(self.0.y, square.y);
+        movement_cost(dx.max(dy) - dx.min(dy), dx.min(dy), 0)
+    }
+}
+
+// Converts a point in world space to grid units.
+pub fn world_to_grid_point(world: Vec2) -> Vec2 {
+    world / crate::GRID_SQUARE_SIZE as f32
+}
+
+// The shape and size in feet of an area of effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AreaShape {
+    // Affects everything within the radius that the origin has line of effect
+    // to.
+    Burst { radius: usize },
+    // Like a burst, but turns corners to fill the area.
+    Spread { radius: usize },
+    // A burst that lasts, usually centered on the caster.
+    Emanation { radius: usize },
+    // A quarter circle starting at a corner of the caster's space.
+    Cone { length: usize },
+    // Starts at a corner of the caster's space and affects every square it
+    // passes through.
+    Line { length: usize },
+    // A burst from the ground up to its height. The battle map is flat, so
+    // only the radius matters.
+    Cylinder { radius: usize, height: usize },
+}
+
+impl AreaShape {
+    // Cones and lines start from a corner of the caster's space and point
+    // somewhere, while the rest can be placed on any intersection.
+    pub fn starts_at_caster(&self) -> bool {
+        matches!(self, AreaShape::Cone { .. } | AreaShape::Line { .. })
+    }
+}
+
+// An area of effect placed on the grid. `direction` is only used by cones and
+// lines.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AreaTemplate {
+    pub shape: AreaShape,
+    pub origin: Intersection,
+    pub direction: Vec2,
+}
+
+impl AreaTemplate {
+    // Aims an area at a point in grid units. Cones and lines start from the
+    // corner of the caster's space closest to the point, and need a caster.
+    pub fn aim(
+        shape: AreaShape,
+        caster: Option<(&GridPos, &Footprint)>,
+        target: Vec2,
+    ) -> Option<Self> {
+        if !shape.starts_at_caster() {
+            return Some(Self {
+                shape,
+                origin: Intersection::nearest(target),
+                direction: Vec2::ZERO,
+            });
+        }
+        let (pos, footprint) = caster?;
+        let corner = corners(pos, footprint)
+            .into_iter()
+            .min_by(|a, b| a.distance(target).total_cmp(&b.distance(target)))?;
+        let direction = target - corner;
+        if direction.length() < EPSILON {
+            return None;
+        }
+        Some(Self {
+            shape,
+            origin: Intersection::nearest(corner),
+            direction,
+        })
+    }
+
+    // The squares the area covers. Wall squares are never included.
+    pub fn squares(&self, terrain: &TerrainMap) -> HashSet<GridPos> {
+        match self.shape {
+            AreaShape::Burst { radius }
+            | AreaShape::Emanation { radius }
+            | AreaShape::Cylinder { radius, .. } => self.burst_squares(radius, terrain, &|_| true),
+            AreaShape::Spread { radius } => self.spread_squares(radius, terrain),
+            AreaShape::Cone { length } => {
+                let direction = snap_direction(self.direction);
+                let origin = self.origin.point();
+                self.burst_squares(length, terrain, &|square: &GridPos| {
+                    let offset = Vec2::new(square.x as f32, square.y as f32) - origin;
+                    offset.angle_between(direction).abs() <= FRAC_PI_4 + EPSILON
+                })
+            }
+            AreaShape::Line { length } => self.line_squares(length, terrain),
+        }
+    }
+
+    // Squares within the radius that pass the filter and have line of effect
+    // from the origin.
+    fn burst_squares(
+        &self,
+        radius: usize,
+        terrain: &TerrainMap,
+        filter: &impl Fn(&GridPos) -> bool,
+    ) -> HashSet<GridPos> {
+        let reach = (radius / FEET_PER_SQUARE) as i32;
+        let origin = self.origin.0;
+        let walls = |square: &GridPos| terrain.is_wall(square);
+        (origin.y - reach..origin.y + reach)
+            .flat_map(|y| (origin.x - reach..origin.x + reach).map(move |x| GridPos::new(x, y)))
+            .filter(|square| {
+                self.origin.distance_to(square) <= radius
+                    && !terrain.is_wall(square)
+                    && filter(square)
+                    && !line_blocked(
+                        self.origin.point(),
+                        Vec2::new(square.x as f32, square.y as f32),
+                        &walls,
+                    )
+            })
+            .collect()
+    }
+
+    // Spreads are measured by the distance travelled around walls, without
+    // squeezing diagonally between them. The search tracks whether the next
+    // diagonal costs double, as pathfinding does.
+    fn spread_squares(&self, radius: usize, terrain: &TerrainMap) -> HashSet<GridPos> {
+        let origin = self.origin.0;
+        let mut open: BinaryHeap<Reverse<(usize, GridPos, bool)>> = BinaryHeap::new();
+        let mut best_cost: HashMap<(GridPos, bool), usize> = HashMap::new();
+        // The squares touching the intersection are each one diagonal away.
+        for dy in [-1, 0] {
+            for dx in [-1, 0] {
+                let square = GridPos::new(origin.x + dx, origin.y + dy);
+                if FEET_PER_SQUARE <= radius && !terrain.is_wall(&square) {
+                    best_cost.insert((square, true), FEET_PER_SQUARE);
+                    open.push(Reverse((FEET_PER_SQUARE, square, true)));
+                }
+            }
+        }
+
+        let mut reached: HashSet<GridPos> = HashSet::new();
+        while let Some(Reverse((cost, square, odd))) = open.pop() {
+            if cost > *best_cost.get(&(square, odd)).unwrap_or(&usize::MAX) {
+                continue;
+            }
+            reached.insert(square);
+            for next in square.neighbors() {
+                let diagonal = square.is_diagonal_to(&next);
+                if terrain.is_wall(&next)
+                    || (diagonal
+                        && (terrain.is_wall(&GridPos::new(next.x, square.y))
+                            || terrain.is_wall(&GridPos::new(square.x, next.y))))
+                {
+                    continue;
+                }
+                let step = if diagonal && odd {
+                    2 * FEET_PER_SQUARE
+                } else {
+                    FEET_PER_SQUARE
+                };
+                let next_cost = cost + step;
+                let next_node = (next, odd ^ diagonal);
+                if next_cost <= radius
+                    && next_cost < *best_cost.get(&next_node).unwrap_or(&usize::MAX)
+                {
+                    best_cost.insert(next_node, next_cost);
+                    open.push(Reverse((next_cost, next, next_node.1)));
+                }
+            }
+        }
+        reached
+    }
+
+    // Every square the line passes through, up to its length or the first
+    // wall.
+    fn line_squares(&self, length: usize, terrain: &TerrainMap) -> HashSet<GridPos> {
+        let start = self.origin.point();
+        let end = start + self.direction.normalize_or_zero() * (length / FEET_PER_SQUARE) as f32;
+        let samples = (start.distance(end) * SAMPLES_PER_SQUARE).ceil() as usize;
+        let mut squares = HashSet::new();
+        for i in 1..=samples {
+            let point = start.lerp(end, i as f32 / samples as f32);
+            let square = GridPos::new(
+                (point.x + 0.5).floor() as i32,
+                (point.y + 0.5).floor() as i32,
+            );
+            if terrain.is_wall(&square) {
+                break;
+            }
+            if self.origin.distance_to(&square) <= length {
+                squares.insert(square);
+            }
+        }
+        squares
+    }
+}
+
+// Cones point straight along the grid or along a diagonal, whichever is
+// closest.
+fn snap_direction(direction: Vec2) -> Vec2 {
+    let angle = (direction.y.atan2(direction.x) / FRAC_PI_4).round() * FRAC_PI_4;
+    Vec2::new(angle.cos(), angle.sin())
+}
+
+// Every creature with at least one square of its space in the area.
+pub fn creatures_in_area<'a>(
+    squares: &HashSet<GridPos>,
+    tokens: impl Iterator<Item = (Entity, &'a GridPos, &'a Footprint)>,
+) -> Vec<Entity> {
+    tokens
+        .filter(|(_, pos, footprint)| {
+            pos.squares(footprint)
+                .iter()
+                .any(|square| squares.contains(square))
+        })
+        .map(|(entity, ..)| entity)
+        .collect()
+}
+
+// The area currently being aimed by the selected token, if any. Spells and
+// abilities set this to start targeting an area.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct AreaTargeting(pub Option<AreaShape>);
+
+// Sent once an area has been placed, with every creature caught in it.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct AreaTargetedEvent {
+    pub source: Option<Entity>,
+    pub template: AreaTemplate,
+    pub squares: HashSet<GridPos>,
+    pub creatures: Vec<Entity>,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct AreaPreviewMarker;
+
+// Drawn above the path preview and below the tokens.
+const AREA_PREVIEW_Z_POS: f32 = 2.;
+
+fn aim_at_cursor(
+    shape: AreaShape,
+    query_window: &Query<&Window>,
+    query_camera: &Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    query_token: &Query<(Entity, &GridPos, &Footprint), With<Token>>,
+    selected: &SelectedToken,
+) -> Option<AreaTemplate> {
+    let cursor = cursor_world_pos(query_window, query_camera)?;
+    let caster = selected
+        .0
+        .and_then(|entity| query_token.get(entity).ok())
+        .map(|(_, pos, footprint)| (pos, footprint));
+    AreaTemplate::aim(shape, caster, world_to_grid_point(cursor))
+}
+
+// Draws the squares the area being aimed would cover.
+#[allow(clippy::too_many_arguments)]
+pub fn preview_area(
+    query_window: Query<&Window>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    query_token: Query<(Entity, &GridPos, &Footprint), With<Token>>,
+    query_marker: Query<Entity, With<AreaPreviewMarker>>,
+    selected: Res<SelectedToken>,
+    targeting: Res<AreaTargeting>,
+    terrain: Res<TerrainMap>,
+    mut previewed: Local<Option<AreaTemplate>>,
+    mut preview_assets: Local<Option<(Handle<Mesh>, Handle<ColorMaterial>)>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    let template = targeting.0.and_then(|shape| {
+        aim_at_cursor(shape, &query_window, &query_camera, &query_token, &selected)
+    });
+    if template == *previewed && !terrain.is_changed() {
+        return;
+    }
+    *previewed = template;
+
+    for marker in query_marker.iter() {
+        commands.entity(marker).despawn();
+    }
+    let Some(template) = template else {
+        return;
+    };
+    let (mesh, material) = preview_assets
+        .get_or_insert_with(|| {
+            (
+                meshes.add(shape::Quad::new(Vec2::splat(crate::GRID_SQUARE_SIZE as f32)).into()),
+                materials.add(Color::rgba(0.9, 0.4, 0.1, 0.4).into()),
+            )
+        })
+        .clone();
+    for square in template.squares(&terrain) {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: mesh.clone().into(),
+                material: material.clone(),
+                transform: Transform::from_translation(
+                    square.to_world().extend(AREA_PREVIEW_Z_POS),
+                ),
+                ..default()
+            },
+            AreaPreviewMarker,
+        ));
+    }
+}
+
+// Left clicking places the area being aimed, and escape cancels it.
+#[allow(clippy::too_many_arguments)]
+pub fn place_area(
+    mouse_input: Res<Input<MouseButton>>,
+    key_input: Res<Input<KeyCode>>,
+    query_window: Query<&Window>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    query_token: Query<(Entity, &GridPos, &Footprint), With<Token>>,
+    selected: Res<SelectedToken>,
+    terrain: Res<TerrainMap>,
+    mut targeting: ResMut<AreaTargeting>,
+    mut event_writer: EventWriter<AreaTargetedEvent>,
+) {
+    let Some(shape) = targeting.0 else {
+        return;
+    };
+    if key_input.just_pressed(KeyCode::Escape) {
+        targeting.0 = None;
+        return;
+    }
+    if !mouse_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(template) =
+        aim_at_cursor(shape, &query_window, &query_camera, &query_token, &selected)
+    else {
+        return;
+    };
+    let squares = template.squares(&terrain);
+    let creatures = creatures_in_area(&squares, query_token.iter());
+    event_writer.send(AreaTargetedEvent {
+        source: selected.0,
+        template,
+        squares,
+        creatures,
+    });
+    targeting.0 = None;
+}
@@ -20,6 +20,10 @@ pub struct FlexGrid {
     pub entity_ids: EntityIds,
 }
 
+// Marks the line meshes drawn for a grid, so they can be despawned again.
+#[derive(Component)]
+pub struct GridLine;
+
 pub fn setup_flex_grid(
     window_query: Query<&Window>,
     commands: Commands,
@@ -36,6 +40,12 @@ pub fn setup_flex_grid(
     .draw(commands, meshes, materials);
 }
 
+pub fn cleanup_flex_grid(query_lines: Query<Entity, With<GridLine>>, mut commands: Commands) {
+    for entity in query_lines.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
 impl FlexGrid {
     pub fn new() -> Self {
         FlexGrid {
@@ -122,6 +132,7 @@ impl FlexGrid {
                         )),
                         ..default()
                     })
+                    .insert(GridLine)
                     .id();
 
                 // Lines in the y dimension, from each flex_point
@@ -144,6 +155,7 @@ impl FlexGrid {
                         )),
                         ..default()
                     })
+                    .insert(GridLine)
                     .id();
                 into_entity_ids.push(x_id);
                 into_entity_ids.push(y_id);
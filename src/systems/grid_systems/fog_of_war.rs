@@ -0,0 +1,111 @@
+use crate::systems::grid_systems::{
+    grid_position::{Footprint, GridPos},
+    token::{Faction, Token},
+    vision::{BattleArea, VisibleSquares},
+};
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+use std::collections::HashSet;
+
+// A tile of fog over a single square of the battle map.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FogTile(pub GridPos);
+
+// Squares the party has seen at some point during the battle.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ExploredSquares(pub HashSet<GridPos>);
+
+#[derive(Resource, Clone, Debug)]
+pub struct FogMaterials {
+    pub unexplored: Handle<ColorMaterial>,
+    pub explored: Handle<ColorMaterial>,
+}
+
+// Drawn over the tokens, so creatures in unseen squares are covered.
+const FOG_Z_POS: f32 = 950.;
+
+pub fn spawn_fog_of_war(
+    area: Res<BattleArea>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    let fog_materials = FogMaterials {
+        unexplored: materials.add(Color::rgba(0., 0., 0., 0.95).into()),
+        explored: materials.add(Color::rgba(0., 0., 0., 0.6).into()),
+    };
+    let mesh = meshes.add(shape::Quad::new(Vec2::splat(crate::GRID_SQUARE_SIZE as f32)).into());
+    for square in area.squares() {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: mesh.clone().into(),
+                material: fog_materials.unexplored.clone(),
+                transform: Transform::from_translation(square.to_world().extend(FOG_Z_POS)),
+                ..default()
+            },
+            FogTile(square),
+        ));
+    }
+    commands.insert_resource(fog_materials);
+}
+
+type TokenVisibilityQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GridPos,
+        &'static Footprint,
+        &'static Faction,
+        &'static mut Visibility,
+    ),
+    (With<Token>, Without<FogTile>),
+>;
+
+// Clears the fog from squares the party can see, greys out squares they have
+// seen before, and hides creatures outside the party's sight.
+pub fn update_fog_of_war(
+    query_changed: Query<(), Changed<VisibleSquares>>,
+    query_party: Query<(&Faction, &VisibleSquares)>,
+    mut query_tile: Query<(&FogTile, &mut Handle<ColorMaterial>, &mut Visibility)>,
+    mut query_token: TokenVisibilityQuery,
+    mut explored: ResMut<ExploredSquares>,
+    fog_materials: Option<Res<FogMaterials>>,
+) {
+    let Some(fog_materials) = fog_materials else {
+        return;
+    };
+    if query_changed.is_empty() {
+        return;
+    }
+    let visible: HashSet<GridPos> = query_party
+        .iter()
+        .filter(|(faction, _)| **faction == Faction::Party)
+        .flat_map(|(_, visible)| visible.0.iter().copied())
+        .collect();
+    explored.0.extend(visible.iter().copied());
+
+    for (tile, mut material, mut visibility) in query_tile.iter_mut() {
+        if visible.contains(&tile.0) {
+            *visibility = Visibility::Hidden;
+        } else {
+            *visibility = Visibility::Visible;
+            *material = if explored.0.contains(&tile.0) {
+                fog_materials.explored.clone()
+            } else {
+                fog_materials.unexplored.clone()
+            };
+        }
+    }
+    for (pos, footprint, faction, mut visibility) in query_token.iter_mut() {
+        *visibility = if *faction == Faction::Party
+            || pos
+                .squares(footprint)
+                .iter()
+                .any(|square| visible.contains(square))
+        {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,132 @@
+use crate::systems::game::character::SizeCategory;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Number of feet covered by one side of a grid square.
+pub const FEET_PER_SQUARE: usize = 5;
+
+// The square a creature or object occupies on the battle map. Square (0, 0) is
+// centered on the world origin, x increases to the right and y increases
+// upward, matching bevy's 2d world coordinates.
+// For creatures larger than one square this is the bottom-left square of
+// their footprint.
+#[derive(
+    Component,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub struct GridPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GridPos {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    // The square containing the given point in world space.
+    pub fn from_world(world: Vec2) -> Self {
+        let square_size = crate::GRID_SQUARE_SIZE as f32;
+        Self {
+            x: (world.x / square_size).round() as i32,
+            y: (world.y / square_size).round() as i32,
+        }
+    }
+
+    // The center of the square in world space.
+    pub fn to_world(self) -> Vec2 {
+        let square_size = crate::GRID_SQUARE_SIZE as f32;
+        Vec2::new(self.x as f32 * square_size, self.y as f32 * square_size)
+    }
+
+    // The center of a footprint that has this square as its bottom-left.
+    pub fn footprint_center(&self, footprint: &Footprint) -> Vec2 {
+        let offset = (footprint.0 as f32 - 1.) * crate::GRID_SQUARE_SIZE as f32 / 2.;
+        self.to_world() + Vec2::splat(offset)
+    }
+
+    // Every square covered by a footprint that has this square as its
+    // bottom-left.
+    pub fn squares(&self, footprint: &Footprint) -> Vec<GridPos> {
+        let side = footprint.0 as i32;
+        (0..side)
+            .flat_map(|dy| (0..side).map(move |dx| GridPos::new(self.x + dx, self.y + dy)))
+            .collect()
+    }
+
+    // The eight surrounding squares.
+    pub fn neighbors(&self) -> [GridPos; 8] {
+        [
+            GridPos::new(self.x - 1, self.y - 1),
+            GridPos::new(self.x, self.y - 1),
+            GridPos::new(self.x + 1, self.y - 1),
+            GridPos::new(self.x - 1, self.y),
+            GridPos::new(self.x + 1, self.y),
+            GridPos::new(self.x - 1, self.y + 1),
+            GridPos::new(self.x, self.y + 1),
+            GridPos::new(self.x + 1, self.y + 1),
+        ]
+    }
+
+    pub fn is_diagonal_to(&self, other: &GridPos) -> bool {
+        self.x != other.x && self.y != other.y
+    }
+
+    // Distance in feet, counting diagonals as alternating 5 and 10 feet.
+    pub fn distance_to(&self, other: &GridPos) -> usize {
+        let (straight, diagonal) = self.steps_to(other);
+        movement_cost(straight, diagonal, 0)
+    }
+
+    // The number of straight and diagonal steps on the shortest route to
+    // another square.
+    pub fn steps_to(&self, other: &GridPos) -> (usize, usize) {
+        let dx = self.x.abs_diff(other.x) as usize;
+        let dy = self.y.abs_diff(other.y) as usize;
+        (dx.max(dy) - dx.min(dy), dx.min(dy))
+    }
+}
+
+// Cost in feet of moving some number of straight and diagonal steps. Every
+// second diagonal costs 10 feet instead of 5, counted across the whole turn,
+// so `diagonals_taken` is the number of diagonal steps already moved this
+// turn.
+pub fn movement_cost(straight: usize, diagonal: usize, diagonals_taken: usize) -> usize {
+    let doubled = (diagonals_taken + diagonal) / 2 - diagonals_taken / 2;
+    (straight + diagonal + doubled) * FEET_PER_SQUARE
+}
+
+// The number of squares per side that a creature takes up.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Footprint(pub usize);
+
+impl Default for Footprint {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+// Creatures smaller than Small can share squares, but are still given a whole
+// square here.
+impl From<&SizeCategory> for Footprint {
+    fn from(size: &SizeCategory) -> Self {
+        use SizeCategory::*;
+        match size {
+            Fine | Diminutive | Tiny | Small | Medium => Self(1),
+            Large => Self(2),
+            Huge => Self(3),
+            Gargantuan => Self(4),
+            Colossal => Self(6),
+        }
+    }
+}
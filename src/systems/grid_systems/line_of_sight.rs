@@ -0,0 +1,214 @@
+use crate::systems::grid_systems::{
+    grid_position::{Footprint, GridPos},
+    pathfinding::TerrainMap,
+};
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+// Lines are traced in grid units, where the center of square (x, y) is the
+// point (x, y) and its corners are half a unit away.
+
+// Number of points checked per square of distance along a line.
+const SAMPLES_PER_SQUARE: f32 = 8.;
+const EPSILON: f32 = 1e-4;
+
+// The squares a point lies in. A point on a border lies in every square that
+// shares that border.
+fn squares_at(point: Vec2) -> Vec<GridPos> {
+    let axis = |value: f32| -> Vec<i32> {
+        let shifted = value + 0.5;
+        if (shifted - shifted.round()).abs() < EPSILON {
+            vec![shifted.round() as i32 - 1, shifted.round() as i32]
+        } else {
+            vec![shifted.floor() as i32]
+        }
+    };
+    let ys = axis(point.y);
+    axis(point.x)
+        .into_iter()
+        .flat_map(|x| ys.iter().map(move |y| GridPos::new(x, *y)))
+        .collect()
+}
+
+// Whether a line between two points passes through a blocking square. Running
+// along the border of a blocking square doesn't count, but passing between two
+// blocking squares that touch at a corner does.
+pub fn line_blocked(from: Vec2, to: Vec2, blocks: &impl Fn(&GridPos) -> bool) -> bool {
+    let samples = (from.distance(to) * SAMPLES_PER_SQUARE).ceil() as usize + 1;
+    (1..samples).any(|i| {
+        let point = from.lerp(to, i as f32 / samples as f32);
+        squares_at(point).iter().all(blocks)
+    })
+}
+
+// The corners of the area covered by a footprint.
+pub fn corners(pos: &GridPos, footprint: &Footprint) -> [Vec2; 4] {
+    let min = Vec2::new(pos.x as f32 - 0.5, pos.y as f32 - 0.5);
+    let max = min + Vec2::splat(footprint.0 as f32);
+    [min, Vec2::new(max.x, min.y), Vec2::new(min.x, max.y), max]
+}
+
+// Every corner of every square in a footprint.
+fn all_corners(pos: &GridPos, footprint: &Footprint) -> Vec<Vec2> {
+    let mut points: Vec<Vec2> = Vec::new();
+    for square in pos.squares(footprint) {
+        for corner in corners(&square, &Footprint(1)) {
+            if !points.contains(&corner) {
+                points.push(corner);
+            }
+        }
+    }
+    points
+}
+
+fn center(pos: &GridPos, footprint: &Footprint) -> Vec2 {
+    Vec2::new(pos.x as f32, pos.y as f32) + Vec2::splat((footprint.0 as f32 - 1.) / 2.)
+}
+
+// Line of effect is a straight, unblocked line between the centers of two
+// areas. Spells and attacks need it to reach their target.
+pub fn line_of_effect(
+    from: &GridPos,
+    from_footprint: &Footprint,
+    to: &GridPos,
+    to_footprint: &Footprint,
+    terrain: &TerrainMap,
+) -> bool {
+    !line_blocked(
+        center(from, from_footprint),
+        center(to, to_footprint),
+        &|square: &GridPos| terrain.is_wall(square),
+    )
+}
+
+// A creature can see another if any line from a corner of its own space to a
+// corner of the other's space is unblocked.
+pub fn line_of_sight(
+    from: &GridPos,
+    from_footprint: &Footprint,
+    to: &GridPos,
+    to_footprint: &Footprint,
+    terrain: &TerrainMap,
+) -> bool {
+    let target_corners = corners(to, to_footprint);
+    all_corners(from, from_footprint).iter().any(|start| {
+        target_corners
+            .iter()
+            .any(|end| !line_blocked(*start, *end, &|square: &GridPos| terrain.is_wall(square)))
+    })
+}
+
+// Cover the target of an attack has against the attacker, from least to most
+// protective.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Cover {
+    #[default]
+    None,
+    // More than half the target is visible.
+    Partial,
+    // Cover from creatures in the way, only against ranged attacks.
+    Soft,
+    Standard,
+    Improved,
+    // No line of effect, the target can't be attacked.
+    Total,
+}
+
+impl Cover {
+    pub fn ac_bonus(&self) -> i32 {
+        match self {
+            Cover::None | Cover::Total => 0,
+            Cover::Partial => 2,
+            Cover::Soft | Cover::Standard => 4,
+            Cover::Improved => 8,
+        }
+    }
+
+    pub fn reflex_bonus(&self) -> i32 {
+        match self {
+            Cover::None | Cover::Soft | Cover::Total => 0,
+            Cover::Partial => 1,
+            Cover::Standard => 2,
+            Cover::Improved => 4,
+        }
+    }
+}
+
+// Cover against an area effect, with lines traced from the area's point of
+// origin to each corner of the target's space. Only walls give this cover.
+pub fn cover_from_point(
+    origin: Vec2,
+    target: &GridPos,
+    target_footprint: &Footprint,
+    terrain: &TerrainMap,
+) -> Cover {
+    let walls = |square: &GridPos| terrain.is_wall(square);
+    let blocked = corners(target, target_footprint)
+        .iter()
+        .filter(|end| line_blocked(origin, **end, &walls))
+        .count();
+    match blocked {
+        0 => Cover::None,
+        1 => Cover::Partial,
+        2 | 3 => Cover::Standard,
+        _ => Cover::Improved,
+    }
+}
+
+// The attacker picks the corner of its space that gives the target the least
+// cover, and lines are traced from it to each corner of the target's space.
+// Walls blocking one line give partial cover, two or three give cover, and all
+// four give improved cover, or total cover if there is no line of effect.
+// Against ranged attacks, lines blocked only by other creatures give soft
+// cover. `creatures` holds the squares of every creature other than the
+// attacker and target.
+pub fn determine_cover(
+    attacker: &GridPos,
+    attacker_footprint: &Footprint,
+    target: &GridPos,
+    target_footprint: &Footprint,
+    terrain: &TerrainMap,
+    creatures: &HashSet<GridPos>,
+    ranged: bool,
+) -> Cover {
+    let walls = |square: &GridPos| terrain.is_wall(square);
+    let creature = |square: &GridPos| ranged && creatures.contains(square);
+    let target_corners = corners(target, target_footprint);
+
+    let cover = all_corners(attacker, attacker_footprint)
+        .iter()
+        .map(|start| {
+            let mut blocked_by_walls = 0;
+            let mut blocked_by_creatures = 0;
+            for end in target_corners.iter() {
+                if line_blocked(*start, *end, &walls) {
+                    blocked_by_walls += 1;
+                } else if line_blocked(*start, *end, &creature) {
+                    blocked_by_creatures += 1;
+                }
+            }
+            match blocked_by_walls {
+                0 if blocked_by_creatures > 0 => Cover::Soft,
+                0 => Cover::None,
+                1 => Cover::Partial,
+                2 | 3 => Cover::Standard,
+                _ => Cover::Improved,
+            }
+        })
+        .min()
+        .unwrap_or_default();
+
+    if cover == Cover::Improved
+        && !line_of_effect(
+            attacker,
+            attacker_footprint,
+            target,
+            target_footprint,
+            terrain,
+        )
+    {
+        Cover::Total
+    } else {
+        cover
+    }
+}
@@ -1,4 +1,11 @@
+pub mod area_of_effect;
 pub mod flex_grid;
+pub mod fog_of_war;
 pub mod grid_components;
+pub mod grid_position;
+pub mod line_of_sight;
+pub mod pathfinding;
 pub mod static_grid;
+pub mod token;
+pub mod vision;
 pub mod window_helper;
@@ -0,0 +1,184 @@
+use crate::systems::grid_systems::grid_position::{Footprint, GridPos, FEET_PER_SQUARE};
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+// How a square affects movement through it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    #[default]
+    Normal,
+    // Costs two squares to move into, or three moving diagonally.
+    Difficult,
+    // Cannot be entered, and blocks moving diagonally past its corners.
+    Wall,
+}
+
+// Terrain for the squares of the battle map. Squares not in the map are
+// Normal.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct TerrainMap(pub HashMap<GridPos, Terrain>);
+
+impl TerrainMap {
+    pub fn get(&self, pos: &GridPos) -> Terrain {
+        self.0.get(pos).copied().unwrap_or_default()
+    }
+    pub fn is_wall(&self, pos: &GridPos) -> bool {
+        self.get(pos) == Terrain::Wall
+    }
+}
+
+// The squares a mover has to take into account besides terrain.
+#[derive(Clone, Debug, Default)]
+pub struct Occupancy {
+    // Squares held by enemies, which can't be entered.
+    pub blocked: HashSet<GridPos>,
+    // Squares held by anyone else, which can be moved through but not ended in.
+    pub allied: HashSet<GridPos>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path {
+    // Squares moved through, not including the start but including the goal.
+    pub squares: Vec<GridPos>,
+    // Total cost in feet.
+    pub cost: usize,
+    // Number of diagonal steps, used to carry the alternating diagonal cost
+    // forward through the turn.
+    pub diagonals: usize,
+}
+
+// The cost in feet of a single step. Every second diagonal of the turn costs
+// double. Moving into difficult terrain costs two squares, or three squares
+// for a diagonal, whichever diagonal of the turn it is.
+fn step_cost(diagonal: bool, diagonals_taken: usize, difficult: bool) -> usize {
+    match (diagonal, difficult) {
+        (true, true) => 3 * FEET_PER_SQUARE,
+        (false, true) => 2 * FEET_PER_SQUARE,
+        (true, false) if diagonals_taken % 2 == 1 => 2 * FEET_PER_SQUARE,
+        _ => FEET_PER_SQUARE,
+    }
+}
+
+// Whether a footprint can stand in or pass through `pos`.
+fn passable(
+    pos: &GridPos,
+    footprint: &Footprint,
+    terrain: &TerrainMap,
+    occupancy: &Occupancy,
+) -> bool {
+    pos.squares(footprint)
+        .iter()
+        .all(|square| !terrain.is_wall(square) && !occupancy.blocked.contains(square))
+}
+
+// Whether a footprint can end its movement at `pos`.
+fn can_end_at(pos: &GridPos, footprint: &Footprint, occupancy: &Occupancy) -> bool {
+    pos.squares(footprint)
+        .iter()
+        .all(|square| !occupancy.allied.contains(square))
+}
+
+// Moving diagonally past the corner of a wall is not allowed.
+fn cuts_corner(from: &GridPos, to: &GridPos, footprint: &Footprint, terrain: &TerrainMap) -> bool {
+    if !from.is_diagonal_to(to) {
+        return false;
+    }
+    [GridPos::new(to.x, from.y), GridPos::new(from.x, to.y)]
+        .iter()
+        .any(|corner| {
+            corner
+                .squares(footprint)
+                .iter()
+                .any(|sq| terrain.is_wall(sq))
+        })
+}
+
+// The search state includes whether the next diagonal costs double, since
+// two routes to the same square can differ in that.
+type Node = (GridPos, bool);
+
+// A* search over grid squares from `start` to `goal`. Paths costing more than
+// `max_cost` feet are not considered. `diagonals_taken` is the number of
+// diagonal steps the mover has already taken this turn.
+pub fn find_path(
+    start: GridPos,
+    goal: GridPos,
+    footprint: &Footprint,
+    terrain: &TerrainMap,
+    occupancy: &Occupancy,
+    max_cost: usize,
+    diagonals_taken: usize,
+) -> Option<Path> {
+    if start == goal
+        || !passable(&goal, footprint, terrain, occupancy)
+        || !can_end_at(&goal, footprint, occupancy)
+    {
+        return None;
+    }
+
+    let start_node: Node = (start, diagonals_taken % 2 == 1);
+    let mut open: BinaryHeap<Reverse<(usize, usize, GridPos, bool)>> = BinaryHeap::new();
+    let mut best_cost: HashMap<Node, usize> = HashMap::new();
+    let mut came_from: HashMap<Node, (Node, bool)> = HashMap::new();
+
+    best_cost.insert(start_node, 0);
+    open.push(Reverse((start.distance_to(&goal), 0, start, start_node.1)));
+
+    while let Some(Reverse((_, cost, pos, odd))) = open.pop() {
+        let node = (pos, odd);
+        if cost > *best_cost.get(&node).unwrap_or(&usize::MAX) {
+            continue;
+        }
+        if pos == goal {
+            return Some(rebuild_path(node, cost, &came_from));
+        }
+        for next in pos.neighbors() {
+            if !passable(&next, footprint, terrain, occupancy)
+                || cuts_corner(&pos, &next, footprint, terrain)
+            {
+                continue;
+            }
+            let diagonal = pos.is_diagonal_to(&next);
+            let difficult = next
+                .squares(footprint)
+                .iter()
+                .any(|square| terrain.get(square) == Terrain::Difficult);
+            let next_cost = cost + step_cost(diagonal, odd as usize, difficult);
+            if next_cost > max_cost {
+                continue;
+            }
+            let next_node = (next, odd ^ diagonal);
+            if next_cost < *best_cost.get(&next_node).unwrap_or(&usize::MAX) {
+                best_cost.insert(next_node, next_cost);
+                came_from.insert(next_node, (node, diagonal));
+                open.push(Reverse((
+                    next_cost + next.distance_to(&goal),
+                    next_cost,
+                    next,
+                    next_node.1,
+                )));
+            }
+        }
+    }
+    None
+}
+
+fn rebuild_path(end: Node, cost: usize, came_from: &HashMap<Node, (Node, bool)>) -> Path {
+    let mut squares = vec![end.0];
+    let mut diagonals = 0;
+    let mut current = end;
+    while let Some((previous, diagonal)) = came_from.get(&current) {
+        diagonals += *diagonal as usize;
+        squares.push(previous.0);
+        current = *previous;
+    }
+    // Drop the starting square
+    squares.pop();
+    squares.reverse();
+    Path {
+        squares,
+        cost,
+        diagonals,
+    }
+}
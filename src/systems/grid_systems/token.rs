@@ -0,0 +1,382 @@
+use crate::{
+    my_camera::my_camera_systems::MainCamera,
+    systems::{
+        game::{
+            ai::AiController,
+            attack_of_opportunity::{AttacksOfOpportunity, ProvokeEvent, ProvokingAction},
+            character::{CharacterSize, GroundSpeed, SizeCategory, SizeType},
+        },
+        grid_systems::{
+            area_of_effect::AreaTargeting,
+            grid_position::{movement_cost, Footprint, GridPos, FEET_PER_SQUARE},
+            pathfinding::{find_path, Occupancy, Path, TerrainMap},
+            vision::VisibleSquares,
+        },
+    },
+    AsciiSheet,
+};
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+
+// Marker for anything drawn on the battle map as a token.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Token;
+
+// Which side a creature is on. Creatures of the same faction are allies.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Faction {
+    Party,
+    Hostile,
+    #[default]
+    Neutral,
+}
+
+impl Faction {
+    // Neutral creatures aren't hostile to anyone.
+    pub fn is_hostile_to(&self, other: &Faction) -> bool {
+        matches!(
+            (self, other),
+            (Faction::Party, Faction::Hostile) | (Faction::Hostile, Faction::Party)
+        )
+    }
+}
+
+// The token currently being controlled, if any.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SelectedToken(pub Option<Entity>);
+
+// Feet of movement left this turn, along with the number of diagonal steps
+// already taken, since every second diagonal costs double.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MovementRemaining {
+    pub feet: usize,
+    pub diagonals_taken: usize,
+}
+
+impl MovementRemaining {
+    pub fn from_speed(speed: &GroundSpeed) -> Self {
+        Self {
+            feet: speed.0 as usize,
+            diagonals_taken: 0,
+        }
+    }
+
+    pub fn reset(&mut self, speed: &GroundSpeed) {
+        *self = Self::from_speed(speed);
+    }
+
+    // The cost of moving between two squares by the shortest route, if the
+    // mover has enough movement left.
+    pub fn cost_to(&self, from: &GridPos, to: &GridPos) -> Option<usize> {
+        let (straight, diagonal) = from.steps_to(to);
+        let cost = movement_cost(straight, diagonal, self.diagonals_taken);
+        (cost <= self.feet).then_some(cost)
+    }
+
+    pub fn spend(&mut self, feet: usize, diagonals: usize) {
+        self.feet = self.feet.saturating_sub(feet);
+        self.diagonals_taken += diagonals;
+    }
+}
+
+#[derive(Bundle)]
+pub struct TokenBundle {
+    pub token: Token,
+    pub grid_pos: GridPos,
+    pub footprint: Footprint,
+    pub size: CharacterSize,
+    pub faction: Faction,
+    pub speed: GroundSpeed,
+    pub movement: MovementRemaining,
+    pub attacks_of_opportunity: AttacksOfOpportunity,
+    pub visible: VisibleSquares,
+    pub sprite_sheet: SpriteSheetBundle,
+}
+
+impl TokenBundle {
+    // A token drawn with one of the tiles in the ascii sprite sheet.
+    pub fn new(
+        ascii: &AsciiSheet,
+        sprite_index: usize,
+        color: Color,
+        grid_pos: GridPos,
+        size: SizeCategory,
+        faction: Faction,
+        speed: GroundSpeed,
+    ) -> Self {
+        let footprint = Footprint::from(&size);
+        let mut sprite = TextureAtlasSprite::new(sprite_index);
+        sprite.color = color;
+        sprite.custom_size = Some(Vec2::splat((crate::GRID_SQUARE_SIZE * footprint.0) as f32));
+        Self {
+            token: Token,
+            grid_pos,
+            footprint,
+            size: CharacterSize {
+                category: size,
+                size_type: SizeType::Tall,
+            },
+            faction,
+            movement: MovementRemaining::from_speed(&speed),
+            speed,
+            attacks_of_opportunity: AttacksOfOpportunity::default(),
+            visible: VisibleSquares::default(),
+            sprite_sheet: SpriteSheetBundle {
+                sprite,
+                texture_atlas: ascii.0.clone(),
+                transform: Transform::from_translation(
+                    grid_pos.footprint_center(&footprint).extend(TOKEN_Z_POS),
+                ),
+                ..default()
+            },
+        }
+    }
+}
+
+// Tokens are drawn above the grid lines.
+pub const TOKEN_Z_POS: f32 = 900.;
+
+// The position of the cursor in world space, if the cursor is in the window.
+pub fn cursor_world_pos(
+    query_window: &Query<&Window>,
+    query_camera: &Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) -> Option<Vec2> {
+    let window = query_window.get_single().ok()?;
+    let (camera, camera_transform) = query_camera.get_single().ok()?;
+    window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+}
+
+// The square under the cursor, if the cursor is in the window.
+pub fn cursor_grid_pos(
+    query_window: &Query<&Window>,
+    query_camera: &Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) -> Option<GridPos> {
+    cursor_world_pos(query_window, query_camera).map(GridPos::from_world)
+}
+
+// Squares the mover can't enter, or can only pass through. Only creatures
+// hostile to the mover block it; anyone else can be passed through but not
+// stopped in.
+pub fn occupancy_for<'a>(
+    mover: Entity,
+    mover_faction: &Faction,
+    tokens: impl Iterator<Item = (Entity, &'a GridPos, &'a Footprint, &'a Faction)>,
+) -> Occupancy {
+    let mut occupancy = Occupancy::default();
+    for (_, pos, footprint, faction) in tokens.filter(|(entity, ..)| *entity != mover) {
+        let squares = if mover_faction.is_hostile_to(faction) {
+            &mut occupancy.blocked
+        } else {
+            &mut occupancy.allied
+        };
+        squares.extend(pos.squares(footprint));
+    }
+    occupancy
+}
+
+// The path from the selected token to the hovered square, kept so that the
+// preview is only rebuilt when something changes.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PlannedPath {
+    pub mover: Option<Entity>,
+    pub from: GridPos,
+    pub goal: GridPos,
+    pub movement: MovementRemaining,
+    pub path: Option<Path>,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PathPreviewMarker;
+
+// Drawn between the grid lines and the tokens.
+const PATH_PREVIEW_Z_POS: f32 = 1.;
+
+// Finds a path from the selected token to the hovered square, and draws it on
+// the grid.
+#[allow(clippy::too_many_arguments)]
+pub fn preview_path(
+    query_window: Query<&Window>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    query_token: Query<(Entity, &GridPos, &Footprint, &Faction, &MovementRemaining), With<Token>>,
+    query_marker: Query<Entity, With<PathPreviewMarker>>,
+    selected: Res<SelectedToken>,
+    terrain: Res<TerrainMap>,
+    mut planned: ResMut<PlannedPath>,
+    mut preview_assets: Local<Option<(Handle<Mesh>, Handle<ColorMaterial>)>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    let hovered = cursor_grid_pos(&query_window, &query_camera);
+    let mover = selected
+        .0
+        .and_then(|entity| query_token.get(entity).ok())
+        .zip(hovered);
+    let Some(((entity, from, footprint, faction, movement), goal)) = mover else {
+        if planned.mover.is_some() {
+            *planned = PlannedPath::default();
+            for marker in query_marker.iter() {
+                commands.entity(marker).despawn();
+            }
+        }
+        return;
+    };
+    if planned.mover == Some(entity)
+        && planned.from == *from
+        && planned.goal == goal
+        && planned.movement == *movement
+    {
+        return;
+    }
+
+    let occupancy = occupancy_for(
+        entity,
+        faction,
+        query_token
+            .iter()
+            .map(|(entity, pos, footprint, faction, _)| (entity, pos, footprint, faction)),
+    );
+    *planned = PlannedPath {
+        mover: Some(entity),
+        from: *from,
+        goal,
+        movement: *movement,
+        path: find_path(
+            *from,
+            goal,
+            footprint,
+            &terrain,
+            &occupancy,
+            movement.feet,
+            movement.diagonals_taken,
+        ),
+    };
+
+    for marker in query_marker.iter() {
+        commands.entity(marker).despawn();
+    }
+    let Some(path) = &planned.path else {
+        return;
+    };
+    let (mesh, material) = preview_assets
+        .get_or_insert_with(|| {
+            let side = crate::GRID_SQUARE_SIZE as f32 * 0.4;
+            (
+                meshes.add(shape::Quad::new(Vec2::splat(side)).into()),
+                materials.add(Color::rgba(0.2, 0.9, 0.3, 0.6).into()),
+            )
+        })
+        .clone();
+    for square in path.squares.iter() {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: mesh.clone().into(),
+                material: material.clone(),
+                transform: Transform::from_translation(
+                    square
+                        .footprint_center(footprint)
+                        .extend(PATH_PREVIEW_Z_POS),
+                ),
+                ..default()
+            },
+            PathPreviewMarker,
+        ));
+    }
+}
+
+type MoverQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut GridPos,
+        &'static Footprint,
+        &'static GroundSpeed,
+        &'static mut MovementRemaining,
+    ),
+    With<Token>,
+>;
+
+// Left clicking a party token the player controls selects it, unless an area
+// is being aimed. Tokens run by the AI can't be selected. Left
+// clicking an empty square with a token selected moves it along the planned
+// path, if there is one, provoking attacks of opportunity from squares it
+// leaves.
+// Holding shift takes a 5-foot step instead, which doesn't provoke. It can
+// only be taken before any other movement this turn, into a square that costs
+// no more than 5 feet, and uses up the rest of the turn's movement.
+#[allow(clippy::too_many_arguments)]
+pub fn select_or_move_token(
+    mouse_input: Res<Input<MouseButton>>,
+    key_input: Res<Input<KeyCode>>,
+    query_window: Query<&Window>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut query_token: MoverQuery,
+    query_control: Query<(&Faction, Option<&AiController>), With<Token>>,
+    mut selected: ResMut<SelectedToken>,
+    planned: Res<PlannedPath>,
+    targeting: Res<AreaTargeting>,
+    mut event_writer: EventWriter<ProvokeEvent>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) || targeting.0.is_some() {
+        return;
+    }
+    let Some(clicked) = cursor_grid_pos(&query_window, &query_camera) else {
+        return;
+    };
+    if let Some((entity, ..)) = query_token
+        .iter()
+        .find(|(_, pos, footprint, ..)| pos.squares(footprint).contains(&clicked))
+    {
+        let controlled = query_control
+            .get(entity)
+            .is_ok_and(|(faction, ai)| *faction == Faction::Party && ai.is_none());
+        if controlled {
+            selected.0 = Some(entity);
+        }
+        return;
+    }
+    let Some(selected_entity) = selected.0 else {
+        return;
+    };
+    let Ok((_, mut pos, _, speed, mut movement)) = query_token.get_mut(selected_entity) else {
+        selected.0 = None;
+        return;
+    };
+    if planned.mover != Some(selected_entity) || planned.goal != clicked || planned.from != *pos {
+        return;
+    }
+    let Some(path) = &planned.path else {
+        return;
+    };
+    if key_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let full_movement = MovementRemaining::from_speed(speed);
+        if *movement != full_movement || path.squares.len() != 1 || path.cost > FEET_PER_SQUARE {
+            return;
+        }
+        let feet = movement.feet;
+        movement.spend(feet, path.diagonals);
+    } else {
+        movement.spend(path.cost, path.diagonals);
+        let mut positions = vec![*pos];
+        positions.extend(path.squares.iter().copied());
+        event_writer.send(ProvokeEvent {
+            actor: selected_entity,
+            action: ProvokingAction::Move(positions),
+        });
+    }
+    *pos = clicked;
+}
+
+// Keep token sprites drawn over the squares they occupy.
+pub fn sync_token_transforms(
+    mut query_token: Query<(&GridPos, &Footprint, &mut Transform), Changed<GridPos>>,
+) {
+    for (pos, footprint, mut transform) in query_token.iter_mut() {
+        let center = pos.footprint_center(footprint);
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+    }
+}
@@ -0,0 +1,220 @@
+use crate::systems::{
+    game::character::{DarkVision, LowLightVision, NormalVision},
+    grid_systems::{
+        grid_position::{Footprint, GridPos},
+        line_of_sight::{line_of_effect, line_of_sight},
+        pathfinding::TerrainMap,
+    },
+};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+// Light levels, from darkest to brightest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LightLevel {
+    Darkness,
+    Dim,
+    #[default]
+    Normal,
+    Bright,
+}
+
+impl LightLevel {
+    pub fn raised(&self) -> Self {
+        match self {
+            LightLevel::Darkness => LightLevel::Dim,
+            LightLevel::Dim => LightLevel::Normal,
+            LightLevel::Normal | LightLevel::Bright => LightLevel::Bright,
+        }
+    }
+}
+
+// The light level of the battle map where no light source reaches.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AmbientLight(pub LightLevel);
+
+// Something giving off light from its square, like a torch or a light spell.
+// Squares within `radius` feet are lit to `level`, and the light is raised
+// one step out to twice that radius. A torch is a radius of 20 at Normal.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct LightSource {
+    pub radius: f32,
+    pub level: LightLevel,
+}
+
+// The area of the battle map in which vision and light are worked out, from
+// the bottom-left square to the top-right square.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BattleArea {
+    pub min: GridPos,
+    pub max: GridPos,
+}
+
+impl BattleArea {
+    pub fn squares(&self) -> impl Iterator<Item = GridPos> + '_ {
+        (self.min.y..=self.max.y)
+            .flat_map(move |y| (self.min.x..=self.max.x).map(move |x| GridPos::new(x, y)))
+    }
+}
+
+// Covers the visible window with the battle area.
+pub fn setup_battle_area(query_window: Query<&Window>, mut area: ResMut<BattleArea>) {
+    let Ok(window) = query_window.get_single() else {
+        return;
+    };
+    let half_size = Vec2::new(window.width(), window.height()) / 2.;
+    *area = BattleArea {
+        min: GridPos::from_world(-half_size),
+        max: GridPos::from_world(half_size),
+    };
+}
+
+// Everything needed to find how well lit a square is.
+#[derive(SystemParam)]
+pub struct Lighting<'w, 's> {
+    pub ambient: Res<'w, AmbientLight>,
+    pub terrain: Res<'w, TerrainMap>,
+    pub sources: Query<'w, 's, (&'static GridPos, &'static LightSource)>,
+}
+
+impl<'w, 's> Lighting<'w, 's> {
+    // The light level at a square, as seen by a creature with or without
+    // low-light vision. Low-light vision doubles the radius of light sources.
+    pub fn light_at(&self, square: &GridPos, low_light: bool) -> LightLevel {
+        let multiplier = if low_light { 2. } else { 1. };
+        let single = Footprint(1);
+        self.sources
+            .iter()
+            .filter(|(source, _)| line_of_effect(source, &single, square, &single, &self.terrain))
+            .fold(self.ambient.0, |light, (source, source_light)| {
+                let distance = source.distance_to(square) as f32;
+                let radius = source_light.radius * multiplier;
+                if distance <= radius {
+                    light.max(source_light.level)
+                } else if distance <= radius * 2. {
+                    light.max(self.ambient.0.raised().min(source_light.level))
+                } else {
+                    light
+                }
+            })
+    }
+}
+
+// How hard a target is to see, and the chance an attack against it misses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Concealment {
+    #[default]
+    None,
+    Partial,
+    Total,
+}
+
+impl Concealment {
+    pub fn miss_chance(&self) -> usize {
+        match self {
+            Concealment::None => 0,
+            Concealment::Partial => 20,
+            Concealment::Total => 50,
+        }
+    }
+}
+
+// The vision components of an observer. Creatures without `NormalVision`
+// are assumed to see normally, while `NormalVision(false)` is blind outside
+// of darkvision. Low-light vision and darkvision are ranges in feet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Senses {
+    pub sight: bool,
+    pub low_light: f32,
+    pub darkvision: f32,
+}
+
+impl Senses {
+    pub fn new(
+        normal: Option<&NormalVision>,
+        low_light: Option<&LowLightVision>,
+        darkvision: Option<&DarkVision>,
+    ) -> Self {
+        Self {
+            sight: normal.is_none_or(|normal| normal.0),
+            low_light: low_light.map_or(0., |low_light| low_light.0),
+            darkvision: darkvision.map_or(0., |darkvision| darkvision.0),
+        }
+    }
+
+    // Dim light gives concealment and darkness gives total concealment, except
+    // within the range of darkvision. Targets within the range of low-light
+    // vision are seen as if light sources reached twice as far.
+    pub fn concealment_of(
+        &self,
+        observer: &GridPos,
+        target: &GridPos,
+        lighting: &Lighting,
+    ) -> Concealment {
+        let distance = observer.distance_to(target) as f32;
+        if distance <= self.darkvision {
+            return Concealment::None;
+        }
+        if !self.sight {
+            return Concealment::Total;
+        }
+        match lighting.light_at(target, distance <= self.low_light) {
+            LightLevel::Darkness => Concealment::Total,
+            LightLevel::Dim => Concealment::Partial,
+            LightLevel::Normal | LightLevel::Bright => Concealment::None,
+        }
+    }
+}
+
+// The squares a creature can currently see.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VisibleSquares(pub HashSet<GridPos>);
+
+type ObserverQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Ref<'static, GridPos>,
+        &'static Footprint,
+        Option<&'static NormalVision>,
+        Option<&'static LowLightVision>,
+        Option<&'static DarkVision>,
+        &'static mut VisibleSquares,
+    ),
+>;
+
+// Light sources that changed or moved.
+type ChangedLight = Or<(Changed<LightSource>, (With<LightSource>, Changed<GridPos>))>;
+
+// Squares are visible when there is line of sight to them and they aren't in
+// total concealment for the observer. Changes to light, terrain or the battle
+// area recalculate every observer, otherwise only the observers that moved.
+pub fn update_visible_squares(
+    mut query_observer: ObserverQuery,
+    query_changed_light: Query<(), ChangedLight>,
+    lighting: Lighting,
+    area: Res<BattleArea>,
+) {
+    let everyone = !query_changed_light.is_empty()
+        || lighting.ambient.is_changed()
+        || lighting.terrain.is_changed()
+        || area.is_changed();
+    for (pos, footprint, normal, low_light, darkvision, mut visible) in query_observer.iter_mut() {
+        if !everyone && !pos.is_changed() {
+            continue;
+        }
+        let senses = Senses::new(normal, low_light, darkvision);
+        let eyes = pos.squares(footprint);
+        visible.0 = area
+            .squares()
+            .filter(|square| {
+                eyes.contains(square)
+                    || (line_of_sight(&pos, footprint, square, &Footprint(1), &lighting.terrain)
+                        && eyes.iter().any(|eye| {
+                            senses.concealment_of(eye, square, &lighting) != Concealment::Total
+                        }))
+            })
+            .collect();
+    }
+}
@@ -7,7 +7,10 @@ use crate::menu::components::ScrollingList;
 use crate::menu::styles::*;
 use crate::systems::game::class::PlayableClass;
 use crate::systems::game::{
-    archetype::ArchetypeName, character::PlayableRace, race::CharacterBuilder,
+    archetype::ArchetypeName,
+    character::{AbilityScores, PlayableRace},
+    race::CharacterBuilder,
+    skills::SkillRanks,
 };
 use bevy::prelude::*;
 use bevy::ui::FocusPolicy;
@@ -25,13 +28,21 @@ impl ButtonBar for HighContainer {}
 
 pub fn build_layout(mut commands: Commands, asset_server: Res<AssetServer>) {
     let shared_font = asset_server.load("fonts/simple_font.TTF");
-    commands.spawn(CharacterBuilder);
+    // The builder starts with average scores and no skill ranks, so the tabs
+    // reading them work before the character has been randomized.
+    commands.spawn((
+        CharacterBuilder,
+        AbilityScores::new(),
+        SkillRanks::default(),
+    ));
     // empty tooltip, insert text as needed.
     commands.spawn((
         Tooltip,
         TextBundle {
             focus_policy: FocusPolicy::Pass,
             background_color: Color::BLACK.into(),
+            // Above the character sheet, which covers the rest of the screen.
+            z_index: ZIndex::Global(2),
             style: Style {
                 position_type: PositionType::Absolute,
                 width: Val::Px(300.),
@@ -535,6 +546,92 @@ pub fn build_layout(mut commands: Commands, asset_server: Res<AssetServer>) {
             Name::from("Archetype TabListParent"),
         ))
         .set_parent(central_scroll_list);
+    // Languages Tab display, filled in by languages::setup_language_tab
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(8.),
+                    ..list_parent.style.clone()
+                },
+                ..list_parent.clone()
+            },
+            TabListParent::Languages,
+            Name::from("Languages TabListParent"),
+        ))
+        .set_parent(central_scroll_list);
+    // Alignment Tab display, filled in by alignment::setup_alignment_tab
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    ..list_parent.style.clone()
+                },
+                ..list_parent.clone()
+            },
+            TabListParent::Alignment,
+            Name::from("Alignment TabListParent"),
+        ))
+        .set_parent(central_scroll_list);
+    // Traits Tab display, filled in by character_traits::build_trait_buttons
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    ..list_parent.style.clone()
+                },
+                ..list_parent.clone()
+            },
+            TabListParent::Traits,
+            Name::from("Traits TabListParent"),
+        ))
+        .set_parent(central_scroll_list);
+    // Favored Class Tab display, filled in by favored_class::setup_favored_class_tab
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    ..list_parent.style.clone()
+                },
+                ..list_parent.clone()
+            },
+            TabListParent::FavoredClass,
+            Name::from("Favored Class TabListParent"),
+        ))
+        .set_parent(central_scroll_list);
+    // Equipment Tab display, filled in by equipment::setup_equipment_tab
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    ..list_parent.style.clone()
+                },
+                ..list_parent.clone()
+            },
+            TabListParent::Equipment,
+            Name::from("Equipment TabListParent"),
+        ))
+        .set_parent(central_scroll_list);
+    // Review Tab display, filled in by wizard::setup_review_tab
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    ..list_parent.style.clone()
+                },
+                ..list_parent.clone()
+            },
+            TabListParent::Review,
+            Name::from("Review TabListParent"),
+        ))
+        .set_parent(central_scroll_list);
 
     // Panel with chosen racial traits and favored class.
     // Should be located on the right of the screen
@@ -1063,6 +1160,16 @@ pub fn build_layout(mut commands: Commands, asset_server: Res<AssetServer>) {
             ));
         })
         .set_parent(bottom_container);
+    commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section("", bottom_button_text_style.clone()),
+                ..default()
+            },
+            StageStatusText,
+            Name::from("Stage status text"),
+        ))
+        .set_parent(bottom_container);
     commands
         .spawn((bottom_button.clone(), Name::from("Character Sheet Button")))
         .with_children(|previous_button| {
@@ -1090,6 +1197,57 @@ pub fn build_layout(mut commands: Commands, asset_server: Res<AssetServer>) {
             ));
         })
         .set_parent(bottom_container);
+    commands
+        .spawn((bottom_button.clone(), Name::from("Creation Mode Button")))
+        .with_children(|mode_button| {
+            mode_button.spawn((
+                TextBundle {
+                    text: Text::from_section("", bottom_button_text_style.clone()),
+                    ..default()
+                },
+                CreationModeButton,
+            ));
+        })
+        .set_parent(bottom_container);
+    commands
+        .spawn((bottom_button.clone(), Name::from("Randomize Button")))
+        .with_children(|randomize_button| {
+            randomize_button.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "Randomize".to_string(),
+                        bottom_button_text_style.clone(),
+                    ),
+                    ..default()
+                },
+                RandomizeButton,
+            ));
+        })
+        .set_parent(bottom_container);
+    commands
+        .spawn((bottom_button.clone(), Name::from("Random Lock Button")))
+        .with_children(|lock_button| {
+            lock_button.spawn((
+                TextBundle {
+                    text: Text::from_section("", bottom_button_text_style.clone()),
+                    ..default()
+                },
+                RandomLockButton,
+            ));
+        })
+        .set_parent(bottom_container);
+    commands
+        .spawn((bottom_button.clone(), Name::from("Score Method Button")))
+        .with_children(|method_button| {
+            method_button.spawn((
+                TextBundle {
+                    text: Text::from_section("", bottom_button_text_style.clone()),
+                    ..default()
+                },
+                ScoreMethodButton,
+            ));
+        })
+        .set_parent(bottom_container);
 }
 
 pub fn setup_class_table(commands: Commands /* query_parent: Query<Entity, With<ListNode>>*/) {
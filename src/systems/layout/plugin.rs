@@ -17,6 +17,7 @@ use crate::{
                 *,
             },
         },
+        character_sheet,
         mouse::mouse_scroll,
     },
     system_scheduling::states::AppState,
@@ -27,6 +28,7 @@ use crate::{
             },
             character::PlayableRace,
             class::{ClassFeature, PlayableClass},
+            dice::GameRng,
             race::{build_race, RaceBuilder, RacialTraitName},
             resources::{
                 archetype_resource,
@@ -38,8 +40,11 @@ use crate::{
     technical::{
         alternate_traits::AltTraitAsset,
         archetype::ArchetypeAsset,
+        character_traits::TraitCatalogAsset,
         class::ClassAsset,
         default_race_traits::DefaultTraitAsset,
+        deity::DeityAsset,
+        equipment::ShopCatalogAsset,
         favored_class::FavoredClassAsset,
         is_custom_asset_loaded::{is_custom_asset_loaded, CustomAssetLoadState},
         race_load::RaceAsset,
@@ -89,7 +94,20 @@ impl Plugin for CharacterCreationPlugin {
             .init_resource::<SelectedRace>()
             .init_resource::<SelectedClass>()
             .init_resource::<SelectedArchetype>()
+            .init_resource::<ChosenLanguages>()
+            .init_resource::<ChosenAlignment>()
+            .init_resource::<ChosenTraits>()
+            .init_resource::<ChosenFavoredClasses>()
+            .init_resource::<ChosenEquipment>()
+            .init_resource::<ChosenProficiencyFeats>()
+            .init_resource::<ShopFilter>()
+            .init_resource::<GameRng>()
             .init_resource::<SelectedTab>()
+            .init_resource::<CreationMode>()
+            .init_resource::<StageIssues>()
+            .init_resource::<WizardStep>()
+            .init_resource::<CharacterIssues>()
+            .init_resource::<Randomizer>()
             .init_resource::<SelectedSubTabsMap>()
             .init_resource::<FlavorTextSetup>()
             .init_resource::<CustomAssetLoadState<RaceAsset>>()
@@ -98,6 +116,9 @@ impl Plugin for CharacterCreationPlugin {
             .init_resource::<CustomAssetLoadState<AltTraitAsset>>()
             .init_resource::<CustomAssetLoadState<FavoredClassAsset>>()
             .init_resource::<CustomAssetLoadState<ArchetypeAsset>>()
+            .init_resource::<CustomAssetLoadState<DeityAsset>>()
+            .init_resource::<CustomAssetLoadState<TraitCatalogAsset>>()
+            .init_resource::<CustomAssetLoadState<ShopCatalogAsset>>()
             .init_resource::<RaceBuilder>()
             .init_resource::<BuiltLists>()
             .init_resource::<BuiltTabButtons>()
@@ -122,6 +143,13 @@ impl Plugin for CharacterCreationPlugin {
                     class_resource::setup_classes,
                     archetype_resource::setup_archetypes,
                     apply_deferred,
+                    languages::setup_language_tab,
+                    alignment::setup_alignment_tab,
+                    character_traits::setup_trait_tab,
+                    favored_class::setup_favored_class_tab,
+                    equipment::setup_equipment_tab,
+                    wizard::setup_review_tab,
+                    character_sheet::setup_character_sheet,
                     build_tab_buttons::build_tab_buttons::<CharacterTabs, Tab>(),
                     build_subtab_buttons::build_subtab_buttons::<
                         CharacterCreationSubTabs,
@@ -139,6 +167,9 @@ impl Plugin for CharacterCreationPlugin {
                     .run_if(is_custom_asset_loaded::<DefaultTraitAsset>())
                     .run_if(is_custom_asset_loaded::<AltTraitAsset>())
                     .run_if(is_custom_asset_loaded::<FavoredClassAsset>())
+                    .run_if(is_custom_asset_loaded::<DeityAsset>())
+                    .run_if(is_custom_asset_loaded::<TraitCatalogAsset>())
+                    .run_if(is_custom_asset_loaded::<ShopCatalogAsset>())
                     .run_if(in_state(AppState::CharacterCreation)),
             )
             .configure_sets(
@@ -154,9 +185,11 @@ impl Plugin for CharacterCreationPlugin {
                 (
                     Build::Super.run_if(resource_changed::<SelectedRace>()),
                     Build::PreBuild
+                        .after(EventSet::Receiving)
                         .before(Build::Build)
                         .run_if(resource_changed::<SelectedRace>()),
                     Build::Build.run_if(resource_changed::<RaceBuilder>()),
+                    Build::PostBuild.after(Build::Build),
                     Changed::Race.run_if(resource_changed::<SelectedRace>()),
                 )
                     .in_set(SuperSet::Super),
@@ -249,8 +282,11 @@ impl Plugin for CharacterCreationPlugin {
             .add_systems(
                 Update,
                 (
-                    select_tab::tab_button_select,
+                    select_tab::tab_button_select.run_if(resource_equals(CreationMode::Expert)),
+                    wizard::guided_tab_select.run_if(resource_equals(CreationMode::Guided)),
+                    wizard::previous_next_buttons,
                     select_tab::subtab_button_select,
+                    randomize::randomize,
                 )
                     .in_set(EventSet::Sending),
             )
@@ -299,6 +335,26 @@ impl Plugin for CharacterCreationPlugin {
                 (left_panel::button_color, left_panel::cleanup_buttons).in_set(SuperSet::Super),
             )
             .add_systems(Update, update_race_builder.in_set(Build::PreBuild))
+            // Random characters
+            .add_systems(
+                Update,
+                randomize::apply_random_race
+                    .after(Build::PreBuild)
+                    .before(Build::Build)
+                    .in_set(SuperSet::Super),
+            )
+            .add_systems(
+                Update,
+                (randomize::apply_random_character, apply_deferred)
+                    .chain()
+                    .in_set(Build::PostBuild),
+            )
+            .add_systems(
+                Update,
+                (randomize::randomize_options, randomize::display_randomizer)
+                    .chain()
+                    .in_set(SuperSet::Super),
+            )
             .add_systems(
                 Update,
                 (
@@ -324,6 +380,114 @@ impl Plugin for CharacterCreationPlugin {
                     .chain()
                     .in_set(Build::Build),
             )
+            // Languages Tab
+            .add_systems(
+                Update,
+                (
+                    languages::choose_language,
+                    languages::update_known_languages,
+                    apply_deferred,
+                    languages::display_languages,
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
+            // Alignment Tab
+            .add_systems(
+                Update,
+                (
+                    alignment::build_deity_buttons,
+                    alignment::choose_alignment,
+                    alignment::update_alignment,
+                    apply_deferred,
+                    alignment::display_alignment,
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
+            // Traits Tab
+            .add_systems(
+                Update,
+                (
+                    character_traits::build_trait_buttons,
+                    character_traits::choose_trait,
+                    character_traits::update_character_traits,
+                    apply_deferred,
+                    character_traits::display_traits,
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
+            // Favored Class Tab
+            .add_systems(
+                Update,
+                (
+                    favored_class::choose_favored_class,
+                    favored_class::update_favored_classes,
+                    apply_deferred,
+                    favored_class::build_favored_bonus_buttons,
+                    apply_deferred,
+                    favored_class::display_favored_classes,
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
+            // Equipment Tab
+            .add_systems(
+                Update,
+                (
+                    (
+                        equipment::build_shop_buttons,
+                        equipment::build_proficiency_feat_buttons,
+                    ),
+                    (
+                        equipment::choose_equipment,
+                        equipment::choose_proficiency_feat,
+                    ),
+                    equipment::update_proficiencies,
+                    equipment::update_equipment,
+                    apply_deferred,
+                    equipment::build_inventory_buttons,
+                    apply_deferred,
+                    (
+                        equipment::display_equipment,
+                        equipment::display_proficiency_feats,
+                    ),
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
+            // Character Sheet
+            .add_systems(
+                Update,
+                (
+                    character_sheet::toggle_character_sheet,
+                    character_sheet::display_character_sheet,
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
+            // Guided creation
+            .add_systems(
+                Update,
+                (
+                    wizard::validate_builder,
+                    wizard::update_stage_issues,
+                    wizard::toggle_creation_mode,
+                    wizard::display_wizard,
+                    wizard::display_tab_stages,
+                    wizard::display_review,
+                )
+                    .chain()
+                    .after(Build::PostBuild)
+                    .in_set(SuperSet::Super),
+            )
             .add_systems(Update, tooltip::display_on_hover.in_set(SuperSet::Super));
     }
 }
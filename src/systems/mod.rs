@@ -1,3 +1,4 @@
+pub mod battle;
 pub mod game;
 pub mod grid_systems;
 pub mod interface;
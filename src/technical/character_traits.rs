@@ -0,0 +1,50 @@
+//! Implements loader for a custom asset type.
+
+use crate::systems::game::character_traits::CharacterTrait;
+use bevy::reflect::TypePath;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+// A list of character traits, loaded from a `*.traits.ron` file. Every
+// catalog in the traits folder is offered during character creation.
+#[derive(Debug, Deserialize, TypeUuid, TypePath, Clone, Default)]
+#[uuid = "b6a2e1d4-2f7c-4e58-9d0a-61c3f8a4e927"]
+#[type_path = "crate::technical::character_traits::TraitCatalogAsset"]
+pub struct TraitCatalogAsset {
+    pub traits: Vec<CharacterTrait>,
+}
+
+#[derive(Default)]
+pub struct TraitCatalogAssetLoader;
+
+impl AssetLoader for TraitCatalogAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let catalog_asset = ron::de::from_bytes::<TraitCatalogAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(catalog_asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["traits.ron"]
+    }
+}
+
+pub struct MyTraitCatalogAssetPlugin;
+
+impl Plugin for MyTraitCatalogAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<TraitCatalogAsset>()
+            .init_asset_loader::<TraitCatalogAssetLoader>();
+    }
+}
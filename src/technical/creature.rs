@@ -0,0 +1,184 @@
+//! Implements loader for a custom asset type.
+
+use crate::systems::game::{
+    ai::BehaviorProfile,
+    character::{AbilityScore, AbilityScores, CreatureSubtype, CreatureType, SizeCategory},
+    class::{BaseAttack, BaseSaves},
+    creature::{ChallengeRating, CreatureAttack, HitDice, SpecialAbility},
+    defenses::{DamageReductionEntry, EnergyType},
+    magic::{CastFrequency, SlaSource, SpellLikeAbility, SpellName},
+};
+use bevy::reflect::TypePath;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+pub const CREATURE_FOLDER: &str = "text/descriptions/creatures";
+
+// A monster or NPC stat block, loaded from a `*.creature.ron` file.
+#[derive(Debug, Deserialize, TypeUuid, TypePath, Clone)]
+#[uuid = "6e297d33-e68d-42e9-b17d-360e31fa1716"]
+#[type_path = "crate::technical::creature::CreatureAsset"]
+pub struct CreatureAsset {
+    pub name: String,
+    pub creature_type: CreatureType,
+    #[serde(default)]
+    pub subtypes: Vec<CreatureSubtype>,
+    pub size: SizeCategory,
+    pub hit_dice: HitDice,
+    pub hit_points: i32,
+    pub ability_scores: AbilityScoresDescription,
+    pub speed: f32,
+    #[serde(default)]
+    pub senses: SensesDescription,
+    pub armor_class: ArmorClassDescription,
+    pub base_attack: usize,
+    pub base_saves: BaseSavesDescription,
+    pub attacks: Vec<CreatureAttack>,
+    #[serde(default)]
+    pub special_abilities: Vec<SpecialAbility>,
+    #[serde(default)]
+    pub spell_like_abilities: Vec<SpellLikeAbilityDescription>,
+    #[serde(default)]
+    pub damage_reduction: Vec<DamageReductionEntry>,
+    #[serde(default)]
+    pub spell_resistance: Option<i32>,
+    #[serde(default)]
+    pub energy_resistance: Vec<(EnergyType, i32)>,
+    // How the creature fights when nobody is controlling it.
+    #[serde(default)]
+    pub behavior: BehaviorProfile,
+    pub challenge_rating: ChallengeRating,
+    // Only needed when the XP differs from the usual award for the CR.
+    #[serde(default)]
+    pub experience: Option<u32>,
+    // The tile of the ascii sheet used for the token, and its color.
+    pub glyph: char,
+    pub color: (f32, f32, f32),
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct AbilityScoresDescription {
+    pub strength: i32,
+    pub dexterity: i32,
+    pub constitution: i32,
+    pub intelligence: i32,
+    pub wisdom: i32,
+    pub charisma: i32,
+}
+
+impl From<AbilityScoresDescription> for AbilityScores {
+    fn from(other: AbilityScoresDescription) -> Self {
+        let mut scores = AbilityScores::new();
+        scores.set(AbilityScore::Strength, other.strength);
+        scores.set(AbilityScore::Dexterity, other.dexterity);
+        scores.set(AbilityScore::Constitution, other.constitution);
+        scores.set(AbilityScore::Intelligence, other.intelligence);
+        scores.set(AbilityScore::Wisdom, other.wisdom);
+        scores.set(AbilityScore::Charisma, other.charisma);
+        scores
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+pub struct SensesDescription {
+    #[serde(default)]
+    pub low_light_vision: bool,
+    #[serde(default)]
+    pub darkvision: Option<f32>,
+}
+
+// The parts of the AC that aren't worked out from ability scores and size.
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct ArmorClassDescription {
+    pub armor: i32,
+    pub shield: i32,
+    pub natural: i32,
+    pub deflection: i32,
+    pub dodge: i32,
+}
+
+// A spell-like ability usable at will, or a number of times per day when
+// `uses` is set. The DC is based on Charisma.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct SpellLikeAbilityDescription {
+    pub spell: SpellName,
+    #[serde(default)]
+    pub uses: Option<u32>,
+}
+
+impl From<SpellLikeAbilityDescription> for SpellLikeAbility {
+    fn from(other: SpellLikeAbilityDescription) -> Self {
+        SpellLikeAbility {
+            source: SlaSource::StatBlock,
+            spell_name: other.spell,
+            cast_frequency: match other.uses {
+                Some(_) => CastFrequency::PerDay,
+                None => CastFrequency::AtWill,
+            },
+            uses: other.uses,
+            ability_score: AbilityScore::Charisma,
+            limitation: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct BaseSavesDescription {
+    pub fort: usize,
+    pub reflex: usize,
+    pub will: usize,
+}
+
+impl From<BaseSavesDescription> for BaseSaves {
+    fn from(other: BaseSavesDescription) -> Self {
+        BaseSaves {
+            fort: other.fort,
+            reflex: other.reflex,
+            will: other.will,
+        }
+    }
+}
+
+impl CreatureAsset {
+    pub fn base_attack(&self) -> BaseAttack {
+        BaseAttack {
+            base: self.base_attack,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CreatureAssetLoader;
+
+impl AssetLoader for CreatureAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let creature_asset = ron::de::from_bytes::<CreatureAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(creature_asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["creature.ron"]
+    }
+}
+
+pub struct MyCreatureAssetPlugin;
+
+impl Plugin for MyCreatureAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<CreatureAsset>()
+            .init_asset_loader::<CreatureAssetLoader>();
+    }
+}
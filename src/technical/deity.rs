@@ -0,0 +1,53 @@
+//! Implements loader for a custom asset type.
+
+use crate::systems::game::{character::Alignment, deity::Domain};
+use bevy::reflect::TypePath;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+// A god that divine casters can worship, loaded from a `*.deity.ron` file.
+#[derive(Debug, Deserialize, TypeUuid, TypePath, Clone, Default)]
+#[uuid = "3f0c6a0e-5d1b-4c0e-9a43-7b8e2d61c5f4"]
+#[type_path = "crate::technical::deity::DeityAsset"]
+pub struct DeityAsset {
+    pub name: String,
+    pub title: String,
+    pub alignment: Alignment,
+    // Domains the deity grants to its clerics.
+    pub domains: Vec<Domain>,
+}
+
+#[derive(Default)]
+pub struct DeityAssetLoader;
+
+impl AssetLoader for DeityAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let deity_asset = ron::de::from_bytes::<DeityAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(deity_asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["deity.ron"]
+    }
+}
+
+pub struct MyDeityAssetPlugin;
+
+impl Plugin for MyDeityAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<DeityAsset>()
+            .init_asset_loader::<DeityAssetLoader>();
+    }
+}
@@ -0,0 +1,79 @@
+//! Implements loader for a custom asset type.
+
+use crate::systems::{game::encounter::Difficulty, grid_systems::grid_position::GridPos};
+use bevy::reflect::TypePath;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+pub const ENCOUNTER_FOLDER: &str = "text/encounters";
+pub const DEFAULT_ENCOUNTER: &str = "text/encounters/goblin_ambush.encounter.ron";
+
+// A prepared fight, loaded from a `*.encounter.ron` file.
+#[derive(Debug, Serialize, Deserialize, TypeUuid, TypePath, Clone, Default)]
+#[uuid = "8d54fce0-fc0a-486a-a72e-a08f751e4746"]
+#[type_path = "crate::technical::encounter::EncounterAsset"]
+pub struct EncounterAsset {
+    pub name: String,
+    pub party_level: usize,
+    pub difficulty: Difficulty,
+    pub creatures: Vec<EncounterCreature>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct EncounterCreature {
+    // File name of the stat block in the creature folder.
+    pub stat_block: String,
+    pub grid_pos: GridPos,
+}
+
+impl EncounterAsset {
+    // Writes the encounter to the encounter folder, named after the encounter,
+    // and returns the path of the file relative to the assets folder.
+    pub fn save(&self) -> Result<String, Box<dyn Error>> {
+        let file_name = format!(
+            "{}.encounter.ron",
+            self.name.to_lowercase().replace(' ', "_")
+        );
+        let folder = format!("assets/{ENCOUNTER_FOLDER}");
+        std::fs::create_dir_all(&folder)?;
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(format!("{folder}/{file_name}"), ron)?;
+        Ok(format!("{ENCOUNTER_FOLDER}/{file_name}"))
+    }
+}
+
+#[derive(Default)]
+pub struct EncounterAssetLoader;
+
+impl AssetLoader for EncounterAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let encounter_asset = ron::de::from_bytes::<EncounterAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(encounter_asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["encounter.ron"]
+    }
+}
+
+pub struct MyEncounterAssetPlugin;
+
+impl Plugin for MyEncounterAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<EncounterAsset>()
+            .init_asset_loader::<EncounterAssetLoader>();
+    }
+}
@@ -0,0 +1,50 @@
+//! Implements loader for a custom asset type.
+
+use crate::systems::game::equipment::Item;
+use bevy::reflect::TypePath;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+// Items for sale, loaded from a `*.shop.ron` file. Every catalog in the
+// equipment folder is offered in the shop during character creation.
+#[derive(Debug, Deserialize, TypeUuid, TypePath, Clone, Default)]
+#[uuid = "4f1c9a7e-83d2-4b6a-a5e0-7d29c3b81f64"]
+#[type_path = "crate::technical::equipment::ShopCatalogAsset"]
+pub struct ShopCatalogAsset {
+    pub items: Vec<Item>,
+}
+
+#[derive(Default)]
+pub struct ShopCatalogAssetLoader;
+
+impl AssetLoader for ShopCatalogAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let catalog_asset = ron::de::from_bytes::<ShopCatalogAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(catalog_asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["shop.ron"]
+    }
+}
+
+pub struct MyShopCatalogAssetPlugin;
+
+impl Plugin for MyShopCatalogAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<ShopCatalogAsset>()
+            .init_asset_loader::<ShopCatalogAssetLoader>();
+    }
+}
@@ -3,12 +3,22 @@ use bevy::prelude::*;
 use bevy::asset::Asset;
 use bevy::asset::LoadState;
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct CustomAssetLoadState<T: Asset> {
     pub handles: Vec<Handle<T>>,
     loaded: bool,
 }
 
+// Written out so the asset type itself doesn't need a default.
+impl<T: Asset> Default for CustomAssetLoadState<T> {
+    fn default() -> Self {
+        Self {
+            handles: Vec::new(),
+            loaded: false,
+        }
+    }
+}
+
 impl<T: Asset> CustomAssetLoadState<T> {
     pub fn add_untyped(&mut self, handle: &HandleUntyped) {
         self.handles.push(handle.clone().typed())
@@ -1,7 +1,12 @@
 pub mod alternate_traits;
 pub mod archetype;
+pub mod character_traits;
 pub mod class;
+pub mod creature;
 pub mod default_race_traits;
+pub mod deity;
+pub mod encounter;
+pub mod equipment;
 pub mod favored_class;
 pub mod is_custom_asset_loaded;
 pub mod race_load;